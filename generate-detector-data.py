@@ -0,0 +1,261 @@
+#!/usr/bin/env python3
+
+# Copyright 2017 Mozilla Foundation. See the COPYRIGHT
+# file at the top-level directory of this distribution.
+#
+# Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+# https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+# <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+# option. This file may not be copied, modified, or distributed
+# except according to those terms.
+
+# Generates src/detector_data.rs, the character frequency tables that the
+# encoding detector scores decoded text with.
+#
+# The corpus is the translations in gettext message catalogs (.mo files).
+# Pass one or more directories to search recursively for
+# <language>/LC_MESSAGES/*.mo, e.g. /usr/share/locale of a Linux
+# distribution with a wide selection of packages and translations installed:
+#
+#   ./generate-detector-data.py /usr/share/locale
+
+import codecs
+import hashlib
+import math
+import os
+import struct
+import sys
+import unicodedata
+
+# The languages of each family and the legacy encoding whose repertoire the
+# text is mapped to before counting, i.e. what decoding text in the family
+# from a legacy encoding produces.
+FAMILIES = [
+  ("WESTERN", "cp1252",
+   ["ca", "da", "de", "es", "fi", "fr", "gl", "is", "it", "nb", "nl", "nn", "pt", "pt_BR", "sv"]),
+  ("CENTRAL", "cp1250", ["bs", "cs", "hr", "hu", "pl", "ro", "sk", "sl", "sr@latin"]),
+  ("BALTIC", "cp1257", ["et", "lt", "lv"]),
+  ("TURKISH", "cp1254", ["tr"]),
+  ("VIETNAMESE", "cp1258", ["vi"]),
+  ("CYRILLIC", "cp1251", ["be", "bg", "mk", "ru", "sr", "uk"]),
+  ("GREEK", "cp1253", ["el"]),
+  ("HEBREW", "cp1255", ["he"]),
+  ("ARABIC", "cp1256", ["ar", "fa"]),
+  ("THAI", "cp874", ["th"]),
+  ("JAPANESE", "cp932", ["ja"]),
+  ("SIMPLIFIED_CHINESE", "gbk", ["zh_CN"]),
+  ("TRADITIONAL_CHINESE", "big5", ["zh_TW", "zh_HK"]),
+  ("KOREAN", "cp949", ["ko"]),
+]
+
+# Substitutions for characters that the legacy encodings of a family lack but
+# that legacy content has a look-alike for.
+LOOK_ALIKES = {
+  u"Ș": u"Ş", # S with comma below -> S with cedilla
+  u"ș": u"ş",
+  u"Ț": u"Ţ", # T with comma below -> T with cedilla
+  u"ț": u"ţ",
+}
+
+# Languages with fewer counted characters than this are left out.
+MIN_COUNTED = 1000
+
+# A listed character has weight floor(log2(frequency) + SCALE) + 1 capped at
+# MAX_WEIGHT, where the frequency is relative to the counted characters of
+# the family. Characters whose weight would be less than one aren't listed.
+ALPHABETIC_SCALE = 10
+CJK_SCALE = 17
+MAX_WEIGHT = 8
+
+def is_cjk(family):
+  return family in ("JAPANESE", "SIMPLIFIED_CHINESE", "TRADITIONAL_CHINESE", "KOREAN")
+
+def read_mo(path):
+  with open(path, "rb") as f:
+    data = f.read()
+  if len(data) < 20:
+    return []
+  magic = struct.unpack("<I", data[:4])[0]
+  if magic == 0x950412de:
+    endian = "<"
+  elif magic == 0xde120495:
+    endian = ">"
+  else:
+    return []
+  (count, originals, translations) = struct.unpack(endian + "III", data[8:20])
+  entries = []
+  charset = "utf-8"
+  for i in range(count):
+    (orig_len, orig_offset) = struct.unpack(endian + "II", data[originals + 8 * i:originals + 8 * i + 8])
+    (length, offset) = struct.unpack(endian + "II", data[translations + 8 * i:translations + 8 * i + 8])
+    translation = data[offset:offset + length]
+    if orig_len == 0:
+      # The header entry
+      for line in translation.split(b"\n"):
+        if line.lower().startswith(b"content-type:") and b"charset=" in line:
+          charset = line.split(b"charset=")[1].strip().decode("ascii", "replace")
+      continue
+    entries.append(translation)
+  try:
+    codecs.lookup(charset)
+  except LookupError:
+    charset = "utf-8"
+  texts = []
+  for entry in entries:
+    for form in entry.split(b"\0"):
+      texts.append(form.decode(charset, "replace"))
+  return texts
+
+def find_catalogs(roots):
+  catalogs = {}
+  seen = set()
+  for root in roots:
+    for (directory, _, files) in os.walk(root):
+      if os.path.basename(directory) != "LC_MESSAGES":
+        continue
+      language = os.path.basename(os.path.dirname(directory))
+      for name in sorted(files):
+        if not name.endswith(".mo"):
+          continue
+        path = os.path.join(directory, name)
+        with open(path, "rb") as f:
+          digest = hashlib.sha1(f.read()).hexdigest()
+        if digest in seen:
+          continue
+        seen.add(digest)
+        catalogs.setdefault(language, []).append(path)
+  return catalogs
+
+def encodable(c, encoding):
+  try:
+    c.encode(encoding)
+    return True
+  except UnicodeEncodeError:
+    return False
+
+def to_legacy(c, encoding):
+  """Returns what c looks like in content in encoding (possibly several
+  characters) or an empty string if encoding can't represent it."""
+  if encodable(c, encoding):
+    return c
+  if c in LOOK_ALIKES and encodable(LOOK_ALIKES[c], encoding):
+    return LOOK_ALIKES[c]
+  # windows-1258 represents some letters as a precomposed letter and a
+  # combining mark.
+  decomposed = unicodedata.normalize("NFD", c)
+  if len(decomposed) < 2:
+    return u""
+  base = unicodedata.normalize("NFC", decomposed[:2])
+  if len(base) == 1 and encodable(base, encoding):
+    rest = decomposed[2:]
+  else:
+    base = decomposed[0]
+    rest = decomposed[1:]
+  result = base + rest
+  if all(encodable(x, encoding) for x in result):
+    return result
+  return u""
+
+def lower(c):
+  l = c.lower()
+  if len(l) != 1:
+    return c
+  return l
+
+def is_counted(c, family):
+  if ord(c) < 0x80 or ord(c) > 0xFFFF:
+    return False
+  category = unicodedata.category(c)
+  if is_cjk(family):
+    return category == "Lo" or category == "Lm"
+  return category[0] == "L" or category[0] == "M"
+
+def count_language(paths, family, encoding):
+  strings = set()
+  for path in paths:
+    strings.update(read_mo(path))
+  counts = {}
+  cache = {}
+  for string in strings:
+    for c in string:
+      if ord(c) < 0x80:
+        continue
+      if c not in cache:
+        cache[c] = [lower(x) for x in to_legacy(c, encoding) if is_counted(x, family)]
+      for x in cache[c]:
+        counts[x] = counts.get(x, 0) + 1
+  return counts
+
+def family_frequencies(catalogs, family, encoding, languages):
+  frequencies = {}
+  used = []
+  for language in languages:
+    if language not in catalogs:
+      continue
+    counts = count_language(catalogs[language], family, encoding)
+    total = sum(counts.values())
+    if total < MIN_COUNTED:
+      continue
+    used.append(language)
+    for (c, count) in counts.items():
+      frequencies[c] = frequencies.get(c, 0.0) + float(count) / total
+  if not used:
+    sys.stderr.write("No corpus for %s\n" % family)
+    sys.exit(1)
+  for c in frequencies:
+    frequencies[c] /= len(used)
+  sys.stderr.write("%s: %s\n" % (family, " ".join(used)))
+  return frequencies
+
+def weights(frequencies, scale):
+  table = []
+  for (c, frequency) in frequencies.items():
+    weight = int(math.floor(math.log(frequency, 2) + scale)) + 1
+    if weight < 1:
+      continue
+    table.append((ord(c), min(weight, MAX_WEIGHT)))
+  table.sort()
+  return table
+
+def write_array(out, name, item_type, items, per_line, formatter):
+  out.write("pub static %s: [%s; %d] =\n    [" % (name, item_type, len(items)))
+  for i in range(len(items)):
+    if i != 0:
+      if i % per_line == 0:
+        out.write(",\n     ")
+      else:
+        out.write(", ")
+    out.write(formatter(items[i]))
+  out.write("];\n\n")
+
+catalogs = find_catalogs(sys.argv[1:])
+
+out = open("src/detector_data.rs", "w")
+out.write('''// Copyright 2017 Mozilla Foundation. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// THIS IS A GENERATED FILE. PLEASE DO NOT EDIT.
+// Instead, please regenerate using generate-detector-data.py
+
+// For each family of languages, the characters (lower-cased where possible)
+// whose frequency in the family is high enough to be listed, sorted by code
+// point, and their weights, which are the binary logarithms of their
+// frequencies (plus a constant and capped).
+
+''')
+
+for (family, encoding, languages) in FAMILIES:
+  frequencies = family_frequencies(catalogs, family, encoding, languages)
+  table = weights(frequencies, CJK_SCALE if is_cjk(family) else ALPHABETIC_SCALE)
+  write_array(out, "%s_CHARS" % family, "u16", [c for (c, _) in table], 11,
+              lambda c: "0x%04X" % c)
+  write_array(out, "%s_WEIGHTS" % family, "u8", [w for (_, w) in table], 24,
+              lambda w: "%d" % w)
+
+out.close()
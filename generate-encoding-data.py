@@ -1368,6 +1368,49 @@ pub fn mul_94(lead: u8) -> usize {
 }
 ''')
 
+# Fallbacks
+
+def read_fallbacks(path):
+  fallbacks = []
+  for line in open(path, "r"):
+    line = line.strip()
+    if not line or line.startswith("#"):
+      continue
+    (key, value) = line.split("=", 1)
+    fallbacks.append((key, value))
+  fallbacks.sort()
+  return fallbacks
+
+def fallback_initializer(name):
+  return "&super::%s_INIT" % to_constant_name(name)
+
+locale_fallbacks = read_fallbacks("fallbacks/locales.properties")
+
+data_file.write('''pub static LOCALE_FALLBACKS: [(&'static str, &'static super::Encoding); %d] = [
+''' % len(locale_fallbacks))
+
+for (locale, name) in locale_fallbacks:
+  data_file.write('''("%s", %s),\n''' % (locale, fallback_initializer(name)))
+
+data_file.write('''];
+
+''')
+
+tld_fallbacks = read_fallbacks("fallbacks/domains.properties")
+
+data_file.write('''pub static TLD_FALLBACKS: [(&'static str, Option<&'static super::Encoding>); %d] = [
+''' % len(tld_fallbacks))
+
+for (tld, name) in tld_fallbacks:
+  if name:
+    data_file.write('''("%s", Some(%s)),\n''' % (tld, fallback_initializer(name)))
+  else:
+    data_file.write('''("%s", None),\n''' % tld)
+
+data_file.write('''];
+
+''')
+
 data_file.close()
 
 # Variant
@@ -1430,6 +1473,37 @@ for variant in decoder_variants:
 variant_file.write('''}
 
 impl VariantDecoder {
+    pub fn has_pending_state(&self) -> bool {
+        match *self {
+''')
+
+for variant in decoder_variants:
+  if variant in [u"single-byte", u"replacement", u"x-user-defined"]:
+    continue
+  variant_file.write("VariantDecoder::%s(ref v) => v.has_pending_state(),\n" % to_camel_name(variant))
+
+variant_file.write('''            _ => false,
+        }
+    }
+    pub fn snapshot_state(&self, state: &mut Vec<u8>) -> bool {
+        match *self {
+''')
+
+for variant in decoder_variants:
+  if variant in [u"single-byte", u"x-user-defined"]:
+    continue
+  variant_file.write("VariantDecoder::%s(ref v) => v.snapshot_state(state),\n" % to_camel_name(variant))
+
+variant_file.write('''            _ => true,
+        }
+    }
+    pub fn restore_state<'a>(&mut self, state: &'a [u8]) -> Option<&'a [u8]> {
+        match *self {
+            VariantDecoder::Iso2022Jp(ref mut v) => v.restore_state(state),
+            VariantDecoder::Replacement(ref mut v) => v.restore_state(state),
+            _ => Some(state),
+        }
+    }
 ''')
 
 def write_variant_method(name, mut, arg_list, ret, variants, excludes, kind):
@@ -1494,6 +1568,17 @@ impl VariantEncoder {
             _ => false,
         }
     }
+    pub fn snapshot_state(&self, state: &mut Vec<u8>) {
+        if let VariantEncoder::Iso2022Jp(ref v) = *self {
+            v.snapshot_state(state);
+        }
+    }
+    pub fn restore_state(&mut self, state: &[u8]) -> bool {
+        match *self {
+            VariantEncoder::Iso2022Jp(ref mut v) => v.restore_state(state),
+            _ => state.is_empty(),
+        }
+    }
 ''')
 
 write_variant_method("max_buffer_length_from_utf16_without_replacement", False, [("u16_length", "usize")], "Option<usize>", encoder_variants, [], "Encoder")
@@ -148,7 +148,7 @@ macro_rules! basic_latin_alu {
                 //                   }
                 //                   (ALIGNMENT - src_alignment) & ALIGNMENT_MASK
                 //               } else
-                if ::std::mem::size_of::<$src_unit>() < ::std::mem::size_of::<$dst_unit>() {
+                if ::core::mem::size_of::<$src_unit>() < ::core::mem::size_of::<$dst_unit>() {
                     // ascii_to_basic_latin
                     let src_until_alignment = (ALIGNMENT - ((src as usize) & ALIGNMENT_MASK)) & ALIGNMENT_MASK;
                     if (dst.offset(src_until_alignment as isize) as usize) & ALIGNMENT_MASK != 0 {
@@ -340,6 +340,86 @@ macro_rules! basic_latin_to_ascii_simd_stride {
     });
 }
 
+macro_rules! validate_utf16_simd {
+    ($name:ident,
+     $is_below:ident,
+     $limit:expr) => (
+    #[inline(always)]
+    pub fn $name(slice: &[u16]) -> Option<(u16, usize)> {
+        let src = slice.as_ptr();
+        let len = slice.len();
+        let mut offset = 0usize;
+        // A stride is a register's worth of code units.
+        if 8 <= len {
+            loop {
+                let simd = unsafe { load8_unaligned(src.offset(offset as isize)) };
+                if !$is_below(simd) {
+                    break;
+                }
+                offset += 8;
+                if offset + 8 > len {
+                    break;
+                }
+            }
+        }
+        while offset < len {
+            let code_unit = slice[offset];
+            if code_unit > $limit {
+                return Some((code_unit, offset));
+            }
+            offset += 1;
+        }
+        None
+    });
+}
+
+macro_rules! validate_utf16_alu {
+    ($name:ident,
+     $mask:ident,
+     $limit:expr) => (
+    #[inline(always)]
+    pub fn $name(slice: &[u16]) -> Option<(u16, usize)> {
+        let src = slice.as_ptr();
+        let len = slice.len();
+        let mut offset = 0usize;
+        // A stride is two words, i.e. `ALIGNMENT` code units. The pointer
+        // is two-byte-aligned, so the distance to word alignment in bytes
+        // is even.
+        let mut until_alignment = ((ALIGNMENT - ((src as usize) & ALIGNMENT_MASK)) &
+                                   ALIGNMENT_MASK) >> 1;
+        if until_alignment + ALIGNMENT <= len {
+            while until_alignment != 0 {
+                let code_unit = slice[offset];
+                if code_unit > $limit {
+                    return Some((code_unit, offset));
+                }
+                offset += 1;
+                until_alignment -= 1;
+            }
+            loop {
+                let ptr = unsafe { src.offset(offset as isize) as *const usize };
+                let (word, second_word) = unsafe { (*ptr, *(ptr.offset(1))) };
+                if ((word | second_word) & $mask) != 0 {
+                    // Let the loop below find the code unit.
+                    break;
+                }
+                offset += ALIGNMENT;
+                if offset + ALIGNMENT > len {
+                    break;
+                }
+            }
+        }
+        while offset < len {
+            let code_unit = slice[offset];
+            if code_unit > $limit {
+                return Some((code_unit, offset));
+            }
+            offset += 1;
+        }
+        None
+    });
+}
+
 cfg_if! {
     if #[cfg(all(feature = "simd-accel", target_feature = "sse2"))] {
         // SIMD
@@ -694,10 +774,14 @@ cfg_if! {
             }
             None
         }
+
+        validate_utf16_simd!(validate_basic_latin, is_basic_latin, 0x7F);
+        validate_utf16_simd!(validate_utf16_latin1, is_utf16_latin1, 0xFF);
     } else {
         // `as` truncates, so works on 32-bit, too.
         const ASCII_MASK: usize = 0x80808080_80808080u64 as usize;
         const BASIC_LATIN_MASK: usize = 0xFF80FF80_FF80FF80u64 as usize;
+        const LATIN1_MASK: usize = 0xFF00FF00_FF00FF00u64 as usize;
 
         #[inline(always)]
         unsafe fn ascii_to_ascii_stride(src: *const usize, dst: *mut usize) -> Option<usize> {
@@ -779,6 +863,8 @@ cfg_if! {
            None
         }
 
+        validate_utf16_alu!(validate_basic_latin, BASIC_LATIN_MASK, 0x7F);
+        validate_utf16_alu!(validate_utf16_latin1, LATIN1_MASK, 0xFF);
     }
 }
 
@@ -844,4 +930,21 @@ mod tests {
     test_ascii!(test_ascii_to_ascii, ascii_to_ascii, u8, u8);
     test_ascii!(test_ascii_to_basic_latin, ascii_to_basic_latin, u8, u16);
     test_ascii!(test_basic_latin_to_ascii, basic_latin_to_ascii, u16, u8);
+
+    #[test]
+    fn test_validate_utf16() {
+        let mut src: Vec<u16> = Vec::with_capacity(40);
+        for i in 0..40 {
+            src.clear();
+            src.resize(40, 0x41);
+            src[i] = 0xE9;
+            assert_eq!(validate_basic_latin(&src[..]), Some((0xE9, i)));
+            assert_eq!(validate_utf16_latin1(&src[..]), None);
+            src[i] = 0x100;
+            assert_eq!(validate_utf16_latin1(&src[..]), Some((0x100, i)));
+            // Misaligned
+            assert_eq!(validate_utf16_latin1(&src[1..]),
+                       if i == 0 { None } else { Some((0x100, i - 1)) });
+        }
+    }
 }
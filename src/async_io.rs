@@ -0,0 +1,556 @@
+// Copyright 2017 Mozilla Foundation. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! This module provides adapters that connect the streaming API to the
+//! `futures-io` and `futures-core` traits. It is compiled only when the
+//! `async-io` feature is enabled.
+
+use std::io;
+use std::pin::Pin;
+use std::task::Context;
+use std::task::Poll;
+
+use futures_core::Stream;
+use futures_io::AsyncBufRead;
+use futures_io::AsyncRead;
+use futures_io::AsyncWrite;
+
+use super::*;
+use io::DecodeState;
+use io::EncodeState;
+use io::DEFAULT_BUF_SIZE;
+
+/// The minimum number of bytes to reserve when a `DecodeStream` gets
+/// `OutputFull`.
+const MIN_RESERVE: usize = 16;
+
+/// An `AsyncRead` and `AsyncBufRead` adapter that decodes a byte stream in
+/// any encoding from an inner asynchronous reader into UTF-8.
+///
+/// This is the asynchronous counterpart of `DecodeReader` and has the same
+/// guarantees: the output taken as a whole is valid UTF-8, chunk boundaries
+/// of the inner reader may fall anywhere and a truncated sequence at EOF
+/// turns into a REPLACEMENT CHARACTER.
+///
+/// Available to Rust only with the `async-io` feature.
+pub struct AsyncDecodeReader<R> {
+    inner: R,
+    state: DecodeState,
+}
+
+impl<R: AsyncRead + Unpin> AsyncDecodeReader<R> {
+    /// Wraps `inner` with a decoder for `encoding` with BOM sniffing enabled.
+    pub fn new(inner: R, encoding: &'static Encoding) -> AsyncDecodeReader<R> {
+        AsyncDecodeReader::with_decoder(inner, encoding.new_decoder())
+    }
+
+    /// Wraps `inner` with a caller-provided decoder.
+    pub fn with_decoder(inner: R, decoder: Decoder) -> AsyncDecodeReader<R> {
+        AsyncDecodeReader::with_decoder_and_capacity(inner, decoder, DEFAULT_BUF_SIZE)
+    }
+
+    /// Wraps `inner` with a caller-provided decoder using internal buffers of
+    /// `capacity` bytes each.
+    pub fn with_decoder_and_capacity(inner: R,
+                                     decoder: Decoder,
+                                     capacity: usize)
+                                     -> AsyncDecodeReader<R> {
+        AsyncDecodeReader {
+            inner,
+            state: DecodeState::new(decoder, capacity),
+        }
+    }
+
+    /// The `Encoding` of the underlying decoder.
+    ///
+    /// BOM sniffing can change the return value of this method during the life
+    /// of the reader.
+    pub fn encoding(&self) -> &'static Encoding {
+        self.state.encoding()
+    }
+
+    /// Returns `true` if malformed sequences have been replaced with the
+    /// REPLACEMENT CHARACTER so far.
+    pub fn had_errors(&self) -> bool {
+        self.state.had_errors()
+    }
+
+    /// Gets a reference to the inner reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the inner reader.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Unwraps this adapter, returning the inner reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for AsyncDecodeReader<R> {
+    fn poll_read(mut self: Pin<&mut Self>,
+                 cx: &mut Context,
+                 buf: &mut [u8])
+                 -> Poll<io::Result<usize>> {
+        let n = {
+            let available = match self.as_mut().poll_fill_buf(cx) {
+                Poll::Ready(Ok(available)) => available,
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                Poll::Pending => return Poll::Pending,
+            };
+            let n = ::std::cmp::min(available.len(), buf.len());
+            buf[..n].copy_from_slice(&available[..n]);
+            n
+        };
+        self.consume(n);
+        Poll::Ready(Ok(n))
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncBufRead for AsyncDecodeReader<R> {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context) -> Poll<io::Result<&[u8]>> {
+        let this = self.get_mut();
+        while this.state.needs_decode() {
+            if this.state.needs_input() {
+                match Pin::new(&mut this.inner).poll_read(cx, this.state.input_space()) {
+                    Poll::Ready(Ok(n)) => this.state.input_filled(n),
+                    Poll::Ready(Err(ref e)) if e.kind() == io::ErrorKind::Interrupted => continue,
+                    Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                    Poll::Pending => return Poll::Pending,
+                }
+            }
+            this.state.decode();
+        }
+        Poll::Ready(Ok(this.state.output()))
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        self.get_mut().state.consume(amt);
+    }
+}
+
+/// An `AsyncWrite` adapter that encodes UTF-8 written to it into a legacy
+/// encoding and writes the result to an inner asynchronous writer.
+///
+/// This is the asynchronous counterpart of `EncodeWriter` with the same input
+/// requirements. Unlike `EncodeWriter`, this adapter cannot block until the
+/// inner writer has taken all the output of a write, so it keeps encoded
+/// bytes in an internal buffer like `BufWriter` does. `poll_write()` drains
+/// that buffer before encoding more, `poll_flush()` drains it and flushes the
+/// inner writer and `poll_close()` ends the stream (reporting a trailing
+/// partial UTF-8 sequence as an error of the kind `InvalidData` and returning
+/// ISO-2022-JP to the ASCII state) before closing the inner writer.
+///
+/// `poll_close()` must be called at the end of the stream.
+///
+/// Available to Rust only with the `async-io` feature.
+pub struct AsyncEncodeWriter<W> {
+    inner: W,
+    state: EncodeState,
+    finished: bool,
+}
+
+impl<W: AsyncWrite + Unpin> AsyncEncodeWriter<W> {
+    /// Wraps `inner` with an encoder for the _output encoding_ of `encoding`
+    /// that replaces unmappable characters with HTML decimal numeric
+    /// character references.
+    pub fn new(inner: W, encoding: &'static Encoding) -> AsyncEncodeWriter<W> {
+        AsyncEncodeWriter {
+            inner,
+            state: EncodeState::new(encoding.new_encoder(), true),
+            finished: false,
+        }
+    }
+
+    /// Wraps `inner` with an encoder for the _output encoding_ of `encoding`
+    /// that fails with an error of the kind `InvalidData` wrapping an
+    /// `UnmappableError` when it encounters an unmappable character.
+    ///
+    /// The `poll_write()` call that reaches the unmappable character
+    /// consumes the input up to and including it and the error is returned
+    /// by the next `poll_write()` or `poll_close()`.
+    pub fn new_without_replacement(inner: W, encoding: &'static Encoding) -> AsyncEncodeWriter<W> {
+        AsyncEncodeWriter {
+            inner,
+            state: EncodeState::new(encoding.new_encoder(), false),
+            finished: false,
+        }
+    }
+
+    /// The `Encoding` of the underlying encoder.
+    pub fn encoding(&self) -> &'static Encoding {
+        self.state.encoding()
+    }
+
+    /// Returns `true` if unmappable characters have been replaced with
+    /// numeric character references so far.
+    pub fn had_unmappables(&self) -> bool {
+        self.state.had_unmappables()
+    }
+
+    /// Gets a reference to the inner writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the inner writer.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Unwraps this adapter, returning the inner writer.
+    ///
+    /// Output that has not been flushed is lost.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    fn poll_drain(&mut self, cx: &mut Context) -> Poll<io::Result<()>> {
+        while !self.state.output().is_empty() {
+            match Pin::new(&mut self.inner).poll_write(cx, self.state.output()) {
+                Poll::Ready(Ok(0)) => {
+                    return Poll::Ready(
+                        Err(io::Error::new(io::ErrorKind::WriteZero, "failed to write encoded output"))
+                    );
+                }
+                Poll::Ready(Ok(n)) => self.state.consume(n),
+                Poll::Ready(Err(ref e)) if e.kind() == io::ErrorKind::Interrupted => {}
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<W: AsyncWrite + Unpin> AsyncWrite for AsyncEncodeWriter<W> {
+    fn poll_write(self: Pin<&mut Self>,
+                  cx: &mut Context,
+                  buf: &[u8])
+                  -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        match this.poll_drain(cx) {
+            Poll::Ready(Ok(())) => {}
+            Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+            Poll::Pending => return Poll::Pending,
+        }
+        Poll::Ready(this.state.encode(buf))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        match this.poll_drain(cx) {
+            Poll::Ready(Ok(())) => {}
+            Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+            Poll::Pending => return Poll::Pending,
+        }
+        Pin::new(&mut this.inner).poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        loop {
+            match this.poll_drain(cx) {
+                Poll::Ready(Ok(())) => {}
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                Poll::Pending => return Poll::Pending,
+            }
+            if this.finished {
+                break;
+            }
+            this.finished = this.state.finish()?;
+        }
+        Pin::new(&mut this.inner).poll_close(cx)
+    }
+}
+
+/// A `Stream` adapter that decodes a stream of byte chunks in any encoding
+/// into a stream of `String`s.
+///
+/// The chunks can be anything that is `AsRef<[u8]>`, such as `Vec<u8>` or
+/// `bytes::Bytes`. A multi-byte sequence split across chunks is emitted as
+/// part of the `String` for the chunk that completes it, so no yielded
+/// `String` ends with a partial character. Chunks that produce no output are
+/// not yielded as empty strings. When the inner stream ends, the decoder is
+/// finished, so a truncated sequence at the end turns into a REPLACEMENT
+/// CHARACTER in a final `String`.
+///
+/// Available to Rust only with the `async-io` feature.
+pub struct DecodeStream<S> {
+    inner: S,
+    decoder: Decoder,
+    finished: bool,
+    had_errors: bool,
+}
+
+impl<S> DecodeStream<S> {
+    /// Wraps `inner` with a decoder for `encoding` with BOM sniffing enabled.
+    pub fn new(inner: S, encoding: &'static Encoding) -> DecodeStream<S> {
+        DecodeStream::with_decoder(inner, encoding.new_decoder())
+    }
+
+    /// Wraps `inner` with a caller-provided decoder.
+    pub fn with_decoder(inner: S, decoder: Decoder) -> DecodeStream<S> {
+        DecodeStream {
+            inner,
+            decoder,
+            finished: false,
+            had_errors: false,
+        }
+    }
+
+    /// The `Encoding` of the underlying decoder.
+    pub fn encoding(&self) -> &'static Encoding {
+        self.decoder.encoding()
+    }
+
+    /// Returns `true` if malformed sequences have been replaced with the
+    /// REPLACEMENT CHARACTER so far.
+    pub fn had_errors(&self) -> bool {
+        self.had_errors
+    }
+
+    /// Unwraps this adapter, returning the inner stream.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    fn decode(&mut self, src: &[u8], last: bool) -> String {
+        let mut string = String::new();
+        let mut total_read = 0usize;
+        loop {
+            // Decoders with a `MalformedPolicy` may need more than the
+            // worst case for the REPLACEMENT CHARACTER, so keep reserving.
+            let needed = self.decoder
+                             .max_utf8_buffer_length(src.len() - total_read)
+                             .expect("chunk too long");
+            string.reserve(::std::cmp::max(needed, MIN_RESERVE));
+            let (result, read, had_errors) =
+                self.decoder
+                    .decode_to_string(&src[total_read..], &mut string, last);
+            total_read += read;
+            self.had_errors |= had_errors;
+            match result {
+                CoderResult::InputEmpty => {
+                    debug_assert_eq!(total_read, src.len());
+                    return string;
+                }
+                CoderResult::OutputFull => {}
+            }
+        }
+    }
+}
+
+impl<S, B> Stream for DecodeStream<S>
+    where S: Stream<Item = B> + Unpin,
+          B: AsRef<[u8]>
+{
+    type Item = String;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<String>> {
+        let this = self.get_mut();
+        while !this.finished {
+            let string = match Pin::new(&mut this.inner).poll_next(cx) {
+                Poll::Ready(Some(chunk)) => this.decode(chunk.as_ref(), false),
+                Poll::Ready(None) => {
+                    this.finished = true;
+                    this.decode(b"", true)
+                }
+                Poll::Pending => return Poll::Pending,
+            };
+            if !string.is_empty() {
+                return Poll::Ready(Some(string));
+            }
+        }
+        Poll::Ready(None)
+    }
+}
+
+// Any copyright to the test code below this comment is dedicated to the
+// Public Domain. http://creativecommons.org/publicdomain/zero/1.0/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+    use futures::io::AsyncReadExt;
+    use futures::io::AsyncWriteExt;
+    use futures::stream;
+    use futures::stream::StreamExt;
+
+    /// A reader that returns at most `chunk` bytes per read and is
+    /// `Pending` before every other read.
+    struct Trickle<'a> {
+        bytes: &'a [u8],
+        chunk: usize,
+        ready: bool,
+    }
+
+    impl<'a> AsyncRead for Trickle<'a> {
+        fn poll_read(mut self: Pin<&mut Self>,
+                     cx: &mut Context,
+                     buf: &mut [u8])
+                     -> Poll<io::Result<usize>> {
+            if !self.ready {
+                self.ready = true;
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+            self.ready = false;
+            let n = ::std::cmp::min(::std::cmp::min(self.chunk, buf.len()), self.bytes.len());
+            buf[..n].copy_from_slice(&self.bytes[..n]);
+            self.bytes = &self.bytes[n..];
+            Poll::Ready(Ok(n))
+        }
+    }
+
+    /// A writer that accepts at most `chunk` bytes per write and is
+    /// `Pending` before every other write.
+    struct Sink {
+        bytes: Vec<u8>,
+        chunk: usize,
+        ready: bool,
+        closed: bool,
+    }
+
+    impl AsyncWrite for Sink {
+        fn poll_write(mut self: Pin<&mut Self>,
+                      cx: &mut Context,
+                      buf: &[u8])
+                      -> Poll<io::Result<usize>> {
+            assert!(!self.closed);
+            if !self.ready {
+                self.ready = true;
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+            self.ready = false;
+            let n = ::std::cmp::min(self.chunk, buf.len());
+            self.bytes.extend_from_slice(&buf[..n]);
+            Poll::Ready(Ok(n))
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _: &mut Context) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_close(mut self: Pin<&mut Self>, _: &mut Context) -> Poll<io::Result<()>> {
+            self.closed = true;
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    fn decode_all(encoding: &'static Encoding, bytes: &[u8], chunk: usize, capacity: usize) -> String {
+        let mut reader = AsyncDecodeReader::with_decoder_and_capacity(
+            Trickle {
+                bytes,
+                chunk,
+                ready: false,
+            },
+            encoding.new_decoder(),
+            capacity,
+        );
+        let mut string = String::new();
+        block_on(reader.read_to_string(&mut string)).unwrap();
+        string
+    }
+
+    fn encode_all(encoding: &'static Encoding, bytes: &[u8], chunk: usize) -> Vec<u8> {
+        let mut writer = AsyncEncodeWriter::new(
+            Sink {
+                bytes: Vec::new(),
+                chunk,
+                ready: false,
+                closed: false,
+            },
+            encoding,
+        );
+        for piece in bytes.chunks(chunk) {
+            block_on(writer.write_all(piece)).unwrap();
+        }
+        block_on(writer.close()).unwrap();
+        let sink = writer.into_inner();
+        assert!(sink.closed);
+        sink.bytes
+    }
+
+    #[test]
+    fn test_async_decode_reader_shift_jis() {
+        let bytes = b"\x83n\x83\x8D\x81[\x81E\x83\x8F\x81[\x83\x8B\x83h";
+        let expectation = "\u{30CF}\u{30ED}\u{30FC}\u{30FB}\u{30EF}\u{30FC}\u{30EB}\u{30C9}";
+        for chunk in 1..bytes.len() + 1 {
+            for capacity in 1..8 {
+                assert_eq!(decode_all(SHIFT_JIS, bytes, chunk, capacity), expectation);
+            }
+        }
+    }
+
+    #[test]
+    fn test_async_decode_reader_truncated_at_eof() {
+        for chunk in 1..4 {
+            assert_eq!(decode_all(UTF_8, b"\xEF\xBB\xBFa\xE2\x82", chunk, 8), "a\u{FFFD}");
+        }
+    }
+
+    #[test]
+    fn test_async_encode_writer_iso_2022_jp() {
+        let string = "a\u{30CF}\u{30ED}b";
+        for chunk in 1..string.len() + 1 {
+            assert_eq!(
+                &encode_all(ISO_2022_JP, string.as_bytes(), chunk)[..],
+                &b"a\x1B$B%O%m\x1B(Bb"[..]
+            );
+        }
+    }
+
+    #[test]
+    fn test_async_encode_writer_ncr() {
+        for chunk in 1..6 {
+            assert_eq!(
+                &encode_all(WINDOWS_1251, "a\u{0416}\u{1F4A9}b".as_bytes(), chunk)[..],
+                &b"a\xC6&#128169;b"[..]
+            );
+        }
+    }
+
+    #[test]
+    fn test_async_encode_writer_close_partial_utf8() {
+        let mut writer = AsyncEncodeWriter::new(Vec::new(), WINDOWS_1252);
+        block_on(writer.write_all(b"a\xE2\x82")).unwrap();
+        assert_eq!(
+            block_on(writer.close()).unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+    }
+
+    #[test]
+    fn test_decode_stream() {
+        let chunks = vec![
+            vec![0xEFu8, 0xBB],
+            vec![0xBF, b'a', 0xE2],
+            vec![0x82],
+            vec![0xAC, b'b', 0xE2],
+        ];
+        let strings: Vec<String> =
+            block_on(DecodeStream::new(stream::iter(chunks), WINDOWS_1252).collect());
+        assert_eq!(strings, vec!["a", "\u{20AC}b", "\u{FFFD}"]);
+    }
+
+    #[test]
+    fn test_decode_stream_empty() {
+        let chunks: Vec<&[u8]> = vec![b"", b""];
+        let strings: Vec<String> =
+            block_on(DecodeStream::new(stream::iter(chunks), UTF_8).collect());
+        assert!(strings.is_empty());
+    }
+}
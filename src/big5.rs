@@ -23,6 +23,19 @@ impl Big5Decoder {
         VariantDecoder::Big5(Big5Decoder { lead: None })
     }
 
+    pub fn has_pending_state(&self) -> bool {
+        self.lead.is_some()
+    }
+
+    /// Writes the lead byte, if any.
+    #[cfg(feature = "alloc")]
+    pub fn snapshot_state(&self, state: &mut Vec<u8>) -> bool {
+        if let Some(lead_minus_offset) = self.lead {
+            state.push(lead_minus_offset + 0x81);
+        }
+        true
+    }
+
     fn plus_one_if_lead(&self, byte_length: usize) -> Option<usize> {
         byte_length.checked_add(
             match self.lead {
@@ -373,6 +386,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_big5_decode_all() {
         let input = include_bytes!("test_data/big5_in.txt");
         let expectation = include_str!("test_data/big5_in_ref.txt");
@@ -382,6 +396,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_big5_encode_all() {
         let input = include_str!("test_data/big5_out.txt");
         let expectation = include_bytes!("test_data/big5_out_ref.txt");
@@ -0,0 +1,191 @@
+// Copyright 2017 Mozilla Foundation. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! This module implements a heuristic for recognizing UTF-16 and UTF-32
+//! that lacks a BOM from the distribution of zero bytes and from the
+//! validity of the code units.
+
+use super::*;
+
+/// The outcome of `sniff_bomless_unicode()`.
+///
+/// Available to Rust only.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum BomlessUnicode {
+    /// The input looks like UTF-16. The wrapped encoding is `UTF_16LE` or
+    /// `UTF_16BE`.
+    Utf16(&'static Encoding),
+    /// The input looks like UTF-32LE, which this crate does not support.
+    UnsupportedUtf32Le,
+    /// The input looks like UTF-32BE, which this crate does not support.
+    UnsupportedUtf32Be,
+    /// The input does not look like UTF-16 or UTF-32, so it is presumably
+    /// in an 8-bit or otherwise ASCII-compatible encoding.
+    EightBit,
+}
+
+impl BomlessUnicode {
+    /// Returns the encoding for `Utf16` and `None` otherwise.
+    pub fn encoding(&self) -> Option<&'static Encoding> {
+        match *self {
+            BomlessUnicode::Utf16(encoding) => Some(encoding),
+            _ => None,
+        }
+    }
+}
+
+/// Checks that the complete UTF-32 code units in `bytes` are all scalar
+/// values and that at least one of them is not U+0000.
+fn is_utf32(bytes: &[u8], big_endian: bool) -> bool {
+    let mut seen_non_zero = false;
+    for unit in bytes.chunks(4) {
+        if unit.len() < 4 {
+            break;
+        }
+        let scalar = if big_endian {
+            ((unit[0] as u32) << 24) | ((unit[1] as u32) << 16) | ((unit[2] as u32) << 8) |
+            (unit[3] as u32)
+        } else {
+            ((unit[3] as u32) << 24) | ((unit[2] as u32) << 16) | ((unit[1] as u32) << 8) |
+            (unit[0] as u32)
+        };
+        if scalar > 0x10FFFF || (0xD800..=0xDFFF).contains(&scalar) {
+            return false;
+        }
+        if scalar != 0 {
+            seen_non_zero = true;
+        }
+    }
+    seen_non_zero
+}
+
+/// Checks that the complete UTF-16 code units in `bytes` don't contain
+/// unpaired surrogates. A high surrogate at the very end is allowed, since
+/// its low surrogate may be in the part of the stream not seen yet.
+fn has_valid_surrogates(bytes: &[u8], big_endian: bool) -> bool {
+    let mut expect_low = false;
+    for unit in bytes.chunks(2) {
+        if unit.len() < 2 {
+            break;
+        }
+        let code_unit = if big_endian {
+            ((unit[0] as u16) << 8) | (unit[1] as u16)
+        } else {
+            ((unit[1] as u16) << 8) | (unit[0] as u16)
+        };
+        let is_low = (0xDC00..=0xDFFF).contains(&code_unit);
+        if expect_low != is_low {
+            return false;
+        }
+        expect_low = (0xD800..=0xDBFF).contains(&code_unit);
+    }
+    true
+}
+
+/// Guesses whether `bytes` is UTF-16 or UTF-32 without a BOM.
+///
+/// `bytes` should be the start of the stream (after checking for a BOM with
+/// `Encoding::for_bom()`). Bytes after the last complete code unit are
+/// ignored.
+///
+/// UTF-32 is recognized if every complete 32-bit unit in one byte order is
+/// a Unicode scalar value. UTF-16 is recognized if zero bytes are much more
+/// common at one parity of offsets than at the other, as happens with
+/// characters from the Basic Latin and Latin-1 ranges, and the code units
+/// in the corresponding byte order don't contain unpaired surrogates. This
+/// covers text that starts with two ASCII characters, such as JSON, even if
+/// it is just four bytes long. Text in the other encodings of this crate
+/// normally does not contain zero bytes at all.
+///
+/// UTF-16 text without Latin characters (e.g. in CJK languages) has few
+/// zero bytes and is reported as `EightBit`.
+///
+/// Available to Rust only.
+pub fn sniff_bomless_unicode(bytes: &[u8]) -> BomlessUnicode {
+    let mut even_zeros = 0usize;
+    let mut odd_zeros = 0usize;
+    let units = bytes.len() / 2;
+    for pair in bytes.chunks(2) {
+        if pair.len() < 2 {
+            break;
+        }
+        if pair[0] == 0 {
+            even_zeros += 1;
+        }
+        if pair[1] == 0 {
+            odd_zeros += 1;
+        }
+    }
+    if even_zeros == 0 && odd_zeros == 0 {
+        return BomlessUnicode::EightBit;
+    }
+    if bytes.len() >= 4 {
+        if is_utf32(bytes, false) {
+            return BomlessUnicode::UnsupportedUtf32Le;
+        }
+        if is_utf32(bytes, true) {
+            return BomlessUnicode::UnsupportedUtf32Be;
+        }
+    }
+    // At least a tenth of the code units need to be Latin-1 characters
+    // and the other parity must have at most a quarter as many zeros.
+    if odd_zeros * 10 >= units && even_zeros * 4 <= odd_zeros &&
+       has_valid_surrogates(bytes, false) {
+        return BomlessUnicode::Utf16(UTF_16LE);
+    }
+    if even_zeros * 10 >= units && odd_zeros * 4 <= even_zeros &&
+       has_valid_surrogates(bytes, true) {
+        return BomlessUnicode::Utf16(UTF_16BE);
+    }
+    BomlessUnicode::EightBit
+}
+
+// Any copyright to the test code below this comment is dedicated to the
+// Public Domain. http://creativecommons.org/publicdomain/zero/1.0/
+
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+
+    #[test]
+    fn test_sniff_bomless_utf16() {
+        assert_eq!(sniff_bomless_unicode(b"{\x00\"\x00"),
+                   BomlessUnicode::Utf16(UTF_16LE));
+        assert_eq!(sniff_bomless_unicode(b"\x00{\x00\""),
+                   BomlessUnicode::Utf16(UTF_16BE));
+        assert_eq!(sniff_bomless_unicode(b"H\x00i\x00 \x00\xE5\x65\x2C\x67"),
+                   BomlessUnicode::Utf16(UTF_16LE));
+        assert_eq!(sniff_bomless_unicode(b"\x00a\x00b\x00c\x00d\xD8\x3D\xDE\x00\xD8"),
+                   BomlessUnicode::Utf16(UTF_16BE));
+        // Unpaired low surrogate
+        assert_eq!(sniff_bomless_unicode(b"a\x00b\x00c\x00d\x00\x00\xDCe\x00"),
+                   BomlessUnicode::EightBit);
+        assert_eq!(BomlessUnicode::Utf16(UTF_16BE).encoding(), Some(UTF_16BE));
+    }
+
+    #[test]
+    fn test_sniff_bomless_utf32() {
+        assert_eq!(sniff_bomless_unicode(b"{\x00\x00\x00\"\x00\x00\x00"),
+                   BomlessUnicode::UnsupportedUtf32Le);
+        assert_eq!(sniff_bomless_unicode(b"\x00\x00\x00{\x00\x01\xF6\x00"),
+                   BomlessUnicode::UnsupportedUtf32Be);
+        assert_eq!(BomlessUnicode::UnsupportedUtf32Le.encoding(), None);
+    }
+
+    #[test]
+    fn test_sniff_bomless_eight_bit() {
+        assert_eq!(sniff_bomless_unicode(b""), BomlessUnicode::EightBit);
+        assert_eq!(sniff_bomless_unicode(b"plain text"), BomlessUnicode::EightBit);
+        assert_eq!(sniff_bomless_unicode(b"\xE6\x97\xA5\xE6\x9C\xAC"),
+                   BomlessUnicode::EightBit);
+        // A stray zero byte in 8-bit text
+        assert_eq!(sniff_bomless_unicode(b"some longer text with a\x00zero"),
+                   BomlessUnicode::EightBit);
+    }
+}
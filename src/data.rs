@@ -19580,3 +19580,121 @@ pub fn big5_other_encode(bmp: u16) -> Option<usize> {
 pub fn mul_94(lead: u8) -> usize {
     lead as usize * 94
 }
+
+pub static LOCALE_FALLBACKS: [(&'static str, &'static super::Encoding); 35] =
+    [("ar", &super::WINDOWS_1256_INIT),
+     ("ba", &super::WINDOWS_1251_INIT),
+     ("be", &super::WINDOWS_1251_INIT),
+     ("bg", &super::WINDOWS_1251_INIT),
+     ("cs", &super::WINDOWS_1250_INIT),
+     ("el", &super::ISO_8859_7_INIT),
+     ("et", &super::WINDOWS_1257_INIT),
+     ("fa", &super::WINDOWS_1256_INIT),
+     ("he", &super::WINDOWS_1255_INIT),
+     ("hr", &super::WINDOWS_1250_INIT),
+     ("hu", &super::ISO_8859_2_INIT),
+     ("ja", &super::SHIFT_JIS_INIT),
+     ("kk", &super::WINDOWS_1251_INIT),
+     ("ko", &super::EUC_KR_INIT),
+     ("ku", &super::WINDOWS_1254_INIT),
+     ("ky", &super::WINDOWS_1251_INIT),
+     ("lt", &super::WINDOWS_1257_INIT),
+     ("lv", &super::WINDOWS_1257_INIT),
+     ("mk", &super::WINDOWS_1251_INIT),
+     ("pl", &super::ISO_8859_2_INIT),
+     ("ru", &super::WINDOWS_1251_INIT),
+     ("sah", &super::WINDOWS_1251_INIT),
+     ("sk", &super::WINDOWS_1250_INIT),
+     ("sl", &super::ISO_8859_2_INIT),
+     ("sr", &super::WINDOWS_1251_INIT),
+     ("tg", &super::WINDOWS_1251_INIT),
+     ("th", &super::WINDOWS_874_INIT),
+     ("tr", &super::WINDOWS_1254_INIT),
+     ("tt", &super::WINDOWS_1251_INIT),
+     ("uk", &super::WINDOWS_1251_INIT),
+     ("vi", &super::WINDOWS_1258_INIT),
+     ("zh-cn", &super::GBK_INIT),
+     ("zh-hk", &super::BIG5_INIT),
+     ("zh-sg", &super::GBK_INIT),
+     ("zh-tw", &super::BIG5_INIT)];
+
+pub static TLD_FALLBACKS: [(&'static str, Option<&'static super::Encoding>); 79] =
+    [("ae", Some(&super::WINDOWS_1256_INIT)),
+     ("az", Some(&super::WINDOWS_1254_INIT)),
+     ("bg", Some(&super::WINDOWS_1251_INIT)),
+     ("bh", Some(&super::WINDOWS_1256_INIT)),
+     ("by", Some(&super::WINDOWS_1251_INIT)),
+     ("cc", None),
+     ("cn", Some(&super::GBK_INIT)),
+     ("co", None),
+     ("cy", Some(&super::ISO_8859_7_INIT)),
+     ("cz", Some(&super::WINDOWS_1250_INIT)),
+     ("dz", Some(&super::WINDOWS_1256_INIT)),
+     ("ee", Some(&super::WINDOWS_1257_INIT)),
+     ("eg", Some(&super::WINDOWS_1256_INIT)),
+     ("fm", None),
+     ("gr", Some(&super::ISO_8859_7_INIT)),
+     ("hk", Some(&super::BIG5_INIT)),
+     ("hr", Some(&super::WINDOWS_1250_INIT)),
+     ("hu", Some(&super::ISO_8859_2_INIT)),
+     ("il", Some(&super::WINDOWS_1255_INIT)),
+     ("io", None),
+     ("iq", Some(&super::WINDOWS_1256_INIT)),
+     ("ir", Some(&super::WINDOWS_1256_INIT)),
+     ("jo", Some(&super::WINDOWS_1256_INIT)),
+     ("jp", Some(&super::SHIFT_JIS_INIT)),
+     ("kg", Some(&super::WINDOWS_1251_INIT)),
+     ("kp", Some(&super::EUC_KR_INIT)),
+     ("kr", Some(&super::EUC_KR_INIT)),
+     ("kw", Some(&super::WINDOWS_1256_INIT)),
+     ("kz", Some(&super::WINDOWS_1251_INIT)),
+     ("lb", Some(&super::WINDOWS_1256_INIT)),
+     ("lt", Some(&super::WINDOWS_1257_INIT)),
+     ("lv", Some(&super::WINDOWS_1257_INIT)),
+     ("ly", Some(&super::WINDOWS_1256_INIT)),
+     ("ma", Some(&super::WINDOWS_1256_INIT)),
+     ("me", None),
+     ("mk", Some(&super::WINDOWS_1251_INIT)),
+     ("mo", Some(&super::BIG5_INIT)),
+     ("nu", None),
+     ("om", Some(&super::WINDOWS_1256_INIT)),
+     ("pl", Some(&super::ISO_8859_2_INIT)),
+     ("ps", Some(&super::WINDOWS_1256_INIT)),
+     ("qa", Some(&super::WINDOWS_1256_INIT)),
+     ("ro", Some(&super::WINDOWS_1250_INIT)),
+     ("rs", Some(&super::WINDOWS_1251_INIT)),
+     ("ru", Some(&super::WINDOWS_1251_INIT)),
+     ("sa", Some(&super::WINDOWS_1256_INIT)),
+     ("sd", Some(&super::WINDOWS_1256_INIT)),
+     ("si", Some(&super::ISO_8859_2_INIT)),
+     ("sk", Some(&super::WINDOWS_1250_INIT)),
+     ("su", Some(&super::WINDOWS_1251_INIT)),
+     ("sy", Some(&super::WINDOWS_1256_INIT)),
+     ("th", Some(&super::WINDOWS_874_INIT)),
+     ("tj", Some(&super::WINDOWS_1251_INIT)),
+     ("tk", None),
+     ("tn", Some(&super::WINDOWS_1256_INIT)),
+     ("tr", Some(&super::WINDOWS_1254_INIT)),
+     ("tv", None),
+     ("tw", Some(&super::BIG5_INIT)),
+     ("ua", Some(&super::WINDOWS_1251_INIT)),
+     ("vn", Some(&super::WINDOWS_1258_INIT)),
+     ("ws", None),
+     ("xn--3e0b707e", Some(&super::EUC_KR_INIT)),
+     ("xn--80ao21a", Some(&super::WINDOWS_1251_INIT)),
+     ("xn--90a3ac", Some(&super::WINDOWS_1251_INIT)),
+     ("xn--90ae", Some(&super::WINDOWS_1251_INIT)),
+     ("xn--90ais", Some(&super::WINDOWS_1251_INIT)),
+     ("xn--d1alf", Some(&super::WINDOWS_1251_INIT)),
+     ("xn--fiqs8s", Some(&super::GBK_INIT)),
+     ("xn--fiqz9s", Some(&super::GBK_INIT)),
+     ("xn--j1amh", Some(&super::WINDOWS_1251_INIT)),
+     ("xn--j6w193g", Some(&super::BIG5_INIT)),
+     ("xn--kprw13d", Some(&super::BIG5_INIT)),
+     ("xn--kpry57d", Some(&super::BIG5_INIT)),
+     ("xn--mgbaam7a8h", Some(&super::WINDOWS_1256_INIT)),
+     ("xn--mgberp4a5d4ar", Some(&super::WINDOWS_1256_INIT)),
+     ("xn--o3cw4h", Some(&super::WINDOWS_874_INIT)),
+     ("xn--p1ai", Some(&super::WINDOWS_1251_INIT)),
+     ("xn--wgbh1c", Some(&super::WINDOWS_1256_INIT)),
+     ("ye", Some(&super::WINDOWS_1256_INIT))];
@@ -0,0 +1,847 @@
+// Copyright 2017 Mozilla Foundation. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! This module implements guessing the legacy encoding of unlabeled content.
+//!
+//! Each candidate encoding has a `Decoder` that the input is run through.
+//! A candidate whose decoder reports a malformed sequence is out of the
+//! running. The characters that the remaining decoders produce are scored
+//! using the character frequencies of the languages that the encoding is
+//! used for and heuristics about which pairs of adjacent character classes
+//! are plausible (e.g. a lower-case letter followed by an upper-case letter
+//! in the middle of a word is not).
+//!
+//! The frequency tables in `detector_data.rs` are generated by
+//! `generate-detector-data.py` from the translations in gettext message
+//! catalogs. A character that is frequent enough to be listed scores the
+//! binary logarithm of its frequency (offset to be positive). A letter of
+//! the family's script that is not listed scores slightly negative, since
+//! mis-decoded text tends to consist of such letters.
+//!
+//! `JapaneseDetector` applies the same approach to just the Japanese
+//! encodings.
+
+use super::*;
+use detector_data::*;
+
+/// Score for a letter of the family's script that is not in the family's
+/// frequency table. Listed characters score their weight from the table.
+const UNLISTED: i64 = -1;
+
+/// Score for a letter outside the family's script.
+const FOREIGN: i64 = -1;
+
+/// Score for a non-ASCII letter following another non-ASCII letter in a
+/// Latin family other than Vietnamese.
+const LATIN_RUN: i64 = -5;
+
+/// Score for a lower-case letter followed by an upper-case letter when at
+/// least one of them is non-ASCII.
+const CASE_SWITCH: i64 = -10;
+
+/// Score for an upper-case letter following another upper-case letter when
+/// at least one of them is non-ASCII. (Mis-decoded text tends to be in all
+/// caps.)
+const ALL_CAPS: i64 = -2;
+
+/// Score for a symbol right next to a letter.
+const SYMBOL_NEXT_TO_LETTER: i64 = -10;
+
+/// Score for a letter right next to a letter of another script.
+const MIXED_SCRIPT: i64 = -10;
+
+/// Score for a Thai combining mark that doesn't follow a consonant or a
+/// Thai leading vowel that doesn't precede one.
+const MISPLACED_THAI: i64 = -10;
+
+/// Score for kana in a non-Japanese CJK family and for half-width katakana
+/// in the Japanese family.
+const IMPROBABLE_CJK: i64 = -3;
+
+/// Score for a character that a CJK family can't account for at all.
+const IMPOSSIBLE_CJK: i64 = -5;
+
+/// Score for C1 controls and private use characters.
+const CONTROL: i64 = -200;
+
+/// Bonus for the candidates of the family that the top-level domain hint
+/// suggests.
+const TLD_BONUS: i64 = 20;
+
+/// A family of languages that a candidate encoding is used for. Determines
+/// the heuristics that the decoded characters are scored with.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum Family {
+    /// Only checks for validity.
+    Unicode,
+    Western,
+    Central,
+    Baltic,
+    Turkish,
+    Vietnamese,
+    Cyrillic,
+    Greek,
+    Hebrew,
+    Arabic,
+    Thai,
+    Japanese,
+    SimplifiedChinese,
+    TraditionalChinese,
+    Korean,
+}
+
+impl Family {
+    /// The characters of the family's frequency table and their weights.
+    fn table(self) -> (&'static [u16], &'static [u8]) {
+        match self {
+            Family::Unicode => (&[], &[]),
+            Family::Western => (&WESTERN_CHARS, &WESTERN_WEIGHTS),
+            Family::Central => (&CENTRAL_CHARS, &CENTRAL_WEIGHTS),
+            Family::Baltic => (&BALTIC_CHARS, &BALTIC_WEIGHTS),
+            Family::Turkish => (&TURKISH_CHARS, &TURKISH_WEIGHTS),
+            Family::Vietnamese => (&VIETNAMESE_CHARS, &VIETNAMESE_WEIGHTS),
+            Family::Cyrillic => (&CYRILLIC_CHARS, &CYRILLIC_WEIGHTS),
+            Family::Greek => (&GREEK_CHARS, &GREEK_WEIGHTS),
+            Family::Hebrew => (&HEBREW_CHARS, &HEBREW_WEIGHTS),
+            Family::Arabic => (&ARABIC_CHARS, &ARABIC_WEIGHTS),
+            Family::Thai => (&THAI_CHARS, &THAI_WEIGHTS),
+            Family::Japanese => (&JAPANESE_CHARS, &JAPANESE_WEIGHTS),
+            Family::SimplifiedChinese => {
+                (&SIMPLIFIED_CHINESE_CHARS, &SIMPLIFIED_CHINESE_WEIGHTS)
+            }
+            Family::TraditionalChinese => {
+                (&TRADITIONAL_CHINESE_CHARS, &TRADITIONAL_CHINESE_WEIGHTS)
+            }
+            Family::Korean => (&KOREAN_CHARS, &KOREAN_WEIGHTS),
+        }
+    }
+
+    fn is_latin(self) -> bool {
+        matches!(self,
+                 Family::Western | Family::Central | Family::Baltic | Family::Turkish |
+                 Family::Vietnamese)
+    }
+
+    fn is_cjk(self) -> bool {
+        matches!(self,
+                 Family::Japanese | Family::SimplifiedChinese | Family::TraditionalChinese |
+                 Family::Korean)
+    }
+
+    /// Whether `c` (known to be non-ASCII) is a letter of the script of a
+    /// non-CJK family.
+    fn is_script_letter(self, c: char) -> bool {
+        match self {
+            Family::Cyrillic => matches!(c, '\u{0400}'..='\u{04FF}'),
+            Family::Greek => matches!(c, '\u{0370}'..='\u{03FF}'),
+            Family::Hebrew => matches!(c, '\u{0591}'..='\u{05F4}'),
+            Family::Arabic => matches!(c, '\u{0621}'..='\u{065F}' | '\u{0671}'..='\u{06D3}'),
+            Family::Thai => matches!(c, '\u{0E01}'..='\u{0E5B}'),
+            Family::Unicode => false,
+            _ => c.is_alphabetic() && matches!(c, '\u{0}'..='\u{024F}' | '\u{1E00}'..='\u{1EFF}'),
+        }
+    }
+}
+
+/// The class of a character for the purpose of scoring character pairs.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum Class {
+    /// Whitespace, digits, ASCII punctuation and non-ASCII punctuation that
+    /// commonly occurs next to letters.
+    Space,
+    /// An ASCII letter.
+    Ascii { upper: bool },
+    /// A non-ASCII letter of the family's script.
+    Letter { upper: bool },
+    /// A non-ASCII letter of another script.
+    Foreign { upper: bool },
+    /// A non-ASCII symbol that is implausible right next to a letter.
+    Symbol,
+}
+
+impl Class {
+    fn is_letter(self) -> bool {
+        matches!(self,
+                 Class::Ascii { .. } | Class::Letter { .. } | Class::Foreign { .. })
+    }
+
+    fn is_non_ascii_letter(self) -> bool {
+        matches!(self, Class::Letter { .. } | Class::Foreign { .. })
+    }
+
+    fn is_lower(self) -> bool {
+        match self {
+            Class::Ascii { upper } | Class::Letter { upper } | Class::Foreign { upper } => !upper,
+            _ => false,
+        }
+    }
+
+    fn is_upper(self) -> bool {
+        match self {
+            Class::Ascii { upper } | Class::Letter { upper } | Class::Foreign { upper } => upper,
+            _ => false,
+        }
+    }
+}
+
+/// Non-ASCII punctuation that is fine right next to letters.
+fn is_word_adjacent_punctuation(c: char) -> bool {
+    matches!(c,
+             '\u{00A0}' | '\u{00AB}' | '\u{00BB}' | '\u{00B7}' | '\u{00AA}' | '\u{00BA}' |
+             '\u{00B0}' | '\u{2013}' | '\u{2014}' | '\u{2018}' | '\u{2019}' | '\u{201A}' |
+             '\u{201C}' | '\u{201D}' | '\u{201E}' | '\u{2026}' | '\u{20AC}')
+}
+
+fn is_thai_consonant(c: char) -> bool {
+    matches!(c, '\u{0E01}'..='\u{0E2E}')
+}
+
+/// Thai vowel signs and tone marks that combine with the preceding
+/// consonant.
+fn is_thai_mark(c: char) -> bool {
+    matches!(c, '\u{0E31}' | '\u{0E34}'..='\u{0E3A}' | '\u{0E47}'..='\u{0E4E}')
+}
+
+fn is_thai_tone_mark(c: char) -> bool {
+    matches!(c, '\u{0E48}'..='\u{0E4B}')
+}
+
+/// Thai vowels that are written before the consonant they follow in speech.
+fn is_thai_leading_vowel(c: char) -> bool {
+    matches!(c, '\u{0E40}'..='\u{0E44}')
+}
+
+/// Scores characters using the heuristics of a `Family`.
+struct Scorer {
+    family: Family,
+    chars: &'static [u16],
+    weights: &'static [u8],
+    prev: Class,
+    prev_char: char,
+    score: i64,
+}
+
+impl Scorer {
+    fn new(family: Family) -> Scorer {
+        let (chars, weights) = family.table();
+        Scorer {
+            family,
+            chars,
+            weights,
+            prev: Class::Space,
+            prev_char: ' ',
+            score: 0,
+        }
+    }
+
+    fn lookup(&self, c: char) -> Option<i64> {
+        if c > '\u{FFFF}' {
+            return None;
+        }
+        self.chars
+            .binary_search(&(c as u16))
+            .ok()
+            .map(|i| self.weights[i] as i64)
+    }
+
+    /// The weight of `c` (or of its lower-case form) in the family's
+    /// frequency table or `UNLISTED`.
+    fn weight(&self, c: char) -> i64 {
+        if let Some(weight) = self.lookup(c) {
+            return weight;
+        }
+        match c.to_lowercase().next() {
+            Some(lower) if lower != c => self.lookup(lower).unwrap_or(UNLISTED),
+            _ => UNLISTED,
+        }
+    }
+
+    fn score_str(&mut self, s: &str) {
+        if self.family == Family::Unicode {
+            return;
+        }
+        for c in s.chars() {
+            let (score, class) = if c < '\u{80}' {
+                if c.is_ascii_alphabetic() {
+                    (0, Class::Ascii { upper: c.is_ascii_uppercase() })
+                } else {
+                    (0, Class::Space)
+                }
+            } else if matches!(c, '\u{80}'..='\u{9F}' | '\u{E000}'..='\u{F8FF}') {
+                (CONTROL, Class::Symbol)
+            } else if self.family.is_cjk() {
+                // CJK characters take two bytes, so weigh them like two
+                // characters of a single-byte encoding.
+                let (score, class) = self.score_cjk(c);
+                (2 * score, class)
+            } else {
+                self.score_alphabetic(c)
+            };
+            self.score += score + self.score_pair(class);
+            if self.family == Family::Thai {
+                self.score += self.score_thai(c);
+            }
+            self.prev = class;
+            self.prev_char = c;
+        }
+    }
+
+    fn score_cjk(&self, c: char) -> (i64, Class) {
+        let japanese = self.family == Family::Japanese;
+        let letter = Class::Letter { upper: false };
+        match c as u32 {
+            0x3041..=0x30FF => {
+                (if japanese { self.weight(c) } else { IMPROBABLE_CJK }, letter)
+            }
+            0xFF61..=0xFF9F => (if japanese { IMPROBABLE_CJK } else { IMPOSSIBLE_CJK }, letter),
+            0xAC00..=0xD7A3 => {
+                if self.family == Family::Korean {
+                    (self.weight(c), letter)
+                } else {
+                    (IMPOSSIBLE_CJK, letter)
+                }
+            }
+            0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xF900..=0xFAFF | 0x20000..=0x2FFFF => {
+                if self.family == Family::Korean {
+                    // Hanja are rare in Korean text but not wrong.
+                    (self.lookup(c).unwrap_or(0), letter)
+                } else {
+                    (self.weight(c), letter)
+                }
+            }
+            0x3000..=0x303F | 0xFF01..=0xFF60 | 0xFFE0..=0xFFEF => (0, Class::Space),
+            _ => (FOREIGN, Class::Symbol),
+        }
+    }
+
+    fn score_alphabetic(&self, c: char) -> (i64, Class) {
+        if matches!(c, '\u{0300}'..='\u{036F}') {
+            // Combining diacritics only occur in windows-1258, where they
+            // attach to the preceding letter.
+            if self.family == Family::Vietnamese && self.prev.is_letter() {
+                return (self.weight(c), self.prev);
+            }
+            return (SYMBOL_NEXT_TO_LETTER, Class::Symbol);
+        }
+        if self.family.is_script_letter(c) {
+            return (self.weight(c), Class::Letter { upper: c.is_uppercase() });
+        }
+        if c.is_alphabetic() {
+            return (FOREIGN, Class::Foreign { upper: c.is_uppercase() });
+        }
+        if is_word_adjacent_punctuation(c) {
+            return (0, Class::Space);
+        }
+        if c == '\u{00BF}' || c == '\u{00A1}' {
+            // Spanish inverted punctuation precedes words.
+            if self.prev.is_letter() {
+                return (SYMBOL_NEXT_TO_LETTER, Class::Space);
+            }
+            return (0, Class::Space);
+        }
+        (0, Class::Symbol)
+    }
+
+    /// Scores the pair formed by the previous character and a character of
+    /// class `class`.
+    fn score_pair(&self, class: Class) -> i64 {
+        let prev = self.prev;
+        let non_ascii = prev.is_non_ascii_letter() || class.is_non_ascii_letter();
+        let mut score = 0;
+        if non_ascii && prev.is_lower() && class.is_upper() {
+            score += CASE_SWITCH;
+        }
+        if non_ascii && prev.is_upper() && class.is_upper() {
+            score += ALL_CAPS;
+        }
+        if (prev == Class::Symbol && class.is_letter()) ||
+           (prev.is_letter() && class == Class::Symbol) {
+            score += SYMBOL_NEXT_TO_LETTER;
+        }
+        if self.family.is_cjk() {
+            return score;
+        }
+        match (prev, class) {
+            (Class::Foreign { .. }, Class::Ascii { .. }) |
+            (Class::Foreign { .. }, Class::Letter { .. }) |
+            (Class::Ascii { .. }, Class::Foreign { .. }) |
+            (Class::Letter { .. }, Class::Foreign { .. }) => {
+                score += MIXED_SCRIPT;
+            }
+            (Class::Ascii { .. }, Class::Letter { .. }) |
+            (Class::Letter { .. }, Class::Ascii { .. }) if !self.family.is_latin() => {
+                score += MIXED_SCRIPT;
+            }
+            (Class::Letter { .. }, Class::Letter { .. })
+                if self.family.is_latin() && self.family != Family::Vietnamese => {
+                score += LATIN_RUN;
+            }
+            _ => {}
+        }
+        score
+    }
+
+    /// Scores Thai marks that don't follow a consonant and leading vowels
+    /// that don't precede one.
+    fn score_thai(&self, c: char) -> i64 {
+        let prev = self.prev_char;
+        if is_thai_mark(c) {
+            if !(is_thai_consonant(prev) || is_thai_mark(prev)) ||
+               (is_thai_tone_mark(prev) && is_thai_tone_mark(c)) {
+                return MISPLACED_THAI;
+            }
+        } else if is_thai_leading_vowel(prev) && !is_thai_consonant(c) {
+            return MISPLACED_THAI;
+        }
+        0
+    }
+}
+
+struct Candidate {
+    encoding: &'static Encoding,
+    decoder: Decoder,
+    scorer: Scorer,
+    disqualified: bool,
+}
+
+impl Candidate {
+    fn new(encoding: &'static Encoding, family: Family) -> Candidate {
+        Candidate {
+            encoding,
+            decoder: encoding.new_decoder_without_bom_handling(),
+            scorer: Scorer::new(family),
+            disqualified: false,
+        }
+    }
+
+    fn feed(&mut self, buffer: &[u8], last: bool) {
+        if self.disqualified {
+            return;
+        }
+        let mut bytes = [0u8; 1024];
+        let dst = ::core::str::from_utf8_mut(&mut bytes[..]).unwrap();
+        let mut total_read = 0usize;
+        loop {
+            let (result, read, written) =
+                self.decoder
+                    .decode_to_str_without_replacement(&buffer[total_read..], dst, last);
+            total_read += read;
+            self.scorer.score_str(&dst[..written]);
+            match result {
+                DecoderResult::InputEmpty => {
+                    return;
+                }
+                DecoderResult::OutputFull => {}
+                DecoderResult::Malformed(_, _) => {
+                    self.disqualified = true;
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// Whether the candidate for `encoding` is among `candidates` and has not
+/// been disqualified.
+fn is_viable(candidates: &[Candidate], encoding: &'static Encoding) -> bool {
+    candidates
+        .iter()
+        .any(|candidate| candidate.encoding == encoding && !candidate.disqualified)
+}
+
+/// Guesses the encoding of unlabeled content from the content itself.
+///
+/// Feed the bytes of the content to the detector in one or more chunks using
+/// `feed()` and then ask for the most likely encoding using `guess()`. The
+/// candidates are the legacy encodings of the Encoding Standard that are in
+/// actual use for text on the Web (and UTF-8 if allowed). A candidate is
+/// never guessed if the bytes are malformed in it.
+///
+/// The detector is meant for content that has no label at all. If the
+/// content has a BOM or a label, those take precedence.
+///
+/// Available to Rust only.
+pub struct EncodingDetector {
+    candidates: Vec<Candidate>,
+    utf8: Candidate,
+    non_ascii_seen: bool,
+    esc_seen: bool,
+    finished: bool,
+}
+
+impl EncodingDetector {
+    /// Instantiates a detector that has not seen any content yet.
+    pub fn new() -> EncodingDetector {
+        EncodingDetector {
+            candidates: vec![
+                Candidate::new(WINDOWS_1252, Family::Western),
+                Candidate::new(WINDOWS_1250, Family::Central),
+                Candidate::new(ISO_8859_2, Family::Central),
+                Candidate::new(WINDOWS_1251, Family::Cyrillic),
+                Candidate::new(KOI8_U, Family::Cyrillic),
+                Candidate::new(IBM866, Family::Cyrillic),
+                Candidate::new(WINDOWS_1253, Family::Greek),
+                Candidate::new(ISO_8859_7, Family::Greek),
+                Candidate::new(WINDOWS_1254, Family::Turkish),
+                Candidate::new(WINDOWS_1255, Family::Hebrew),
+                Candidate::new(WINDOWS_1256, Family::Arabic),
+                Candidate::new(ISO_8859_6, Family::Arabic),
+                Candidate::new(WINDOWS_1257, Family::Baltic),
+                Candidate::new(ISO_8859_13, Family::Baltic),
+                Candidate::new(WINDOWS_1258, Family::Vietnamese),
+                Candidate::new(WINDOWS_874, Family::Thai),
+                Candidate::new(GBK, Family::SimplifiedChinese),
+                Candidate::new(BIG5, Family::TraditionalChinese),
+                Candidate::new(EUC_KR, Family::Korean),
+                Candidate::new(SHIFT_JIS, Family::Japanese),
+                Candidate::new(EUC_JP, Family::Japanese),
+                Candidate::new(ISO_2022_JP, Family::Japanese),
+            ],
+            utf8: Candidate::new(UTF_8, Family::Unicode),
+            non_ascii_seen: false,
+            esc_seen: false,
+            finished: false,
+        }
+    }
+
+    /// Feeds the next chunk of the content to the detector. `last` indicates
+    /// that `buffer` is the last chunk (which may be empty).
+    ///
+    /// Returns `true` if non-ASCII bytes have been seen so far, i.e. whether
+    /// the guess can be anything other than the fallback.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called after a call with `last` set to `true`.
+    pub fn feed(&mut self, buffer: &[u8], last: bool) -> bool {
+        assert!(!self.finished, "Must not feed after the last chunk.");
+        self.finished = last;
+        if !self.non_ascii_seen {
+            let up_to = Encoding::ascii_valid_up_to(buffer);
+            self.non_ascii_seen = up_to < buffer.len();
+            self.esc_seen |= buffer[..up_to].contains(&0x1B);
+        }
+        for candidate in self.candidates.iter_mut() {
+            candidate.feed(buffer, last);
+        }
+        self.utf8.feed(buffer, last);
+        self.non_ascii_seen
+    }
+
+    /// Returns the most likely encoding of the content fed so far.
+    ///
+    /// `tld` is the top-level domain of the URL the content was retrieved
+    /// from, if any, without the dot (e.g. `b"jp"`). It is used as a hint:
+    /// encodings of the same script as `Encoding::fallback_for_tld()` get a
+    /// boost and if the content is all ASCII, the guess is the fallback for
+    /// the domain.
+    ///
+    /// If `allow_utf8` is `false`, UTF-8 is never guessed. (E.g. when the
+    /// caller has already checked for UTF-8 in a more precise way.)
+    pub fn guess(&self, tld: Option<&[u8]>, allow_utf8: bool) -> &'static Encoding {
+        let hint = tld.and_then(Encoding::fallback_for_tld);
+        if !self.non_ascii_seen {
+            if self.esc_seen && is_viable(&self.candidates, ISO_2022_JP) {
+                return ISO_2022_JP;
+            }
+            return hint.unwrap_or(WINDOWS_1252);
+        }
+        if allow_utf8 && !self.utf8.disqualified {
+            return UTF_8;
+        }
+        let hint_family = match hint {
+            Some(encoding) => {
+                self.candidates
+                    .iter()
+                    .find(|candidate| candidate.encoding == encoding)
+                    .map(|candidate| candidate.scorer.family)
+            }
+            None => None,
+        };
+        let mut best: Option<(i64, &'static Encoding)> = None;
+        for candidate in self.candidates.iter() {
+            if candidate.disqualified {
+                continue;
+            }
+            let mut score = candidate.scorer.score;
+            if Some(candidate.scorer.family) == hint_family {
+                score += TLD_BONUS;
+            }
+            match best {
+                Some((best_score, _)) if best_score >= score => {}
+                _ => {
+                    best = Some((score, candidate.encoding));
+                }
+            }
+        }
+        match best {
+            Some((_, encoding)) => encoding,
+            None => hint.unwrap_or(WINDOWS_1252),
+        }
+    }
+}
+
+impl Default for EncodingDetector {
+    fn default() -> EncodingDetector {
+        EncodingDetector::new()
+    }
+}
+
+/// Tells Shift_JIS, EUC-JP and ISO-2022-JP apart in unlabeled content that
+/// is known to be Japanese.
+///
+/// The decoders for the three encodings run in parallel over the chunks fed
+/// using `feed()`. A candidate whose decoder reports a malformed sequence is
+/// out of the running. Among the rest, `guess()` picks the one whose output
+/// scores best according to the frequencies of kana and kanji in Japanese
+/// text (the same table and heuristics `EncodingDetector` uses for
+/// Japanese). Since ISO-2022-JP is a 7-bit encoding, it wins whenever the
+/// content is ASCII with escape sequences.
+///
+/// Unlike `EncodingDetector`, this detector doesn't consider encodings for
+/// other languages, so it is not fooled by Japanese text that also happens
+/// to score well as e.g. GBK.
+///
+/// Available to Rust only.
+pub struct JapaneseDetector {
+    candidates: Vec<Candidate>,
+    non_ascii_seen: bool,
+    esc_seen: bool,
+    finished: bool,
+}
+
+impl JapaneseDetector {
+    /// Instantiates a detector that has not seen any content yet.
+    pub fn new() -> JapaneseDetector {
+        JapaneseDetector {
+            candidates: vec![
+                Candidate::new(SHIFT_JIS, Family::Japanese),
+                Candidate::new(EUC_JP, Family::Japanese),
+                Candidate::new(ISO_2022_JP, Family::Japanese),
+            ],
+            non_ascii_seen: false,
+            esc_seen: false,
+            finished: false,
+        }
+    }
+
+    /// Feeds the next chunk of the content to the detector. `last` indicates
+    /// that `buffer` is the last chunk (which may be empty).
+    ///
+    /// Returns `true` if at most one candidate is left, i.e. if feeding more
+    /// content can no longer change the guess.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called after a call with `last` set to `true`.
+    pub fn feed(&mut self, buffer: &[u8], last: bool) -> bool {
+        assert!(!self.finished, "Must not feed after the last chunk.");
+        self.finished = last;
+        if !self.non_ascii_seen {
+            let up_to = Encoding::ascii_valid_up_to(buffer);
+            self.non_ascii_seen = up_to < buffer.len();
+            self.esc_seen |= buffer[..up_to].contains(&0x1B);
+        }
+        for candidate in self.candidates.iter_mut() {
+            candidate.feed(buffer, last);
+        }
+        self.candidates
+            .iter()
+            .filter(|candidate| !candidate.disqualified)
+            .count() <= 1
+    }
+
+    /// Returns the most likely encoding of the content fed so far or `None`
+    /// if the content is malformed in all three encodings or is plain ASCII
+    /// (without ISO-2022-JP escape sequences), in which case any of them
+    /// would do.
+    ///
+    /// If Shift_JIS and EUC-JP score the same, Shift_JIS is returned.
+    pub fn guess(&self) -> Option<&'static Encoding> {
+        if !self.non_ascii_seen {
+            if self.esc_seen && is_viable(&self.candidates, ISO_2022_JP) {
+                return Some(ISO_2022_JP);
+            }
+            return None;
+        }
+        let mut best: Option<(i64, &'static Encoding)> = None;
+        for candidate in self.candidates.iter() {
+            if candidate.disqualified {
+                continue;
+            }
+            match best {
+                Some((best_score, _)) if best_score >= candidate.scorer.score => {}
+                _ => {
+                    best = Some((candidate.scorer.score, candidate.encoding));
+                }
+            }
+        }
+        best.map(|(_, encoding)| encoding)
+    }
+}
+
+impl Default for JapaneseDetector {
+    fn default() -> JapaneseDetector {
+        JapaneseDetector::new()
+    }
+}
+
+// Any copyright to the test code below this comment is dedicated to the
+// Public Domain. http://creativecommons.org/publicdomain/zero/1.0/
+
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+
+    fn guess(bytes: &[u8], tld: Option<&[u8]>, allow_utf8: bool) -> &'static Encoding {
+        let mut detector = EncodingDetector::new();
+        detector.feed(bytes, true);
+        detector.guess(tld, allow_utf8)
+    }
+
+    fn check(text: &str, encoding: &'static Encoding) {
+        let (bytes, used, had_errors) = encoding.encode(text);
+        assert_eq!(used, encoding);
+        assert!(!had_errors);
+        assert_eq!(guess(&bytes, None, true), encoding, "{}", text);
+    }
+
+    #[test]
+    fn test_detect_latin() {
+        check("Le café est très bon, mais le thé est meilleur. À bientôt !",
+              WINDOWS_1252);
+        check("Grüße aus München, die Straße ist schön.", WINDOWS_1252);
+        check("Zażółć gęślą jaźń. Pchnąć w tę łódź jeża lub ośm skrzyń fig.",
+              WINDOWS_1250);
+        check("Příliš žluťoučký kůň úpěl ďábelské ódy.", WINDOWS_1250);
+        check("Pijamalı hasta yağız şoföre çabucak güvendi.", WINDOWS_1254);
+        check("Įlinkdama fechtuotojo špaga sublykčiojusi pragręžė apvalų arbūzą.",
+              WINDOWS_1257);
+        check("Glāžšķūņa rūķīši dzērumā čiepj Baha koncertflīģeļu vākus.",
+              WINDOWS_1257);
+        // windows-1258 represents most Vietnamese letters with tone marks
+        // as combining sequences.
+        check("Chào mư\u{300}ng đê\u{301}n vơ\u{300}i Viê\u{323}t Nam, tôi yêu \
+               tiê\u{301}ng Viê\u{323}t.",
+              WINDOWS_1258);
+    }
+
+    #[test]
+    fn test_detect_non_latin() {
+        check("Съешь же ещё этих мягких французских булок, да выпей чаю.",
+              WINDOWS_1251);
+        check("Съешь же ещё этих мягких французских булок, да выпей чаю.", KOI8_U);
+        check("Съешь же ещё этих мягких французских булок, да выпей чаю.", IBM866);
+        check("Ξεσκεπάζω την ψυχοφθόρα βδελυγμία. Καλημέρα σας.", WINDOWS_1253);
+        check("דג סקרן שט בים מאוכזב ולפתע מצא חברה", WINDOWS_1255);
+        check("نص حكيم له سر قاطع وذو شأن عظيم مكتوب على ثوب أخضر", WINDOWS_1256);
+        check("เป็นมนุษย์สุดประเสริฐเลิศคุณค่า กว่าบรรดาฝูงสัตว์เดรัจฉาน", WINDOWS_874);
+    }
+
+    #[test]
+    fn test_detect_cjk() {
+        check("日本語の文章です。これはテストです。", SHIFT_JIS);
+        check("日本語の文章です。これはテストです。", EUC_JP);
+        check("日本語の文章です。これはテストです。", ISO_2022_JP);
+        check("我们的国家有很多人，他们都是中国人。", GBK);
+        check("我們的國家有很多人，他們都是中國人。", BIG5);
+        check("이것은 한국어 문장입니다. 우리는 학교에 갑니다.", EUC_KR);
+    }
+
+    #[test]
+    fn test_detect_utf8() {
+        let text = "Le café est très bon.";
+        assert_eq!(guess(text.as_bytes(), None, true), UTF_8);
+        assert!(guess(text.as_bytes(), None, false) != UTF_8);
+    }
+
+    #[test]
+    fn test_detect_ascii_and_tld() {
+        assert_eq!(guess(b"Hello", None, true), WINDOWS_1252);
+        assert_eq!(guess(b"Hello", Some(b"RU"), true), WINDOWS_1251);
+        assert_eq!(guess(b"Hello", Some(b"jp"), true), SHIFT_JIS);
+        assert_eq!(guess(b"Hello", Some(b"example"), true), WINDOWS_1252);
+        // A lone non-ASCII byte is ambiguous; the hint decides.
+        assert_eq!(guess(b"a \xE9 b", Some(b"gr"), true), WINDOWS_1253);
+    }
+
+    #[test]
+    fn test_detect_incremental() {
+        let (bytes, _, _) = SHIFT_JIS.encode("日本語の文章です。これはテストです。");
+        let mut detector = EncodingDetector::new();
+        assert!(!detector.feed(b"<p>", false));
+        for chunk in bytes.chunks(3) {
+            assert!(detector.feed(chunk, false));
+        }
+        detector.feed(b"", true);
+        assert_eq!(detector.guess(None, true), SHIFT_JIS);
+    }
+
+    #[test]
+    fn test_detect_never_malformed() {
+        // 0x81 0x20 is malformed in all the CJK encodings, so their character
+        // scores can't win no matter what.
+        let (bytes, _, _) = GBK.encode("我们的国家有很多人，他们都是中国人。");
+        let mut bytes = bytes.into_owned();
+        bytes.extend_from_slice(b"\x81 ");
+        let encoding = guess(&bytes, Some(b"cn"), true);
+        assert!(encoding != GBK);
+        let mut decoder = encoding.new_decoder_without_bom_handling();
+        let mut string = String::with_capacity(1024);
+        let (result, _) =
+            decoder.decode_to_string_without_replacement(&bytes, &mut string, true);
+        assert_eq!(result, DecoderResult::InputEmpty);
+    }
+
+    fn guess_japanese(bytes: &[u8], chunk: usize) -> Option<&'static Encoding> {
+        let mut detector = JapaneseDetector::new();
+        for piece in bytes.chunks(chunk) {
+            detector.feed(piece, false);
+        }
+        detector.feed(b"", true);
+        detector.guess()
+    }
+
+    #[test]
+    fn test_detect_japanese() {
+        let text = "お知らせ：明日の会議は午後三時からです。メーリングリスト ML-123";
+        for &encoding in &[SHIFT_JIS, EUC_JP, ISO_2022_JP] {
+            let (bytes, _, had_errors) = encoding.encode(text);
+            assert!(!had_errors);
+            for &chunk in &[1usize, 2, 3, 7, 1024] {
+                assert_eq!(guess_japanese(&bytes, chunk), Some(encoding), "{}", encoding.name());
+            }
+        }
+    }
+
+    #[test]
+    fn test_detect_japanese_frequencies() {
+        // EUC-JP bytes of kana and kanji are also well-formed Shift_JIS, which
+        // decodes them as half-width katakana and rare kanji instead.
+        let (bytes, _, _) = EUC_JP.encode("お知らせ");
+        let (_, malformed) = SHIFT_JIS.decode_without_bom_handling(&bytes);
+        assert!(!malformed);
+        for &chunk in &[1usize, 2, 1024] {
+            assert_eq!(guess_japanese(&bytes, chunk), Some(EUC_JP));
+        }
+    }
+
+    #[test]
+    fn test_detect_japanese_undecided() {
+        assert_eq!(guess_japanese(b"Subject: hello", 4), None);
+        // 0xFF is malformed in all three.
+        assert_eq!(guess_japanese(b"a\xFFb", 4), None);
+        let mut detector = JapaneseDetector::new();
+        assert!(!detector.feed(b"plain", false));
+        // 0x82 0xA0 is hiragana in Shift_JIS and malformed in EUC-JP.
+        assert!(detector.feed(b"\x82\xA0", false));
+        assert!(detector.feed(b"", true));
+        assert_eq!(detector.guess(), Some(SHIFT_JIS));
+    }
+}
@@ -0,0 +1,715 @@
+// Copyright 2017 Mozilla Foundation. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// THIS IS A GENERATED FILE. PLEASE DO NOT EDIT.
+// Instead, please regenerate using generate-detector-data.py
+
+// For each family of languages, the characters (lower-cased where possible)
+// whose frequency in the family is high enough to be listed, sorted by code
+// point, and their weights, which are the binary logarithms of their
+// frequencies (plus a constant and capped).
+
+pub static WESTERN_CHARS: [u16; 31] =
+    [0x00DF, 0x00E0, 0x00E1, 0x00E2, 0x00E3, 0x00E4, 0x00E5, 0x00E6, 0x00E7, 0x00E8, 0x00E9,
+     0x00EA, 0x00EB, 0x00ED, 0x00EE, 0x00EF, 0x00F0, 0x00F1, 0x00F2, 0x00F3, 0x00F4, 0x00F5,
+     0x00F6, 0x00F8, 0x00F9, 0x00FA, 0x00FC, 0x00FD, 0x00FE, 0x0161, 0x017E];
+
+pub static WESTERN_WEIGHTS: [u8; 31] =
+    [2, 5, 7, 3, 6, 7, 7, 6, 5, 6, 7, 4, 5, 6, 1, 4, 5, 4, 4, 7, 2, 3, 6, 7,
+     3, 5, 6, 3, 2, 2, 1];
+
+pub static CENTRAL_CHARS: [u16; 34] =
+    [0x00E1, 0x00E2, 0x00E9, 0x00ED, 0x00EE, 0x00F3, 0x00F4, 0x00F6, 0x00FA, 0x00FC, 0x00FD,
+     0x0103, 0x0105, 0x0107, 0x010D, 0x0111, 0x0119, 0x011B, 0x013E, 0x0142, 0x0144, 0x0148,
+     0x0151, 0x0159, 0x015B, 0x015F, 0x0161, 0x0163, 0x0165, 0x016F, 0x0171, 0x017A, 0x017C,
+     0x017E];
+
+pub static CENTRAL_WEIGHTS: [u8; 34] =
+    [7, 2, 6, 6, 4, 5, 1, 4, 4, 3, 5, 6, 4, 7, 8, 5, 4, 3, 3, 5, 3, 1, 3, 4,
+     4, 5, 8, 5, 4, 2, 1, 1, 5, 7];
+
+pub static BALTIC_CHARS: [u16; 20] =
+    [0x00E4, 0x00F5, 0x00F6, 0x00FC, 0x0101, 0x0105, 0x010D, 0x0113, 0x0117, 0x0119, 0x0123,
+     0x012B, 0x012F, 0x0137, 0x013C, 0x0146, 0x0161, 0x016B, 0x0173, 0x017E];
+
+pub static BALTIC_WEIGHTS: [u8; 20] =
+    [8, 7, 4, 7, 7, 5, 5, 7, 7, 4, 2, 6, 6, 3, 4, 4, 7, 5, 6, 6];
+
+pub static TURKISH_CHARS: [u16; 8] =
+    [0x00E1, 0x00E7, 0x00F6, 0x00FC, 0x011F, 0x0130, 0x0131, 0x015F];
+
+pub static TURKISH_WEIGHTS: [u8; 8] =
+    [1, 8, 6, 7, 7, 5, 8, 8];
+
+pub static VIETNAMESE_CHARS: [u16; 19] =
+    [0x00E0, 0x00E1, 0x00E2, 0x00E9, 0x00EA, 0x00ED, 0x00F3, 0x00F4, 0x00F9, 0x00FA, 0x0103,
+     0x0111, 0x01A1, 0x01B0, 0x0300, 0x0301, 0x0303, 0x0309, 0x0323];
+
+pub static VIETNAMESE_WEIGHTS: [u8; 19] =
+    [6, 6, 6, 3, 7, 4, 5, 7, 4, 3, 4, 7, 7, 7, 7, 7, 6, 7, 7];
+
+pub static CYRILLIC_CHARS: [u16; 38] =
+    [0x0430, 0x0431, 0x0432, 0x0433, 0x0434, 0x0435, 0x0436, 0x0437, 0x0438, 0x0439, 0x043A,
+     0x043B, 0x043C, 0x043D, 0x043E, 0x043F, 0x0440, 0x0441, 0x0442, 0x0443, 0x0444, 0x0445,
+     0x0446, 0x0447, 0x0448, 0x0449, 0x044A, 0x044B, 0x044C, 0x044D, 0x044E, 0x044F, 0x0454,
+     0x0456, 0x0458, 0x0459, 0x045A, 0x045E];
+
+pub static CYRILLIC_WEIGHTS: [u8; 38] =
+    [8, 4, 6, 4, 6, 7, 3, 5, 7, 3, 6, 6, 5, 7, 7, 6, 6, 6, 6, 5, 3, 3, 4, 4,
+     3, 1, 2, 4, 4, 2, 2, 4, 1, 4, 3, 1, 2, 2];
+
+pub static GREEK_CHARS: [u16; 32] =
+    [0x03AC, 0x03AD, 0x03AE, 0x03AF, 0x03B1, 0x03B2, 0x03B3, 0x03B4, 0x03B5, 0x03B6, 0x03B7,
+     0x03B8, 0x03B9, 0x03BA, 0x03BB, 0x03BC, 0x03BD, 0x03BE, 0x03BF, 0x03C0, 0x03C1, 0x03C2,
+     0x03C3, 0x03C4, 0x03C5, 0x03C6, 0x03C7, 0x03C8, 0x03C9, 0x03CC, 0x03CD, 0x03CE];
+
+pub static GREEK_WEIGHTS: [u8; 32] =
+    [5, 5, 5, 5, 7, 3, 5, 5, 7, 2, 6, 4, 6, 6, 5, 6, 6, 2, 7, 6, 6, 5, 6, 7,
+     5, 4, 4, 1, 4, 5, 4, 3];
+
+pub static HEBREW_CHARS: [u16; 27] =
+    [0x05D0, 0x05D1, 0x05D2, 0x05D3, 0x05D4, 0x05D5, 0x05D6, 0x05D7, 0x05D8, 0x05D9, 0x05DA,
+     0x05DB, 0x05DC, 0x05DD, 0x05DE, 0x05DF, 0x05E0, 0x05E1, 0x05E2, 0x05E3, 0x05E4, 0x05E5,
+     0x05E6, 0x05E7, 0x05E8, 0x05E9, 0x05EA];
+
+pub static HEBREW_WEIGHTS: [u8; 27] =
+    [6, 6, 5, 5, 7, 7, 4, 5, 5, 7, 3, 4, 7, 5, 7, 4, 6, 5, 5, 1, 5, 3, 4, 6,
+     6, 6, 7];
+
+pub static ARABIC_CHARS: [u16; 40] =
+    [0x0621, 0x0622, 0x0623, 0x0625, 0x0626, 0x0627, 0x0628, 0x0629, 0x062A, 0x062B, 0x062C,
+     0x062D, 0x062E, 0x062F, 0x0630, 0x0631, 0x0632, 0x0633, 0x0634, 0x0635, 0x0636, 0x0637,
+     0x0638, 0x0639, 0x063A, 0x0641, 0x0642, 0x0643, 0x0644, 0x0645, 0x0646, 0x0647, 0x0648,
+     0x0649, 0x064A, 0x0651, 0x067E, 0x0686, 0x06A9, 0x06AF];
+
+pub static ARABIC_WEIGHTS: [u8; 40] =
+    [1, 1, 3, 3, 2, 8, 6, 5, 6, 2, 4, 4, 4, 6, 3, 7, 4, 6, 5, 4, 3, 4, 2, 5,
+     3, 5, 4, 4, 7, 6, 7, 6, 6, 2, 6, 3, 4, 1, 4, 3];
+
+pub static THAI_CHARS: [u16; 51] =
+    [0x0E01, 0x0E02, 0x0E04, 0x0E07, 0x0E08, 0x0E0A, 0x0E0B, 0x0E0D, 0x0E10, 0x0E13, 0x0E14,
+     0x0E15, 0x0E16, 0x0E17, 0x0E18, 0x0E19, 0x0E1A, 0x0E1B, 0x0E1C, 0x0E1E, 0x0E1F, 0x0E20,
+     0x0E21, 0x0E22, 0x0E23, 0x0E25, 0x0E27, 0x0E29, 0x0E2A, 0x0E2B, 0x0E2D, 0x0E2E, 0x0E30,
+     0x0E31, 0x0E32, 0x0E33, 0x0E34, 0x0E35, 0x0E36, 0x0E37, 0x0E38, 0x0E39, 0x0E40, 0x0E41,
+     0x0E42, 0x0E43, 0x0E44, 0x0E47, 0x0E48, 0x0E49, 0x0E4C];
+
+pub static THAI_WEIGHTS: [u8; 51] =
+    [6, 5, 4, 5, 4, 4, 3, 1, 1, 3, 5, 5, 3, 5, 2, 6, 5, 4, 2, 4, 3, 2, 6, 5,
+     6, 5, 5, 2, 5, 4, 6, 1, 4, 5, 7, 4, 5, 5, 2, 4, 3, 4, 6, 5, 4, 4, 5, 3,
+     6, 6, 4];
+
+pub static JAPANESE_CHARS: [u16; 833] =
+    [0x3005, 0x3041, 0x3042, 0x3044, 0x3046, 0x3048, 0x304A, 0x304B, 0x304C, 0x304D, 0x304E,
+     0x304F, 0x3050, 0x3051, 0x3052, 0x3053, 0x3054, 0x3055, 0x3056, 0x3057, 0x3058, 0x3059,
+     0x305A, 0x305B, 0x305D, 0x305E, 0x305F, 0x3060, 0x3061, 0x3063, 0x3064, 0x3065, 0x3066,
+     0x3067, 0x3068, 0x3069, 0x306A, 0x306B, 0x306C, 0x306E, 0x306F, 0x3070, 0x3071, 0x3072,
+     0x3073, 0x3075, 0x3076, 0x3078, 0x3079, 0x307B, 0x307E, 0x307F, 0x3080, 0x3081, 0x3082,
+     0x3084, 0x3087, 0x3088, 0x3089, 0x308A, 0x308B, 0x308C, 0x308D, 0x308F, 0x3092, 0x3093,
+     0x30A1, 0x30A2, 0x30A3, 0x30A4, 0x30A5, 0x30A6, 0x30A7, 0x30A8, 0x30A9, 0x30AA, 0x30AB,
+     0x30AC, 0x30AD, 0x30AE, 0x30AF, 0x30B0, 0x30B1, 0x30B2, 0x30B3, 0x30B4, 0x30B5, 0x30B6,
+     0x30B7, 0x30B8, 0x30B9, 0x30BA, 0x30BB, 0x30BC, 0x30BD, 0x30BE, 0x30BF, 0x30C0, 0x30C1,
+     0x30C3, 0x30C4, 0x30C6, 0x30C7, 0x30C8, 0x30C9, 0x30CA, 0x30CB, 0x30CC, 0x30CD, 0x30CE,
+     0x30CF, 0x30D0, 0x30D1, 0x30D2, 0x30D3, 0x30D4, 0x30D5, 0x30D6, 0x30D7, 0x30D8, 0x30D9,
+     0x30DA, 0x30DB, 0x30DC, 0x30DD, 0x30DE, 0x30DF, 0x30E0, 0x30E1, 0x30E2, 0x30E3, 0x30E4,
+     0x30E5, 0x30E6, 0x30E7, 0x30E8, 0x30E9, 0x30EA, 0x30EB, 0x30EC, 0x30ED, 0x30EF, 0x30F3,
+     0x30F4, 0x30FC, 0x4E00, 0x4E09, 0x4E0A, 0x4E0B, 0x4E0D, 0x4E0E, 0x4E16, 0x4E21, 0x4E26,
+     0x4E2D, 0x4E38, 0x4E3B, 0x4E45, 0x4E57, 0x4E71, 0x4E86, 0x4E88, 0x4E8B, 0x4E8C, 0x4E92,
+     0x4EA4, 0x4EBA, 0x4ECA, 0x4ED5, 0x4ED6, 0x4ED8, 0x4EE3, 0x4EE4, 0x4EE5, 0x4EEE, 0x4EF6,
+     0x4EFB, 0x4F1A, 0x4F1D, 0x4F34, 0x4F38, 0x4F3C, 0x4F4D, 0x4F4E, 0x4F53, 0x4F55, 0x4F59,
+     0x4F5C, 0x4F75, 0x4F7F, 0x4F8B, 0x4F9B, 0x4F9D, 0x4FA1, 0x4FC2, 0x4FC3, 0x4FDD, 0x4FE1,
+     0x4FEE, 0x500B, 0x500D, 0x5019, 0x5024, 0x505C, 0x5065, 0x5074, 0x5076, 0x507D, 0x5099,
+     0x50CD, 0x50CF, 0x512A, 0x5143, 0x5145, 0x5148, 0x5165, 0x5168, 0x516C, 0x516D, 0x5171,
+     0x5185, 0x518D, 0x5197, 0x5199, 0x51CD, 0x51E6, 0x51FA, 0x5206, 0x5207, 0x5217, 0x521D,
+     0x5224, 0x5225, 0x5229, 0x5230, 0x5236, 0x5237, 0x523B, 0x5247, 0x524A, 0x524D, 0x526F,
+     0x5270, 0x5272, 0x529B, 0x529F, 0x52A0, 0x52A9, 0x52B9, 0x52D5, 0x5305, 0x5316, 0x5317,
+     0x533A, 0x533F, 0x5341, 0x5348, 0x534A, 0x5357, 0x5358, 0x5360, 0x5370, 0x5371, 0x5373,
+     0x5374, 0x539F, 0x53B3, 0x53BB, 0x53C2, 0x53CA, 0x53CD, 0x53CE, 0x53D6, 0x53D7, 0x53E4,
+     0x53E5, 0x53EF, 0x53F0, 0x53F3, 0x53F7, 0x5404, 0x5408, 0x540C, 0x540D, 0x5411, 0x5426,
+     0x542B, 0x544A, 0x5468, 0x5473, 0x547C, 0x547D, 0x548C, 0x54C1, 0x554F, 0x5668, 0x56DE,
+     0x56E0, 0x56F2, 0x56F3, 0x56FA, 0x56FD, 0x571F, 0x5727, 0x5728, 0x5730, 0x5747, 0x5782,
+     0x578B, 0x57CB, 0x57DF, 0x57FA, 0x5831, 0x5834, 0x584A, 0x5883, 0x5897, 0x58CA, 0x5909,
+     0x5916, 0x591A, 0x5927, 0x592A, 0x592E, 0x5931, 0x5947, 0x594F, 0x5968, 0x59A5, 0x59CB,
+     0x5B50, 0x5B57, 0x5B58, 0x5B64, 0x5B66, 0x5B88, 0x5B89, 0x5B8C, 0x5B9A, 0x5B9B, 0x5B9F,
+     0x5BA3, 0x5BB3, 0x5BB9, 0x5BC4, 0x5BC6, 0x5BFE, 0x5C01, 0x5C02, 0x5C0F, 0x5C11, 0x5C3D,
+     0x5C3E, 0x5C40, 0x5C55, 0x5C5E, 0x5C64, 0x5C65, 0x5C71, 0x5C90, 0x5CF6, 0x5DDE, 0x5DE5,
+     0x5DE6, 0x5DEE, 0x5DF1, 0x5DFB, 0x5E02, 0x5E03, 0x5E2F, 0x5E30, 0x5E38, 0x5E45, 0x5E55,
+     0x5E73, 0x5E74, 0x5E83, 0x5E8F, 0x5E95, 0x5EA6, 0x5EA7, 0x5EAB, 0x5EC3, 0x5EF6, 0x5F0F,
+     0x5F15, 0x5F27, 0x5F31, 0x5F35, 0x5F37, 0x5F53, 0x5F62, 0x5F71, 0x5F79, 0x5F85, 0x5F8C,
+     0x5F93, 0x5F97, 0x5FA1, 0x5FA9, 0x5FAA, 0x5FC5, 0x5FDC, 0x5FF5, 0x601D, 0x6025, 0x6027,
+     0x6050, 0x60C5, 0x60F3, 0x610F, 0x611F, 0x614B, 0x616E, 0x61B6, 0x6210, 0x623B, 0x6240,
+     0x624B, 0x6253, 0x6271, 0x627F, 0x6280, 0x6291, 0x6298, 0x629C, 0x629E, 0x62BC, 0x62BD,
+     0x62C5, 0x62D2, 0x62E1, 0x62EC, 0x6301, 0x6307, 0x6319, 0x632F, 0x633F, 0x6349, 0x6355,
+     0x6357, 0x6368, 0x6383, 0x6392, 0x63A2, 0x63A5, 0x63A8, 0x63C3, 0x63CF, 0x63D0, 0x63DB,
+     0x640D, 0x642C, 0x64CD, 0x6539, 0x653E, 0x6557, 0x6570, 0x6574, 0x6587, 0x65AD, 0x65B0,
+     0x65B9, 0x65BD, 0x65CF, 0x65E2, 0x65E5, 0x65E7, 0x65E9, 0x6607, 0x660E, 0x6627, 0x6642,
+     0x666E, 0x666F, 0x6697, 0x66D6, 0x66DC, 0x66F2, 0x66F4, 0x66F8, 0x66FF, 0x6700, 0x6708,
+     0x6709, 0x671B, 0x671D, 0x671F, 0x672A, 0x672B, 0x672C, 0x6761, 0x6765, 0x676F, 0x6771,
+     0x6790, 0x679C, 0x67A0, 0x67FB, 0x683C, 0x6841, 0x6848, 0x68C4, 0x691C, 0x696D, 0x6975,
+     0x69CB, 0x69D8, 0x6A19, 0x6A29, 0x6A2A, 0x6A5F, 0x6B20, 0x6B21, 0x6B27, 0x6B62, 0x6B63,
+     0x6B74, 0x6B8A, 0x6B8B, 0x6BB5, 0x6BCE, 0x6BD4, 0x6C11, 0x6C17, 0x6C34, 0x6C38, 0x6C42,
+     0x6C7A, 0x6CC1, 0x6CD5, 0x6CE8, 0x6D3B, 0x6D3E, 0x6D6E, 0x6D77, 0x6D88, 0x6DF1, 0x6DF7,
+     0x6DFB, 0x6E08, 0x6E1B, 0x6E21, 0x6E2C, 0x6E7E, 0x6E80, 0x6E90, 0x6E96, 0x6EA2, 0x6EC5,
+     0x6F14, 0x6F5C, 0x70B9, 0x7121, 0x7136, 0x7167, 0x7247, 0x7248, 0x7269, 0x7279, 0x72B6,
+     0x72EC, 0x7372, 0x7387, 0x738B, 0x73FE, 0x7406, 0x74B0, 0x751F, 0x7528, 0x7531, 0x753B,
+     0x754C, 0x7559, 0x7565, 0x756A, 0x7570, 0x7573, 0x7591, 0x767A, 0x767B, 0x767D, 0x7684,
+     0x76E3, 0x76EE, 0x76F4, 0x76F8, 0x76FE, 0x7701, 0x771F, 0x7740, 0x77DB, 0x77E2, 0x77E5,
+     0x77E9, 0x77ED, 0x7834, 0x78BA, 0x793A, 0x793E, 0x7981, 0x79D2, 0x79D8, 0x79F0, 0x79FB,
+     0x7A0B, 0x7A2E, 0x7A3C, 0x7A4D, 0x7A76, 0x7A7A, 0x7A81, 0x7ACB, 0x7AEF, 0x7AF6, 0x7B26,
+     0x7B2C, 0x7B49, 0x7B52, 0x7B54, 0x7B87, 0x7B97, 0x7BA1, 0x7BB1, 0x7BC0, 0x7BC4, 0x7BC9,
+     0x7C73, 0x7CBE, 0x7CFB, 0x7D04, 0x7D0D, 0x7D14, 0x7D19, 0x7D20, 0x7D22, 0x7D2F, 0x7D30,
+     0x7D42, 0x7D44, 0x7D4C, 0x7D50, 0x7D61, 0x7D71, 0x7D76, 0x7D99, 0x7D9A, 0x7DAD, 0x7DCF,
+     0x7DD2, 0x7DDA, 0x7DE8, 0x7DE9, 0x7E01, 0x7E26, 0x7E2E, 0x7E70, 0x7F6B, 0x7F6E, 0x7F72,
+     0x7FA4, 0x7FA9, 0x7FFB, 0x8003, 0x8005, 0x80A2, 0x80CC, 0x80FD, 0x8108, 0x81EA, 0x81F4,
+     0x822C, 0x826F, 0x8272, 0x82F1, 0x8377, 0x843D, 0x8457, 0x8846, 0x884C, 0x8853, 0x885D,
+     0x8868, 0x88AB, 0x88C5, 0x88DC, 0x88FD, 0x8907, 0x897F, 0x8981, 0x898B, 0x898F, 0x8996,
+     0x89A7, 0x89AA, 0x89B3, 0x89D2, 0x89E3, 0x8A00, 0x8A08, 0x8A0E, 0x8A18, 0x8A2A, 0x8A2D,
+     0x8A31, 0x8A33, 0x8A3A, 0x8A3C, 0x8A55, 0x8A66, 0x8A70, 0x8A71, 0x8A72, 0x8A73, 0x8A8D,
+     0x8A9E, 0x8AA4, 0x8AAC, 0x8AAD, 0x8ABF, 0x8AD6, 0x8AF8, 0x8B58, 0x8B66, 0x8B77, 0x8C61,
+     0x8CA0, 0x8CA8, 0x8CAB, 0x8CBB, 0x8CBC, 0x8CC7, 0x8CEA, 0x8D64, 0x8D70, 0x8D77, 0x8D85,
+     0x8D8A, 0x8DB3, 0x8DDD, 0x8DE1, 0x8DEF, 0x8EAB, 0x8EE2, 0x8F03, 0x8F09, 0x8F9E, 0x8FBA,
+     0x8FBC, 0x8FBF, 0x8FD1, 0x8FD4, 0x8FF0, 0x8FFD, 0x9000, 0x9001, 0x9006, 0x900F, 0x9014,
+     0x901A, 0x901F, 0x9020, 0x9023, 0x9031, 0x9032, 0x9045, 0x9047, 0x904E, 0x9053, 0x9054,
+     0x9055, 0x9060, 0x9063, 0x9069, 0x906D, 0x9077, 0x9078, 0x907F, 0x90A6, 0x90E8, 0x914D,
+     0x91C8, 0x91CD, 0x91CF, 0x91D1, 0x91E3, 0x9332, 0x9375, 0x9577, 0x9589, 0x958B, 0x9593,
+     0x95A2, 0x9632, 0x963B, 0x964D, 0x9650, 0x9664, 0x967A, 0x968E, 0x9694, 0x9699, 0x969B,
+     0x969C, 0x96A0, 0x96A3, 0x96C6, 0x96D1, 0x96E2, 0x96FB, 0x9759, 0x975E, 0x9762, 0x97D3,
+     0x97F3, 0x97FF, 0x9805, 0x9806, 0x9808, 0x9818, 0x982D, 0x983B, 0x983C, 0x984C, 0x9858,
+     0x985E, 0x98DB, 0x98FE, 0x9A13, 0x9AD8, 0x9BAE, 0x9CF4, 0x9ED9];
+
+pub static JAPANESE_WEIGHTS: [u8; 833] =
+    [3, 1, 8, 8, 8, 8, 7, 8, 8, 8, 7, 8, 3, 8, 3, 8, 4, 8, 1, 8, 7, 8, 6, 8,
+     7, 3, 8, 8, 6, 8, 8, 3, 8, 8, 8, 7, 8, 8, 3, 8, 8, 8, 1, 2, 7, 1, 1, 7,
+     6, 3, 8, 8, 6, 8, 8, 6, 4, 8, 8, 8, 8, 8, 4, 8, 8, 8, 8, 8, 8, 8, 5, 8,
+     8, 8, 8, 8, 8, 7, 8, 5, 8, 8, 8, 6, 8, 6, 8, 8, 8, 8, 8, 8, 8, 5, 8, 5,
+     8, 8, 8, 8, 6, 8, 8, 8, 8, 8, 7, 4, 7, 6, 7, 8, 8, 5, 8, 7, 8, 8, 8, 7,
+     8, 7, 6, 8, 8, 8, 7, 8, 8, 8, 8, 5, 8, 8, 8, 3, 8, 8, 8, 8, 8, 7, 8, 5,
+     8, 8, 1, 7, 7, 8, 5, 1, 5, 5, 8, 1, 4, 1, 2, 2, 8, 6, 3, 5, 5, 2, 4, 4,
+     2, 6, 7, 6, 6, 7, 5, 5, 3, 2, 2, 2, 2, 4, 7, 4, 5, 5, 4, 8, 4, 8, 5, 4,
+     5, 4, 5, 1, 7, 7, 5, 6, 4, 3, 8, 5, 1, 5, 2, 3, 5, 3, 6, 5, 6, 2, 7, 8,
+     7, 4, 2, 7, 8, 8, 3, 2, 1, 6, 8, 7, 7, 8, 6, 2, 6, 6, 3, 7, 4, 5, 2, 8,
+     8, 5, 1, 6, 8, 4, 7, 3, 8, 8, 2, 7, 3, 6, 2, 4, 1, 1, 4, 6, 1, 5, 2, 1,
+     4, 3, 2, 4, 7, 3, 4, 3, 8, 6, 6, 5, 8, 1, 5, 8, 5, 8, 7, 8, 5, 5, 7, 7,
+     4, 4, 5, 6, 6, 1, 7, 2, 6, 3, 7, 2, 4, 6, 1, 6, 8, 2, 2, 3, 8, 4, 5, 5,
+     7, 8, 1, 6, 4, 5, 8, 8, 6, 7, 1, 1, 8, 1, 1, 3, 2, 7, 8, 8, 8, 1, 2, 1,
+     4, 6, 8, 2, 8, 4, 3, 6, 2, 5, 8, 3, 4, 7, 4, 1, 5, 3, 5, 6, 4, 3, 1, 2,
+     4, 4, 1, 5, 4, 2, 2, 1, 2, 1, 5, 6, 6, 1, 4, 3, 1, 5, 1, 6, 1, 5, 3, 4,
+     8, 8, 3, 2, 6, 5, 7, 7, 4, 1, 6, 7, 3, 7, 5, 5, 3, 8, 6, 1, 1, 1, 7, 1,
+     7, 6, 6, 1, 6, 1, 1, 8, 5, 7, 5, 2, 6, 4, 1, 3, 4, 2, 7, 4, 4, 1, 5, 6,
+     3, 6, 8, 3, 1, 5, 1, 1, 2, 1, 1, 3, 5, 7, 4, 2, 5, 5, 7, 4, 1, 6, 5, 3,
+     8, 8, 6, 8, 4, 8, 7, 1, 5, 6, 6, 5, 2, 3, 8, 3, 8, 2, 4, 6, 3, 3, 2, 8,
+     8, 5, 8, 4, 8, 2, 1, 7, 6, 6, 5, 5, 5, 1, 3, 4, 6, 1, 5, 5, 4, 2, 4, 7,
+     3, 3, 6, 5, 7, 6, 2, 6, 3, 6, 1, 6, 8, 3, 3, 4, 3, 3, 5, 3, 1, 4, 3, 6,
+     6, 3, 6, 5, 2, 2, 5, 1, 4, 4, 3, 3, 5, 3, 4, 2, 1, 2, 2, 7, 2, 2, 6, 1,
+     5, 8, 2, 7, 1, 3, 3, 6, 6, 3, 2, 3, 2, 7, 7, 5, 8, 8, 4, 7, 4, 3, 5, 7,
+     6, 3, 3, 7, 4, 5, 7, 4, 7, 5, 5, 2, 5, 3, 1, 2, 4, 5, 1, 4, 5, 6, 8, 1,
+     3, 6, 5, 2, 6, 1, 5, 3, 3, 1, 7, 4, 3, 6, 5, 5, 4, 5, 3, 5, 2, 7, 5, 2,
+     1, 7, 4, 1, 4, 2, 7, 4, 3, 2, 6, 7, 2, 5, 8, 5, 4, 6, 1, 5, 4, 5, 7, 1,
+     3, 3, 5, 5, 1, 1, 2, 6, 4, 1, 8, 7, 2, 7, 5, 3, 6, 2, 4, 8, 2, 6, 7, 3,
+     2, 5, 4, 1, 3, 1, 1, 8, 2, 3, 8, 3, 5, 5, 4, 7, 2, 8, 7, 5, 7, 7, 5, 1,
+     2, 6, 6, 6, 3, 6, 1, 8, 6, 5, 2, 8, 4, 5, 4, 3, 3, 5, 8, 8, 5, 4, 8, 4,
+     4, 3, 7, 7, 3, 5, 4, 1, 3, 1, 1, 2, 2, 1, 1, 6, 5, 3, 5, 1, 3, 3, 4, 4,
+     5, 1, 6, 3, 8, 2, 3, 7, 5, 7, 3, 6, 4, 2, 3, 6, 3, 4, 5, 3, 6, 5, 1, 6,
+     1, 4, 6, 2, 1, 6, 1, 3, 7, 4, 2, 7, 7, 5, 6, 5, 1, 1, 5, 8, 7, 5, 8, 8,
+     8, 2, 2, 3, 7, 8, 2, 2, 4, 1, 6, 2, 3, 2, 7, 1, 3, 4, 2, 6, 3, 1, 4, 4,
+     6, 6, 4, 5, 5, 1, 3, 5, 1, 5, 2, 3, 1, 5, 1, 2, 4];
+
+pub static SIMPLIFIED_CHINESE_CHARS: [u16; 1383] =
+    [0x4E00, 0x4E01, 0x4E07, 0x4E09, 0x4E0A, 0x4E0B, 0x4E0D, 0x4E0E, 0x4E13, 0x4E14, 0x4E16,
+     0x4E18, 0x4E1A, 0x4E1C, 0x4E22, 0x4E24, 0x4E25, 0x4E2A, 0x4E2D, 0x4E30, 0x4E32, 0x4E34,
+     0x4E39, 0x4E3A, 0x4E3B, 0x4E3E, 0x4E45, 0x4E48, 0x4E49, 0x4E4B, 0x4E4C, 0x4E4E, 0x4E4F,
+     0x4E50, 0x4E54, 0x4E58, 0x4E5D, 0x4E5F, 0x4E60, 0x4E66, 0x4E70, 0x4E86, 0x4E88, 0x4E8B,
+     0x4E8C, 0x4E8E, 0x4E92, 0x4E94, 0x4E9A, 0x4E9B, 0x4EA1, 0x4EA4, 0x4EA6, 0x4EA7, 0x4EAB,
+     0x4EAC, 0x4EAE, 0x4EB5, 0x4EBA, 0x4EC0, 0x4EC5, 0x4ECB, 0x4ECD, 0x4ECE, 0x4ED3, 0x4ED6,
+     0x4EE3, 0x4EE4, 0x4EE5, 0x4EEC, 0x4EF6, 0x4EF7, 0x4EFB, 0x4EFD, 0x4EFF, 0x4F0A, 0x4F0D,
+     0x4F10, 0x4F11, 0x4F18, 0x4F1A, 0x4F20, 0x4F26, 0x4F2A, 0x4F2F, 0x4F30, 0x4F38, 0x4F3C,
+     0x4F46, 0x4F4D, 0x4F4E, 0x4F4F, 0x4F50, 0x4F53, 0x4F55, 0x4F59, 0x4F5B, 0x4F5C, 0x4F60,
+     0x4F69, 0x4F73, 0x4F7F, 0x4F8B, 0x4F9B, 0x4F9D, 0x4FA7, 0x4FBF, 0x4FC4, 0x4FD7, 0x4FDD,
+     0x4FE1, 0x4FEE, 0x4FFE, 0x500D, 0x5012, 0x5019, 0x501A, 0x501F, 0x503C, 0x5047, 0x504F,
+     0x505A, 0x505C, 0x5065, 0x5076, 0x50A8, 0x50CF, 0x50E7, 0x513F, 0x5141, 0x5143, 0x5145,
+     0x5148, 0x5149, 0x514B, 0x514D, 0x5165, 0x5168, 0x516B, 0x516C, 0x516D, 0x5170, 0x5171,
+     0x5173, 0x5176, 0x5177, 0x5178, 0x5179, 0x517C, 0x5185, 0x5188, 0x518C, 0x518D, 0x5192,
+     0x5197, 0x5199, 0x519C, 0x51B0, 0x51B2, 0x51B3, 0x51B5, 0x51BB, 0x51C0, 0x51C6, 0x51CF,
+     0x51D1, 0x51E0, 0x51E1, 0x51ED, 0x51EF, 0x51FA, 0x51FB, 0x51FD, 0x5206, 0x5207, 0x5212,
+     0x5217, 0x5219, 0x521A, 0x521B, 0x521D, 0x5220, 0x5224, 0x5229, 0x522B, 0x5230, 0x5236,
+     0x5237, 0x523B, 0x524D, 0x5254, 0x5256, 0x5265, 0x5269, 0x526A, 0x526F, 0x5272, 0x529B,
+     0x529E, 0x529F, 0x52A0, 0x52A1, 0x52A8, 0x52A9, 0x52AA, 0x52B3, 0x52BF, 0x52C3, 0x52C9,
+     0x52D2, 0x52FF, 0x5305, 0x5308, 0x5316, 0x5317, 0x5319, 0x5339, 0x533A, 0x533F, 0x5341,
+     0x5343, 0x5347, 0x5348, 0x534A, 0x534E, 0x534F, 0x5355, 0x5356, 0x5357, 0x535A, 0x535C,
+     0x5360, 0x5361, 0x5362, 0x5370, 0x5371, 0x5373, 0x5374, 0x5377, 0x5378, 0x5382, 0x5384,
+     0x5386, 0x538B, 0x539F, 0x53BB, 0x53BF, 0x53C2, 0x53C8, 0x53C9, 0x53CA, 0x53CC, 0x53CD,
+     0x53D1, 0x53D6, 0x53D7, 0x53D8, 0x53D9, 0x53E0, 0x53E3, 0x53E4, 0x53E5, 0x53E6, 0x53EA,
+     0x53EF, 0x53F0, 0x53F2, 0x53F3, 0x53F6, 0x53F7, 0x5404, 0x5408, 0x5409, 0x540A, 0x540C,
+     0x540D, 0x540E, 0x5411, 0x5417, 0x5426, 0x542B, 0x542C, 0x542F, 0x544A, 0x5458, 0x5468,
+     0x5473, 0x547D, 0x548C, 0x54AC, 0x54C1, 0x54C7, 0x54C8, 0x54CD, 0x54E5, 0x54EA, 0x552F,
+     0x5546, 0x5570, 0x5580, 0x559C, 0x55E6, 0x5609, 0x5668, 0x56DB, 0x56DE, 0x56E0, 0x56F4,
+     0x56FA, 0x56FD, 0x56FE, 0x5706, 0x571F, 0x5723, 0x5728, 0x572D, 0x5730, 0x573A, 0x573E,
+     0x5740, 0x5747, 0x574E, 0x574F, 0x5757, 0x5761, 0x5766, 0x5782, 0x5783, 0x578B, 0x57C3,
+     0x57CE, 0x57DF, 0x57FA, 0x5806, 0x5821, 0x5854, 0x585E, 0x586B, 0x5883, 0x589E, 0x58A8,
+     0x58EB, 0x58F0, 0x5904, 0x5907, 0x590D, 0x590F, 0x5916, 0x591A, 0x591F, 0x5927, 0x5929,
+     0x592A, 0x592B, 0x5931, 0x5934, 0x5937, 0x5938, 0x5939, 0x5947, 0x5948, 0x594E, 0x5957,
+     0x5965, 0x5974, 0x5978, 0x597D, 0x5982, 0x59C6, 0x59CB, 0x59D3, 0x5A01, 0x5A92, 0x5AE9,
+     0x5B50, 0x5B54, 0x5B57, 0x5B58, 0x5B5F, 0x5B63, 0x5B64, 0x5B66, 0x5B81, 0x5B83, 0x5B88,
+     0x5B89, 0x5B8C, 0x5B8F, 0x5B97, 0x5B98, 0x5B9A, 0x5B9B, 0x5B9C, 0x5B9E, 0x5BA1, 0x5BA2,
+     0x5BA3, 0x5BB6, 0x5BB9, 0x5BBD, 0x5BBE, 0x5BBF, 0x5BC4, 0x5BC6, 0x5BCC, 0x5BDF, 0x5BF8,
+     0x5BF9, 0x5BFB, 0x5BFC, 0x5C01, 0x5C04, 0x5C06, 0x5C0F, 0x5C11, 0x5C14, 0x5C16, 0x5C1A,
+     0x5C1D, 0x5C24, 0x5C31, 0x5C38, 0x5C3A, 0x5C3C, 0x5C3D, 0x5C3E, 0x5C40, 0x5C42, 0x5C45,
+     0x5C4F, 0x5C55, 0x5C5E, 0x5C71, 0x5C9B, 0x5CB8, 0x5CF0, 0x5D29, 0x5D4C, 0x5DDD, 0x5DDE,
+     0x5DE5, 0x5DE6, 0x5DEE, 0x5DF1, 0x5DF2, 0x5DF4, 0x5E01, 0x5E02, 0x5E03, 0x5E0C, 0x5E10,
+     0x5E15, 0x5E26, 0x5E27, 0x5E2E, 0x5E38, 0x5E42, 0x5E45, 0x5E55, 0x5E72, 0x5E73, 0x5E74,
+     0x5E76, 0x5E7B, 0x5E7F, 0x5E8F, 0x5E93, 0x5E94, 0x5E95, 0x5E9C, 0x5E9F, 0x5EA6, 0x5EA7,
+     0x5EB7, 0x5EF6, 0x5EF7, 0x5EFA, 0x5F00, 0x5F02, 0x5F03, 0x5F0F, 0x5F15, 0x5F17, 0x5F20,
+     0x5F27, 0x5F31, 0x5F39, 0x5F3A, 0x5F52, 0x5F53, 0x5F55, 0x5F62, 0x5F69, 0x5F6D, 0x5F71,
+     0x5F7B, 0x5F7C, 0x5F80, 0x5F81, 0x5F84, 0x5F85, 0x5F88, 0x5F8B, 0x5F97, 0x5FAA, 0x5FAE,
+     0x5FB7, 0x5FC3, 0x5FC5, 0x5FC6, 0x5FD7, 0x5FD8, 0x5FD9, 0x5FEB, 0x5FF5, 0x5FFD, 0x6000,
+     0x6001, 0x601D, 0x6025, 0x6027, 0x602A, 0x603B, 0x6052, 0x6062, 0x6069, 0x606F, 0x6070,
+     0x60A8, 0x60AC, 0x60C5, 0x60E0, 0x60EF, 0x60F3, 0x610F, 0x611F, 0x613F, 0x6162, 0x6208,
+     0x620F, 0x6210, 0x6211, 0x6216, 0x622A, 0x6233, 0x6234, 0x6237, 0x623F, 0x6240, 0x6247,
+     0x624B, 0x624D, 0x624E, 0x6253, 0x6258, 0x6267, 0x6269, 0x626B, 0x626C, 0x6279, 0x627E,
+     0x627F, 0x6280, 0x628A, 0x6291, 0x6293, 0x6297, 0x6298, 0x62A4, 0x62A5, 0x62B1, 0x62BD,
+     0x62C5, 0x62C6, 0x62C9, 0x62CD, 0x62D2, 0x62D3, 0x62D6, 0x62DC, 0x62DF, 0x62E3, 0x62E5,
+     0x62E9, 0x62EC, 0x62F7, 0x62FC, 0x62FF, 0x6301, 0x6302, 0x6307, 0x6309, 0x6311, 0x631D,
+     0x6324, 0x632A, 0x6346, 0x6349, 0x6355, 0x635F, 0x6362, 0x636E, 0x6377, 0x6388, 0x6389,
+     0x6392, 0x63A2, 0x63A5, 0x63A7, 0x63A8, 0x63A9, 0x63CF, 0x63D0, 0x63D2, 0x63E1, 0x641C,
+     0x642D, 0x643A, 0x6444, 0x6458, 0x6469, 0x6478, 0x6492, 0x6495, 0x649E, 0x64A4, 0x64AD,
+     0x64CD, 0x64CE, 0x64E6, 0x652F, 0x6536, 0x6539, 0x653E, 0x653F, 0x6545, 0x6548, 0x654F,
+     0x6559, 0x6563, 0x6566, 0x6570, 0x6574, 0x6587, 0x6590, 0x6599, 0x659C, 0x65A5, 0x65AD,
+     0x65AF, 0x65B0, 0x65B9, 0x65BD, 0x65C1, 0x65CB, 0x65CF, 0x65D7, 0x65E0, 0x65E2, 0x65E5,
+     0x65E6, 0x65E7, 0x65E9, 0x65F6, 0x65FA, 0x6602, 0x6606, 0x660E, 0x6613, 0x661F, 0x6620,
+     0x662F, 0x663E, 0x665A, 0x666E, 0x666F, 0x667A, 0x6682, 0x6697, 0x66B4, 0x66F2, 0x66F3,
+     0x66F4, 0x66FC, 0x66FE, 0x66FF, 0x6700, 0x6708, 0x6709, 0x670D, 0x6717, 0x671B, 0x671D,
+     0x671F, 0x6728, 0x672A, 0x672B, 0x672C, 0x672F, 0x6731, 0x673A, 0x6740, 0x6742, 0x6743,
+     0x6746, 0x6751, 0x675C, 0x675F, 0x6760, 0x6761, 0x6765, 0x6770, 0x677E, 0x677F, 0x6781,
+     0x6784, 0x6790, 0x6797, 0x679A, 0x679C, 0x67B6, 0x67C4, 0x67CF, 0x67D0, 0x67D3, 0x67E5,
+     0x67EF, 0x67F3, 0x6807, 0x6808, 0x680F, 0x6811, 0x6821, 0x6837, 0x6838, 0x6839, 0x683C,
+     0x6846, 0x6848, 0x684C, 0x6851, 0x6863, 0x6885, 0x68AF, 0x68B5, 0x68C0, 0x68C9, 0x68EE,
+     0x690D, 0x695A, 0x6960, 0x6982, 0x69FD, 0x6A21, 0x6A2A, 0x6B21, 0x6B22, 0x6B27, 0x6B32,
+     0x6B3E, 0x6B49, 0x6B4C, 0x6B62, 0x6B63, 0x6B64, 0x6B65, 0x6B66, 0x6B67, 0x6B7B, 0x6B8A,
+     0x6BB5, 0x6BC1, 0x6BCD, 0x6BCF, 0x6BD4, 0x6BD5, 0x6BDB, 0x6BEB, 0x6C11, 0x6C14, 0x6C34,
+     0x6C38, 0x6C42, 0x6C47, 0x6C49, 0x6C57, 0x6C5F, 0x6C60, 0x6C64, 0x6C76, 0x6C83, 0x6C99,
+     0x6CA1, 0x6CB3, 0x6CBB, 0x6CCA, 0x6CD5, 0x6CE1, 0x6CE2, 0x6CE8, 0x6CF0, 0x6CFD, 0x6D0B,
+     0x6D1B, 0x6D1E, 0x6D25, 0x6D2A, 0x6D32, 0x6D3B, 0x6D3E, 0x6D41, 0x6D45, 0x6D4B, 0x6D4E,
+     0x6D4F, 0x6D66, 0x6D6A, 0x6D6E, 0x6D77, 0x6D85, 0x6D88, 0x6D89, 0x6DEB, 0x6DF1, 0x6DF7,
+     0x6DFB, 0x6E05, 0x6E0E, 0x6E10, 0x6E20, 0x6E21, 0x6E29, 0x6E2F, 0x6E32, 0x6E38, 0x6E56,
+     0x6E7E, 0x6E83, 0x6E90, 0x6EA2, 0x6EAF, 0x6ED1, 0x6ED5, 0x6EDA, 0x6EDE, 0x6EE1, 0x6EE4,
+     0x6EE8, 0x6F0F, 0x6F14, 0x6F58, 0x6F5C, 0x6FB3, 0x6FC0, 0x706F, 0x7075, 0x70B9, 0x70C1,
+     0x70C8, 0x70ED, 0x7126, 0x7136, 0x7167, 0x722A, 0x7231, 0x7236, 0x7247, 0x7248, 0x724C,
+     0x7259, 0x7269, 0x7279, 0x72B6, 0x72EC, 0x731C, 0x7387, 0x738B, 0x739B, 0x73AF, 0x73B0,
+     0x73BB, 0x73ED, 0x7403, 0x7406, 0x7459, 0x745E, 0x745F, 0x74DC, 0x74E6, 0x7518, 0x751A,
+     0x751F, 0x7528, 0x7531, 0x7533, 0x7535, 0x7538, 0x753B, 0x754C, 0x7554, 0x7559, 0x7565,
+     0x7574, 0x7578, 0x7586, 0x758F, 0x7591, 0x767B, 0x767D, 0x767E, 0x7684, 0x76AE, 0x76CA,
+     0x76D1, 0x76D2, 0x76D6, 0x76D8, 0x76EE, 0x76F2, 0x76F4, 0x76F8, 0x76FE, 0x7701, 0x7709,
+     0x770B, 0x771F, 0x7720, 0x7740, 0x7761, 0x77DB, 0x77E2, 0x77E5, 0x77ED, 0x7801, 0x7834,
+     0x7840, 0x786C, 0x786E, 0x7881, 0x788E, 0x789F, 0x78B0, 0x78C1, 0x793A, 0x793E, 0x7956,
+     0x795E, 0x7981, 0x798F, 0x79BB, 0x79C1, 0x79CD, 0x79D1, 0x79D2, 0x79D8, 0x79EF, 0x79F0,
+     0x79FB, 0x7A00, 0x7A0B, 0x7A0D, 0x7A33, 0x7A3F, 0x7A46, 0x7A77, 0x7A7A, 0x7A81, 0x7A97,
+     0x7ACB, 0x7AD6, 0x7AD9, 0x7AE0, 0x7AEF, 0x7B11, 0x7B14, 0x7B26, 0x7B2C, 0x7B49, 0x7B54,
+     0x7B56, 0x7B5B, 0x7B7E, 0x7B80, 0x7B97, 0x7BA1, 0x7BAD, 0x7BB1, 0x7C07, 0x7C73, 0x7C7B,
+     0x7C89, 0x7C97, 0x7C98, 0x7CBE, 0x7CCA, 0x7CFB, 0x7D20, 0x7D22, 0x7D27, 0x7D2F, 0x7E41,
+     0x7EA2, 0x7EA6, 0x7EA7, 0x7EAF, 0x7EB3, 0x7EB5, 0x7EB8, 0x7EB9, 0x7EBD, 0x7EBF, 0x7EC4,
+     0x7EC6, 0x7EC8, 0x7ECD, 0x7ECF, 0x7ED1, 0x7ED3, 0x7ED5, 0x7ED8, 0x7ED9, 0x7EDC, 0x7EDD,
+     0x7EDF, 0x7EE7, 0x7EEA, 0x7EED, 0x7EF4, 0x7EFF, 0x7F00, 0x7F05, 0x7F13, 0x7F16, 0x7F18,
+     0x7F29, 0x7F3A, 0x7F51, 0x7F55, 0x7F57, 0x7F69, 0x7F6E, 0x7F72, 0x7F8E, 0x7FA4, 0x7FC1,
+     0x7FFB, 0x8001, 0x8003, 0x8005, 0x800C, 0x8017, 0x8033, 0x8036, 0x804A, 0x8054, 0x805A,
+     0x80AF, 0x80CC, 0x80E1, 0x80FD, 0x8109, 0x810F, 0x8111, 0x811A, 0x8131, 0x814A, 0x817E,
+     0x81EA, 0x81F3, 0x81F4, 0x820D, 0x822C, 0x826F, 0x8272, 0x827A, 0x827E, 0x8282, 0x8292,
+     0x82AC, 0x82B1, 0x82CF, 0x82E5, 0x82F1, 0x82F9, 0x8303, 0x8328, 0x8335, 0x8350, 0x8377,
+     0x838E, 0x83AB, 0x83B1, 0x83B7, 0x83DC, 0x83F2, 0x8404, 0x8428, 0x843D, 0x8457, 0x8461,
+     0x8482, 0x8499, 0x84DD, 0x84EC, 0x853D, 0x85CF, 0x8651, 0x865A, 0x867D, 0x8702, 0x8840,
+     0x884C, 0x884D, 0x8857, 0x8865, 0x8868, 0x88AB, 0x88C1, 0x88C5, 0x88F9, 0x897F, 0x8981,
+     0x8986, 0x89C1, 0x89C2, 0x89C4, 0x89C6, 0x89C8, 0x89C9, 0x89D2, 0x89E3, 0x89E6, 0x8A00,
+     0x8A79, 0x8B66, 0x8BA1, 0x8BA2, 0x8BA4, 0x8BA9, 0x8BAE, 0x8BAF, 0x8BB0, 0x8BB7, 0x8BB8,
+     0x8BBA, 0x8BBE, 0x8BBF, 0x8BC1, 0x8BC4, 0x8BC6, 0x8BC9, 0x8BCA, 0x8BCD, 0x8BD1, 0x8BD5,
+     0x8BDD, 0x8BE2, 0x8BE5, 0x8BE6, 0x8BED, 0x8BEF, 0x8BF4, 0x8BF7, 0x8BF8, 0x8BFA, 0x8BFB,
+     0x8C03, 0x8C22, 0x8C37, 0x8C61, 0x8C6A, 0x8D1D, 0x8D1F, 0x8D21, 0x8D23, 0x8D25, 0x8D26,
+     0x8D27, 0x8D28, 0x8D2E, 0x8D34, 0x8D35, 0x8D39, 0x8D3E, 0x8D44, 0x8D4B, 0x8D4C, 0x8D56,
+     0x8D5B, 0x8D5E, 0x8D64, 0x8D6B, 0x8D77, 0x8D85, 0x8D8A, 0x8DB3, 0x8DC3, 0x8DDD, 0x8DDF,
+     0x8DE8, 0x8DEF, 0x8DF3, 0x8E2A, 0x8EAB, 0x8F66, 0x8F68, 0x8F6C, 0x8F6E, 0x8F6F, 0x8F74,
+     0x8F7B, 0x8F7D, 0x8F83, 0x8F85, 0x8F91, 0x8F93, 0x8F96, 0x8F9B, 0x8FA8, 0x8FB9, 0x8FBE,
+     0x8FC1, 0x8FC7, 0x8FD0, 0x8FD1, 0x8FD4, 0x8FD8, 0x8FD9, 0x8FDB, 0x8FDC, 0x8FDD, 0x8FDE,
+     0x8FDF, 0x8FEA, 0x8FED, 0x8FF0, 0x8FF9, 0x8FFD, 0x9000, 0x9001, 0x9002, 0x9006, 0x9009,
+     0x900F, 0x9010, 0x9012, 0x9014, 0x9017, 0x901A, 0x901F, 0x9020, 0x9038, 0x903B, 0x903C,
+     0x9047, 0x904D, 0x9053, 0x9057, 0x906D, 0x906E, 0x9075, 0x907F, 0x9093, 0x90A3, 0x90A6,
+     0x90AE, 0x90BB, 0x90CE, 0x90E1, 0x90E8, 0x90FD, 0x914D, 0x91C7, 0x91CA, 0x91CC, 0x91CD,
+     0x91CF, 0x91D1, 0x9274, 0x9488, 0x949F, 0x94A5, 0x94A6, 0x94A9, 0x94AE, 0x94C3, 0x94FE,
+     0x9500, 0x9501, 0x9519, 0x9521, 0x9525, 0x952E, 0x952F, 0x9551, 0x955C, 0x957F, 0x95E8,
+     0x95EA, 0x95ED, 0x95EE, 0x95F0, 0x95F2, 0x95F4, 0x95F9, 0x95FB, 0x95FD, 0x9605, 0x9608,
+     0x961F, 0x9631, 0x9632, 0x9634, 0x9636, 0x963B, 0x963F, 0x9644, 0x9645, 0x964D, 0x9650,
+     0x9664, 0x9669, 0x9676, 0x9677, 0x9686, 0x968F, 0x9690, 0x9694, 0x969C, 0x96C5, 0x96C6,
+     0x96D5, 0x96F6, 0x96F7, 0x9700, 0x970D, 0x9752, 0x9759, 0x975E, 0x9760, 0x9762, 0x977C,
+     0x9791, 0x97E6, 0x97E9, 0x97F3, 0x9875, 0x9876, 0x9879, 0x987A, 0x987B, 0x987F, 0x9881,
+     0x9884, 0x9886, 0x9891, 0x9898, 0x989C, 0x989D, 0x98A0, 0x98CE, 0x9970, 0x9996, 0x9999,
+     0x9A6C, 0x9A71, 0x9A7B, 0x9A8C, 0x9AA4, 0x9AD8, 0x9B54, 0x9C81, 0x9C9C, 0x9E23, 0x9EA6,
+     0x9ECE, 0x9ED1, 0x9ED8, 0x9F20, 0x9F50, 0x9F7F, 0x9F84, 0x9F99];
+
+pub static SIMPLIFIED_CHINESE_WEIGHTS: [u8; 1383] =
+    [8, 7, 2, 5, 8, 8, 8, 8, 3, 7, 3, 2, 1, 5, 6, 7, 4, 8, 8, 1, 8, 7, 4, 8,
+     8, 4, 4, 5, 8, 8, 5, 3, 2, 2, 3, 3, 1, 7, 3, 7, 1, 8, 3, 7, 7, 8, 5, 3,
+     8, 7, 1, 8, 2, 5, 6, 2, 4, 1, 5, 5, 7, 4, 5, 8, 7, 6, 8, 8, 8, 6, 8, 4,
+     8, 7, 3, 6, 1, 2, 2, 5, 8, 6, 4, 4, 5, 3, 1, 5, 8, 8, 5, 4, 2, 7, 7, 5,
+     3, 8, 5, 3, 3, 8, 6, 7, 6, 4, 4, 5, 1, 7, 8, 7, 1, 4, 3, 5, 1, 1, 8, 5,
+     7, 6, 6, 2, 3, 6, 7, 1, 2, 8, 7, 5, 6, 6, 8, 4, 8, 8, 4, 6, 5, 6, 7, 8,
+     8, 7, 6, 4, 6, 8, 2, 5, 6, 4, 2, 8, 1, 2, 7, 6, 5, 2, 3, 8, 4, 1, 4, 1,
+     3, 3, 8, 5, 8, 8, 6, 5, 8, 8, 3, 8, 6, 8, 2, 7, 8, 8, 8, 4, 2, 8, 1, 3,
+     1, 4, 2, 4, 4, 4, 2, 6, 8, 8, 8, 7, 4, 3, 1, 1, 1, 5, 1, 8, 3, 7, 5, 3,
+     8, 8, 3, 6, 2, 5, 1, 3, 2, 6, 8, 1, 6, 4, 1, 4, 7, 5, 8, 3, 6, 4, 4, 5,
+     2, 2, 5, 7, 7, 5, 5, 8, 2, 2, 6, 4, 6, 8, 8, 6, 8, 3, 4, 8, 5, 7, 6, 8,
+     8, 6, 5, 6, 1, 8, 4, 8, 5, 6, 8, 8, 8, 7, 5, 8, 8, 3, 8, 8, 6, 5, 4, 8,
+     8, 1, 3, 2, 6, 6, 5, 5, 4, 4, 1, 3, 1, 1, 2, 8, 4, 8, 7, 7, 4, 7, 8, 2,
+     4, 5, 8, 3, 8, 2, 3, 7, 4, 3, 7, 8, 1, 5, 2, 3, 8, 5, 2, 7, 7, 3, 3, 6,
+     6, 5, 6, 6, 2, 4, 6, 8, 8, 8, 3, 8, 8, 6, 8, 5, 7, 5, 8, 8, 1, 1, 4, 5,
+     2, 2, 6, 6, 1, 1, 6, 8, 5, 8, 3, 5, 5, 1, 8, 2, 8, 8, 3, 2, 1, 3, 2, 8,
+     3, 8, 7, 3, 3, 2, 8, 2, 1, 7, 1, 5, 1, 4, 8, 7, 3, 1, 5, 8, 3, 3, 4, 8,
+     5, 7, 5, 6, 8, 8, 8, 8, 2, 5, 6, 3, 5, 1, 5, 7, 5, 6, 5, 6, 2, 5, 7, 8,
+     4, 5, 1, 1, 3, 5, 1, 6, 8, 6, 6, 3, 8, 6, 2, 5, 7, 6, 4, 5, 7, 2, 6, 7,
+     2, 2, 5, 4, 6, 5, 8, 1, 3, 8, 8, 8, 4, 5, 5, 8, 1, 3, 5, 2, 8, 8, 6, 6,
+     8, 8, 4, 3, 2, 2, 4, 6, 8, 8, 8, 6, 4, 1, 6, 1, 2, 2, 1, 8, 6, 4, 3, 7,
+     5, 4, 7, 4, 8, 2, 7, 2, 2, 5, 2, 7, 1, 8, 2, 3, 8, 3, 6, 1, 6, 4, 8, 2,
+     8, 3, 6, 3, 2, 5, 7, 3, 2, 1, 5, 1, 8, 5, 8, 5, 5, 2, 8, 1, 8, 1, 6, 6,
+     4, 8, 5, 8, 7, 4, 2, 4, 8, 5, 4, 6, 3, 3, 2, 4, 5, 6, 3, 2, 2, 3, 8, 1,
+     5, 2, 3, 3, 4, 4, 4, 7, 6, 5, 3, 3, 8, 5, 8, 7, 2, 1, 2, 3, 1, 1, 3, 6,
+     8, 8, 4, 4, 3, 7, 3, 8, 7, 6, 4, 7, 8, 6, 2, 7, 1, 2, 1, 4, 4, 3, 1, 2,
+     1, 4, 4, 8, 3, 1, 8, 7, 8, 7, 2, 3, 8, 3, 2, 5, 2, 8, 7, 8, 1, 4, 5, 3,
+     7, 8, 8, 8, 3, 2, 3, 4, 2, 8, 3, 7, 1, 7, 4, 8, 2, 2, 2, 7, 3, 3, 6, 8,
+     8, 1, 6, 5, 3, 6, 2, 2, 3, 1, 8, 5, 2, 7, 8, 4, 8, 8, 4, 6, 2, 8, 1, 8,
+     5, 8, 5, 2, 7, 3, 3, 7, 1, 1, 4, 8, 5, 8, 8, 3, 4, 6, 2, 7, 7, 4, 4, 8,
+     6, 4, 4, 5, 3, 8, 2, 1, 8, 5, 5, 6, 6, 6, 4, 6, 8, 6, 5, 4, 4, 8, 4, 1,
+     1, 8, 1, 3, 3, 2, 1, 3, 5, 8, 3, 8, 1, 3, 2, 2, 3, 1, 8, 8, 8, 6, 2, 4,
+     6, 5, 8, 4, 5, 7, 7, 2, 2, 4, 3, 1, 5, 4, 7, 4, 4, 2, 3, 2, 2, 1, 4, 4,
+     8, 4, 3, 1, 8, 1, 5, 7, 4, 3, 2, 5, 2, 2, 1, 3, 6, 4, 6, 3, 6, 3, 4, 2,
+     2, 5, 4, 2, 8, 3, 1, 5, 4, 7, 7, 1, 1, 1, 2, 3, 2, 3, 6, 1, 2, 3, 8, 5,
+     1, 4, 2, 5, 2, 4, 6, 2, 3, 3, 2, 1, 2, 5, 2, 2, 8, 3, 2, 3, 5, 6, 6, 2,
+     3, 5, 6, 8, 4, 5, 5, 8, 8, 4, 3, 4, 3, 2, 7, 8, 2, 4, 2, 8, 2, 4, 1, 4,
+     6, 3, 2, 8, 8, 7, 2, 5, 1, 4, 6, 2, 6, 8, 1, 1, 1, 4, 1, 6, 5, 3, 8, 4,
+     2, 5, 2, 6, 6, 8, 2, 6, 7, 1, 8, 3, 6, 6, 3, 5, 1, 1, 1, 8, 5, 8, 3, 3,
+     5, 8, 1, 2, 1, 2, 5, 8, 3, 3, 1, 7, 4, 5, 5, 6, 6, 6, 2, 3, 8, 8, 4, 8,
+     2, 3, 3, 3, 1, 8, 6, 7, 6, 4, 4, 2, 7, 1, 4, 8, 8, 7, 3, 5, 2, 8, 5, 7,
+     7, 4, 3, 4, 5, 8, 1, 2, 3, 5, 3, 8, 6, 8, 3, 3, 1, 3, 6, 8, 4, 6, 2, 3,
+     4, 2, 7, 8, 6, 7, 3, 7, 5, 8, 3, 5, 7, 5, 6, 8, 6, 1, 6, 6, 2, 7, 2, 7,
+     8, 3, 7, 8, 5, 1, 7, 1, 8, 1, 5, 6, 2, 6, 4, 6, 8, 8, 4, 3, 4, 1, 7, 6,
+     3, 4, 3, 8, 1, 3, 3, 6, 4, 3, 1, 8, 7, 6, 2, 3, 2, 7, 2, 3, 8, 1, 3, 3,
+     5, 6, 5, 1, 7, 3, 1, 3, 3, 1, 5, 5, 8, 6, 4, 3, 6, 4, 1, 3, 4, 3, 2, 1,
+     1, 6, 5, 4, 1, 3, 1, 8, 1, 1, 7, 8, 8, 1, 7, 1, 7, 8, 6, 6, 3, 7, 7, 5,
+     2, 6, 8, 7, 6, 1, 8, 8, 5, 8, 3, 6, 1, 8, 1, 8, 3, 8, 7, 8, 2, 8, 1, 3,
+     6, 7, 7, 7, 7, 8, 6, 8, 8, 7, 8, 5, 5, 8, 8, 3, 1, 8, 2, 6, 5, 2, 1, 8,
+     3, 2, 4, 5, 3, 1, 3, 3, 6, 5, 1, 6, 1, 3, 1, 4, 7, 8, 5, 6, 2, 5, 7, 3,
+     8, 7, 6, 5, 4, 3, 8, 4, 8, 1, 1, 8, 6, 4, 7, 8, 1, 1, 2, 6, 8, 2, 8, 8,
+     5, 7, 6, 8, 8, 7, 3, 8, 5, 4, 3, 6, 1, 5, 8, 7, 6, 2, 8, 3, 3, 6, 3, 5,
+     8, 6, 4, 1, 5, 1, 5, 3, 6, 3, 2, 3, 3, 4, 1, 6, 4, 5, 2, 2, 5, 8, 6, 8,
+     3, 6, 8, 8, 8, 4, 3, 5, 4, 8, 2, 3, 6, 3, 7, 6, 7, 8, 4, 1, 8, 2, 2, 3,
+     8, 4, 3, 7, 8, 2, 3, 8, 1, 1, 1, 5, 1, 3, 3, 2, 3, 3, 5, 7, 6, 5, 3, 8,
+     8, 3, 3, 4, 5, 5, 5, 7, 3, 3, 7, 1, 6, 5, 8, 3, 1, 4, 8, 2, 7, 2, 2, 4,
+     1, 6, 7, 5, 8, 5, 8, 4, 2, 7, 3, 6, 7, 6, 5, 1, 5, 4, 6, 1, 7, 4, 1, 7,
+     2, 6, 2, 5, 1, 3, 3, 2, 3, 8, 5, 6, 2, 1, 2];
+
+pub static TRADITIONAL_CHINESE_CHARS: [u16; 1428] =
+    [0x4E00, 0x4E01, 0x4E03, 0x4E09, 0x4E0A, 0x4E0B, 0x4E0D, 0x4E14, 0x4E16, 0x4E18, 0x4E1F,
+     0x4E26, 0x4E2D, 0x4E32, 0x4E39, 0x4E3B, 0x4E45, 0x4E4B, 0x4E4D, 0x4E4E, 0x4E4F, 0x4E58,
+     0x4E5D, 0x4E5F, 0x4E7E, 0x4E82, 0x4E86, 0x4E88, 0x4E8B, 0x4E8C, 0x4E92, 0x4E94, 0x4E9B,
+     0x4E9E, 0x4EA4, 0x4EA6, 0x4EA8, 0x4EAB, 0x4EAE, 0x4EB3, 0x4EBA, 0x4EC0, 0x4ECB, 0x4ECD,
+     0x4ED6, 0x4EE3, 0x4EE4, 0x4EE5, 0x4EF6, 0x4EFB, 0x4EFD, 0x4F0A, 0x4F0F, 0x4F10, 0x4F2F,
+     0x4F38, 0x4F3A, 0x4F3C, 0x4F46, 0x4F47, 0x4F48, 0x4F4D, 0x4F4E, 0x4F4F, 0x4F50, 0x4F54,
+     0x4F55, 0x4F5B, 0x4F5C, 0x4F60, 0x4F69, 0x4F73, 0x4F75, 0x4F7F, 0x4F86, 0x4F8B, 0x4F97,
+     0x4F9B, 0x4F9D, 0x4FB6, 0x4FBF, 0x4FC2, 0x4FC4, 0x4FD7, 0x4FDD, 0x4FE1, 0x4FEE, 0x4FFE,
+     0x500B, 0x500D, 0x5011, 0x5012, 0x5019, 0x502B, 0x503C, 0x5047, 0x504F, 0x5055, 0x505A,
+     0x505C, 0x5065, 0x5074, 0x5075, 0x5076, 0x507D, 0x5099, 0x50A3, 0x50B3, 0x50BE, 0x50C5,
+     0x50CF, 0x50E7, 0x50F9, 0x512A, 0x5132, 0x5141, 0x5143, 0x5145, 0x5148, 0x5149, 0x514B,
+     0x514C, 0x514D, 0x5165, 0x5167, 0x5168, 0x5169, 0x516B, 0x516C, 0x516D, 0x5171, 0x5176,
+     0x5177, 0x5178, 0x517C, 0x518A, 0x518D, 0x5192, 0x5197, 0x51AC, 0x51B0, 0x51B7, 0x51E1,
+     0x51F1, 0x51F8, 0x51FA, 0x51FD, 0x5206, 0x5207, 0x5217, 0x521D, 0x5224, 0x5225, 0x5229,
+     0x522A, 0x5230, 0x5236, 0x5237, 0x523B, 0x5247, 0x524D, 0x5256, 0x525B, 0x5269, 0x526A,
+     0x526F, 0x5272, 0x5275, 0x5283, 0x5291, 0x529B, 0x529F, 0x52A0, 0x52A9, 0x52AA, 0x52D2,
+     0x52D5, 0x52D9, 0x52DE, 0x52E2, 0x5305, 0x5308, 0x5316, 0x5317, 0x5319, 0x5323, 0x532F,
+     0x5339, 0x533F, 0x5340, 0x5341, 0x5343, 0x5347, 0x5348, 0x534A, 0x5354, 0x5357, 0x535A,
+     0x5360, 0x5361, 0x5370, 0x5371, 0x5373, 0x5377, 0x5378, 0x537B, 0x5384, 0x5398, 0x539A,
+     0x539F, 0x53BB, 0x53C3, 0x53C9, 0x53CA, 0x53CD, 0x53D6, 0x53D7, 0x53E3, 0x53E4, 0x53E5,
+     0x53E6, 0x53EA, 0x53EB, 0x53EF, 0x53F0, 0x53F2, 0x53F3, 0x5404, 0x5408, 0x5409, 0x540B,
+     0x540C, 0x540D, 0x540E, 0x5411, 0x5426, 0x542B, 0x5438, 0x543B, 0x543E, 0x5440, 0x5448,
+     0x544A, 0x5468, 0x5473, 0x547C, 0x547D, 0x548C, 0x54C1, 0x54C7, 0x54C8, 0x54E1, 0x54E5,
+     0x54EA, 0x54F2, 0x552F, 0x5546, 0x554F, 0x555F, 0x5580, 0x5584, 0x559C, 0x55AC, 0x55AE,
+     0x55CE, 0x55F6, 0x5608, 0x5609, 0x5617, 0x5668, 0x56B4, 0x56DB, 0x56DE, 0x56E0, 0x56FA,
+     0x5708, 0x570B, 0x570D, 0x5713, 0x5716, 0x571C, 0x571F, 0x5728, 0x572D, 0x5730, 0x573E,
+     0x5740, 0x5747, 0x574E, 0x5761, 0x5766, 0x5782, 0x5783, 0x578B, 0x57C3, 0x57CE, 0x57D4,
+     0x57DF, 0x57E0, 0x57F7, 0x57FA, 0x5805, 0x5806, 0x5821, 0x5824, 0x5831, 0x5834, 0x584A,
+     0x5854, 0x585E, 0x586B, 0x5883, 0x589E, 0x58A8, 0x58D3, 0x58DE, 0x58EB, 0x58EF, 0x590F,
+     0x5916, 0x591A, 0x5920, 0x5927, 0x5929, 0x592A, 0x592B, 0x592E, 0x5931, 0x5937, 0x593E,
+     0x5947, 0x5948, 0x5951, 0x5957, 0x5967, 0x5973, 0x597D, 0x5982, 0x59A5, 0x59C6, 0x59CB,
+     0x59D3, 0x59D4, 0x5A01, 0x5A1C, 0x5A46, 0x5A92, 0x5AE9, 0x5B50, 0x5B54, 0x5B57, 0x5B58,
+     0x5B5F, 0x5B64, 0x5B78, 0x5B83, 0x5B88, 0x5B89, 0x5B8C, 0x5B8F, 0x5B97, 0x5B98, 0x5B9A,
+     0x5B9B, 0x5BA2, 0x5BA3, 0x5BAE, 0x5BB6, 0x5BB9, 0x5BBF, 0x5BC4, 0x5BC6, 0x5BCC, 0x5BDF,
+     0x5BE6, 0x5BE7, 0x5BE8, 0x5BEB, 0x5BEC, 0x5BEE, 0x5BF8, 0x5C01, 0x5C04, 0x5C07, 0x5C08,
+     0x5C0B, 0x5C0D, 0x5C0E, 0x5C0F, 0x5C11, 0x5C16, 0x5C1A, 0x5C24, 0x5C31, 0x5C3A, 0x5C3C,
+     0x5C3E, 0x5C45, 0x5C46, 0x5C55, 0x5C64, 0x5C6C, 0x5C71, 0x5CA1, 0x5CB8, 0x5CC7, 0x5CF6,
+     0x5D14, 0x5D4C, 0x5DBC, 0x5DDD, 0x5DE2, 0x5DE5, 0x5DE6, 0x5DE8, 0x5DEE, 0x5DF1, 0x5DF2,
+     0x5DF4, 0x5DFD, 0x5E02, 0x5E03, 0x5E0C, 0x5E15, 0x5E1D, 0x5E33, 0x5E36, 0x5E38, 0x5E45,
+     0x5E55, 0x5E5F, 0x5E63, 0x5E6B, 0x5E72, 0x5E73, 0x5E74, 0x5E7C, 0x5E7E, 0x5E87, 0x5E8F,
+     0x5E95, 0x5EA6, 0x5EA7, 0x5EAB, 0x5EB7, 0x5EE0, 0x5EE2, 0x5EE3, 0x5EF6, 0x5EF7, 0x5EFA,
+     0x5F0F, 0x5F15, 0x5F17, 0x5F27, 0x5F31, 0x5F35, 0x5F37, 0x5F48, 0x5F4A, 0x5F5D, 0x5F62,
+     0x5F69, 0x5F71, 0x5F7C, 0x5F80, 0x5F85, 0x5F88, 0x5F8B, 0x5F8C, 0x5F91, 0x5F97, 0x5F9E,
+     0x5FA9, 0x5FAA, 0x5FAE, 0x5FB7, 0x5FB9, 0x5FC3, 0x5FC5, 0x5FD7, 0x5FD8, 0x5FD9, 0x5FEB,
+     0x5FF5, 0x5FFD, 0x601D, 0x6025, 0x6027, 0x6028, 0x602A, 0x6069, 0x606F, 0x6070, 0x60A8,
+     0x60C5, 0x60E0, 0x60F3, 0x610F, 0x611B, 0x611F, 0x614B, 0x6155, 0x6167, 0x616E, 0x6191,
+     0x61B6, 0x61C9, 0x61F8, 0x6208, 0x6210, 0x6211, 0x6216, 0x622A, 0x6233, 0x6234, 0x6236,
+     0x623F, 0x6240, 0x624B, 0x624D, 0x624E, 0x6253, 0x6258, 0x6279, 0x627E, 0x627F, 0x6280,
+     0x628A, 0x6291, 0x6293, 0x6295, 0x6298, 0x62AB, 0x62AC, 0x62B1, 0x62B5, 0x62BD, 0x62C9,
+     0x62CB, 0x62D2, 0x62D6, 0x62DC, 0x62EC, 0x62FC, 0x62FF, 0x6301, 0x6307, 0x6309, 0x6311,
+     0x632A, 0x6355, 0x6368, 0x6372, 0x6377, 0x6383, 0x6388, 0x6389, 0x6392, 0x639B, 0x63A1,
+     0x63A5, 0x63A7, 0x63A8, 0x63C0, 0x63CF, 0x63D0, 0x63D2, 0x63DA, 0x63DB, 0x63F4, 0x640D,
+     0x641C, 0x642D, 0x6458, 0x6469, 0x647A, 0x6492, 0x649E, 0x64A3, 0x64A4, 0x64AD, 0x64BE,
+     0x64C1, 0x64C7, 0x64CA, 0x64CB, 0x64CD, 0x64CE, 0x64D4, 0x64DA, 0x64EC, 0x64F1, 0x64F4,
+     0x64F7, 0x64FA, 0x6514, 0x651C, 0x652F, 0x6536, 0x6539, 0x653E, 0x653F, 0x6545, 0x6548,
+     0x654F, 0x6551, 0x6557, 0x6558, 0x6559, 0x6563, 0x6566, 0x6574, 0x6578, 0x6587, 0x6590,
+     0x6599, 0x659C, 0x65A5, 0x65AF, 0x65B0, 0x65B7, 0x65B9, 0x65BC, 0x65BD, 0x65C1, 0x65CB,
+     0x65CF, 0x65D7, 0x65E2, 0x65E5, 0x65E6, 0x65E8, 0x65E9, 0x65FA, 0x6602, 0x6606, 0x6607,
+     0x660E, 0x6613, 0x661F, 0x6620, 0x6628, 0x662F, 0x6642, 0x666E, 0x666F, 0x667A, 0x6697,
+     0x66AB, 0x66B4, 0x66C6, 0x66F2, 0x66F3, 0x66F4, 0x66F8, 0x66FC, 0x66FE, 0x66FF, 0x6700,
+     0x6703, 0x6708, 0x6709, 0x670D, 0x6717, 0x671B, 0x671D, 0x671F, 0x6728, 0x672A, 0x672B,
+     0x672C, 0x672D, 0x674E, 0x675C, 0x675F, 0x6770, 0x6771, 0x677E, 0x677F, 0x6790, 0x6797,
+     0x679C, 0x67B6, 0x67C4, 0x67CF, 0x67D0, 0x67E5, 0x67EC, 0x67F4, 0x6821, 0x6838, 0x6839,
+     0x683C, 0x6846, 0x6848, 0x684C, 0x6851, 0x6876, 0x6885, 0x689D, 0x68AF, 0x68B5, 0x68C4,
+     0x68C9, 0x68EE, 0x690D, 0x694A, 0x695A, 0x6960, 0x696D, 0x6975, 0x6982, 0x69AE, 0x69CB,
+     0x69FD, 0x6A02, 0x6A19, 0x6A21, 0x6A23, 0x6A39, 0x6A5F, 0x6A6B, 0x6A94, 0x6AA2, 0x6B04,
+     0x6B0A, 0x6B21, 0x6B32, 0x6B3D, 0x6B3E, 0x6B49, 0x6B50, 0x6B61, 0x6B62, 0x6B63, 0x6B64,
+     0x6B65, 0x6B67, 0x6B77, 0x6B78, 0x6B8A, 0x6BB5, 0x6BBC, 0x6BC0, 0x6BCD, 0x6BCF, 0x6BD4,
+     0x6BD7, 0x6BDB, 0x6BEB, 0x6C11, 0x6C34, 0x6C38, 0x6C42, 0x6C57, 0x6C76, 0x6C7A, 0x6C83,
+     0x6C92, 0x6C99, 0x6CB3, 0x6CBB, 0x6CC1, 0x6CCA, 0x6CD5, 0x6CE2, 0x6CE8, 0x6CF0, 0x6D0B,
+     0x6D1B, 0x6D1E, 0x6D25, 0x6D2A, 0x6D32, 0x6D3B, 0x6D3E, 0x6D41, 0x6D66, 0x6D6E, 0x6D77,
+     0x6D88, 0x6D89, 0x6DE8, 0x6DF1, 0x6DF7, 0x6DFA, 0x6DFB, 0x6E05, 0x6E1B, 0x6E2C, 0x6E2F,
+     0x6E38, 0x6E4A, 0x6E6F, 0x6E90, 0x6E96, 0x6E9D, 0x6EA2, 0x6EAA, 0x6EAF, 0x6ED1, 0x6ED5,
+     0x6EEF, 0x6EF4, 0x6EFE, 0x6EFF, 0x6F0F, 0x6F14, 0x6F22, 0x6F2B, 0x6F38, 0x6F58, 0x6FA4,
+     0x6FB3, 0x6FDF, 0x6FFE, 0x7006, 0x700F, 0x7063, 0x7070, 0x70BA, 0x70C8, 0x70CF, 0x7121,
+     0x7126, 0x7136, 0x7167, 0x71B1, 0x71C8, 0x720D, 0x722A, 0x7236, 0x723E, 0x7247, 0x7248,
+     0x7259, 0x7269, 0x7279, 0x72C0, 0x731C, 0x7336, 0x7368, 0x7372, 0x7387, 0x738B, 0x73BB,
+     0x73CD, 0x73ED, 0x73FE, 0x7403, 0x7406, 0x7459, 0x745E, 0x745F, 0x7464, 0x746A, 0x74B0,
+     0x74DC, 0x74E6, 0x7518, 0x751F, 0x7522, 0x7528, 0x7531, 0x7533, 0x7538, 0x754C, 0x7559,
+     0x7562, 0x7565, 0x756B, 0x7570, 0x7576, 0x7586, 0x758A, 0x758F, 0x767B, 0x767C, 0x767D,
+     0x767E, 0x7684, 0x7686, 0x7687, 0x76AE, 0x76C8, 0x76CA, 0x76D2, 0x76DF, 0x76E1, 0x76E3,
+     0x76E4, 0x76E7, 0x76EE, 0x76F4, 0x76F8, 0x76FE, 0x7701, 0x770B, 0x771F, 0x7720, 0x773E,
+     0x7761, 0x77E5, 0x77ED, 0x780D, 0x7834, 0x786C, 0x788C, 0x788E, 0x7891, 0x789F, 0x78B3,
+     0x78BA, 0x78BC, 0x78C1, 0x7901, 0x790E, 0x7919, 0x793A, 0x793E, 0x7947, 0x7956, 0x795E,
+     0x7981, 0x798F, 0x79C1, 0x79D1, 0x79D2, 0x79D8, 0x79FB, 0x7A00, 0x7A0B, 0x7A0D, 0x7A1C,
+     0x7A2E, 0x7A31, 0x7A3F, 0x7A46, 0x7A4D, 0x7A69, 0x7A7A, 0x7A81, 0x7A97, 0x7ACB, 0x7AD9,
+     0x7AE0, 0x7AED, 0x7AEF, 0x7B26, 0x7B2C, 0x7B46, 0x7B49, 0x7B52, 0x7B54, 0x7B56, 0x7B97,
+     0x7BA1, 0x7BAD, 0x7BB1, 0x7BC0, 0x7BC4, 0x7BE9, 0x7C21, 0x7C3D, 0x7C3F, 0x7C64, 0x7C73,
+     0x7C89, 0x7C97, 0x7C9F, 0x7CBE, 0x7CCA, 0x7CFB, 0x7D00, 0x7D04, 0x7D05, 0x7D0B, 0x7D0D,
+     0x7D10, 0x7D14, 0x7D19, 0x7D1A, 0x7D20, 0x7D22, 0x7D2F, 0x7D30, 0x7D39, 0x7D42, 0x7D44,
+     0x7D50, 0x7D55, 0x7D61, 0x7D66, 0x7D68, 0x7D71, 0x7D81, 0x7D93, 0x7DA0, 0x7DAD, 0x7DB2,
+     0x7DB4, 0x7DCA, 0x7DD2, 0x7DDA, 0x7DE3, 0x7DE8, 0x7DE9, 0x7DEC, 0x7E23, 0x7E2E, 0x7E31,
+     0x7E3D, 0x7E5E, 0x7E6A, 0x7E6B, 0x7E7C, 0x7E8C, 0x7F3A, 0x7F3D, 0x7F69, 0x7F6E, 0x7F72,
+     0x7F85, 0x7F8E, 0x7FA4, 0x7FA9, 0x7FC1, 0x7FFB, 0x8001, 0x8003, 0x8005, 0x800C, 0x8017,
+     0x8033, 0x8036, 0x804A, 0x8056, 0x805A, 0x806F, 0x8072, 0x807D, 0x8096, 0x80AF, 0x80CC,
+     0x80DA, 0x80E1, 0x80FD, 0x8108, 0x812B, 0x8153, 0x8166, 0x8173, 0x81D8, 0x81E3, 0x81E8,
+     0x81EA, 0x81ED, 0x81F3, 0x81F4, 0x81FA, 0x8207, 0x8209, 0x820A, 0x820C, 0x8212, 0x822A,
+     0x822C, 0x826F, 0x8272, 0x827E, 0x8292, 0x82AC, 0x82B1, 0x82B3, 0x82D7, 0x82E5, 0x82F1,
+     0x8328, 0x8332, 0x8349, 0x8377, 0x837C, 0x83AB, 0x83EF, 0x83F2, 0x8404, 0x840A, 0x840C,
+     0x842C, 0x843D, 0x8457, 0x8461, 0x8482, 0x8499, 0x84CB, 0x84EC, 0x853D, 0x856D, 0x85A6,
+     0x85A9, 0x85C9, 0x85CD, 0x85CF, 0x8607, 0x860B, 0x862D, 0x8655, 0x865B, 0x865F, 0x878D,
+     0x87A2, 0x87F2, 0x884C, 0x884D, 0x8853, 0x8857, 0x885D, 0x8861, 0x8863, 0x8868, 0x8896,
+     0x88AB, 0x88DC, 0x88DD, 0x88E1, 0x88FD, 0x8907, 0x893B, 0x897F, 0x8981, 0x8986, 0x898B,
+     0x898F, 0x8996, 0x89BA, 0x89BD, 0x89C0, 0x89D2, 0x89E3, 0x89F8, 0x8A00, 0x8A02, 0x8A08,
+     0x8A0A, 0x8A0E, 0x8A17, 0x8A18, 0x8A2A, 0x8A2D, 0x8A31, 0x8A34, 0x8A3A, 0x8A3B, 0x8A3C,
+     0x8A55, 0x8A5E, 0x8A62, 0x8A66, 0x8A71, 0x8A72, 0x8A73, 0x8A8C, 0x8A8D, 0x8A95, 0x8A9E,
+     0x8AA4, 0x8AAA, 0x8AB0, 0x8ABF, 0x8AC7, 0x8ACB, 0x8AD6, 0x8AF8, 0x8AFE, 0x8B02, 0x8B1D,
+     0x8B49, 0x8B58, 0x8B5C, 0x8B66, 0x8B6F, 0x8B70, 0x8B77, 0x8B80, 0x8B8A, 0x8B93, 0x8C50,
+     0x8C61, 0x8C6A, 0x8C8C, 0x8C9D, 0x8CA0, 0x8CA2, 0x8CA8, 0x8CAF, 0x8CB7, 0x8CBB, 0x8CBC,
+     0x8CC7, 0x8CC8, 0x8CD3, 0x8CEA, 0x8CF4, 0x8CFD, 0x8D0A, 0x8D64, 0x8D6B, 0x8D77, 0x8D85,
+     0x8D8A, 0x8DB3, 0x8DD1, 0x8DDD, 0x8DDF, 0x8DE1, 0x8DE8, 0x8DEF, 0x8DF3, 0x8E64, 0x8E66,
+     0x8E8D, 0x8EAB, 0x8ECA, 0x8ECC, 0x8EDF, 0x8EF8, 0x8F03, 0x8F09, 0x8F14, 0x8F15, 0x8F2A,
+     0x8F2F, 0x8F38, 0x8F49, 0x8F9B, 0x8FA6, 0x8FA8, 0x8FB2, 0x8FD1, 0x8FD4, 0x8FE6, 0x8FEA,
+     0x8FEB, 0x8FED, 0x8FF0, 0x8FF4, 0x8FFD, 0x9000, 0x9001, 0x900F, 0x9010, 0x9014, 0x9017,
+     0x9019, 0x901A, 0x901F, 0x9020, 0x9023, 0x9031, 0x9032, 0x9038, 0x903E, 0x9047, 0x904B,
+     0x904D, 0x904E, 0x9053, 0x9054, 0x905C, 0x905E, 0x9060, 0x9069, 0x906D, 0x906E, 0x9072,
+     0x9075, 0x9078, 0x907A, 0x907F, 0x9080, 0x9084, 0x908A, 0x908F, 0x90A3, 0x90A6, 0x90CE,
+     0x90E8, 0x90F5, 0x90FD, 0x9130, 0x914B, 0x914D, 0x9192, 0x91CB, 0x91CC, 0x91CD, 0x91CF,
+     0x91D1, 0x91DD, 0x9200, 0x9215, 0x9234, 0x9264, 0x9280, 0x9296, 0x9298, 0x92B7, 0x9304,
+     0x932B, 0x932F, 0x9375, 0x937E, 0x938A, 0x9396, 0x93C8, 0x93E1, 0x9418, 0x9470, 0x9577,
+     0x9580, 0x9583, 0x9589, 0x958B, 0x958F, 0x9592, 0x9593, 0x95A9, 0x95B1, 0x95CA, 0x95DC,
+     0x9631, 0x9632, 0x963B, 0x963F, 0x9640, 0x9644, 0x964D, 0x9650, 0x9663, 0x9664, 0x9670,
+     0x9673, 0x9675, 0x9676, 0x9677, 0x967D, 0x9686, 0x968E, 0x9694, 0x969B, 0x969C, 0x96A8,
+     0x96AA, 0x96B1, 0x96B8, 0x96C5, 0x96C6, 0x96D6, 0x96D9, 0x96DC, 0x96E2, 0x96E3, 0x96F6,
+     0x96F7, 0x96FB, 0x9700, 0x970D, 0x9727, 0x9732, 0x975C, 0x975E, 0x9760, 0x9762, 0x977C,
+     0x97C3, 0x97CB, 0x97CC, 0x97D3, 0x97F3, 0x97FF, 0x9801, 0x9802, 0x9805, 0x9806, 0x9808,
+     0x9810, 0x9813, 0x9818, 0x982D, 0x983B, 0x984C, 0x984D, 0x984F, 0x985B, 0x985E, 0x986F,
+     0x98A8, 0x98FD, 0x98FE, 0x9918, 0x994B, 0x9996, 0x9999, 0x99AC, 0x99C1, 0x9A0E, 0x9A30,
+     0x9A45, 0x9A57, 0x9A5F, 0x9AD2, 0x9AD4, 0x9AD8, 0x9B06, 0x9B41, 0x9B6F, 0x9BAE, 0x9CF4,
+     0x9EA5, 0x9EBC, 0x9ECE, 0x9ECF, 0x9ED1, 0x9ED8, 0x9EDE, 0x9F20, 0x9F4A];
+
+pub static TRADITIONAL_CHINESE_WEIGHTS: [u8; 1428] =
+    [8, 5, 1, 5, 8, 8, 8, 6, 3, 2, 2, 8, 8, 8, 5, 8, 3, 8, 2, 3, 2, 2, 1, 6,
+     1, 1, 8, 3, 6, 6, 5, 3, 6, 8, 8, 3, 1, 4, 5, 1, 6, 5, 6, 5, 7, 8, 8, 8,
+     8, 7, 7, 6, 1, 5, 5, 6, 8, 5, 7, 2, 6, 8, 5, 5, 1, 5, 7, 3, 8, 7, 3, 3,
+     7, 8, 8, 7, 1, 7, 6, 1, 4, 4, 5, 2, 7, 7, 7, 1, 8, 4, 6, 5, 4, 4, 8, 5,
+     6, 1, 6, 8, 1, 6, 5, 4, 3, 7, 2, 7, 4, 6, 8, 2, 1, 4, 7, 7, 8, 5, 6, 4,
+     8, 2, 3, 8, 8, 7, 7, 4, 5, 5, 8, 8, 8, 5, 3, 5, 5, 3, 3, 2, 3, 1, 1, 3,
+     1, 8, 6, 8, 7, 8, 6, 4, 8, 8, 7, 8, 8, 1, 5, 8, 8, 1, 4, 4, 4, 6, 5, 1,
+     1, 2, 5, 7, 8, 7, 5, 5, 8, 6, 4, 4, 8, 4, 7, 5, 5, 4, 6, 3, 4, 8, 6, 1,
+     6, 2, 4, 6, 7, 4, 3, 7, 8, 3, 6, 3, 4, 5, 4, 1, 1, 7, 4, 8, 2, 8, 7, 8,
+     7, 1, 7, 4, 5, 8, 6, 8, 4, 4, 8, 4, 8, 6, 4, 8, 8, 1, 8, 8, 8, 1, 3, 1,
+     1, 4, 7, 6, 2, 6, 7, 8, 3, 3, 5, 6, 6, 5, 1, 4, 3, 6, 8, 3, 2, 2, 3, 8,
+     5, 1, 1, 2, 6, 8, 4, 5, 8, 7, 5, 5, 8, 7, 3, 8, 2, 5, 8, 5, 8, 3, 7, 3,
+     4, 3, 6, 7, 3, 8, 6, 3, 2, 7, 5, 8, 7, 2, 5, 3, 1, 5, 2, 8, 6, 6, 6, 6,
+     7, 4, 7, 5, 5, 3, 3, 8, 8, 5, 8, 5, 7, 5, 4, 8, 2, 7, 5, 3, 1, 7, 6, 2,
+     6, 8, 1, 5, 8, 3, 2, 6, 1, 1, 6, 3, 8, 1, 8, 8, 4, 1, 3, 7, 2, 7, 8, 2,
+     1, 1, 8, 3, 3, 3, 1, 4, 8, 1, 1, 8, 4, 1, 7, 3, 2, 8, 7, 2, 7, 7, 4, 8,
+     4, 7, 8, 5, 8, 7, 3, 7, 3, 6, 7, 8, 7, 1, 1, 7, 6, 8, 3, 3, 2, 1, 7, 1,
+     5, 2, 1, 3, 8, 7, 2, 5, 4, 8, 8, 2, 3, 7, 5, 5, 3, 5, 5, 7, 4, 7, 1, 4,
+     2, 4, 7, 4, 1, 5, 1, 8, 7, 8, 1, 8, 3, 2, 6, 3, 6, 3, 8, 8, 8, 4, 3, 3,
+     5, 6, 5, 2, 2, 6, 6, 8, 2, 2, 5, 4, 5, 8, 8, 8, 8, 5, 4, 5, 7, 1, 4, 7,
+     2, 2, 1, 7, 1, 7, 2, 2, 8, 1, 2, 4, 8, 2, 7, 6, 2, 5, 7, 5, 4, 8, 3, 1,
+     3, 6, 7, 8, 1, 3, 8, 5, 8, 6, 4, 3, 4, 1, 8, 6, 5, 3, 8, 5, 3, 8, 2, 4,
+     6, 3, 3, 1, 4, 4, 5, 2, 3, 3, 8, 1, 5, 5, 4, 5, 4, 4, 5, 8, 8, 2, 4, 1,
+     4, 7, 7, 3, 5, 3, 8, 6, 4, 8, 7, 5, 3, 7, 8, 6, 3, 8, 8, 6, 7, 2, 4, 6,
+     3, 4, 1, 1, 5, 5, 3, 4, 8, 6, 2, 6, 3, 2, 6, 5, 1, 6, 3, 1, 3, 3, 8, 6,
+     8, 8, 2, 2, 8, 4, 1, 8, 3, 3, 3, 3, 8, 8, 8, 3, 8, 6, 2, 8, 8, 6, 8, 8,
+     1, 4, 2, 4, 5, 4, 7, 3, 2, 5, 4, 3, 2, 4, 8, 4, 6, 6, 1, 8, 8, 6, 7, 3,
+     2, 7, 2, 4, 4, 4, 8, 6, 5, 3, 7, 8, 8, 6, 8, 8, 5, 4, 1, 8, 1, 8, 4, 8,
+     1, 1, 4, 7, 2, 5, 2, 5, 7, 5, 8, 5, 2, 4, 6, 7, 2, 1, 3, 5, 6, 8, 7, 8,
+     5, 5, 3, 3, 7, 2, 2, 7, 3, 4, 2, 1, 3, 1, 5, 3, 2, 1, 5, 2, 2, 8, 8, 7,
+     6, 8, 5, 8, 7, 8, 7, 8, 3, 2, 5, 2, 5, 1, 7, 8, 8, 6, 2, 4, 3, 5, 7, 3,
+     5, 6, 7, 8, 1, 4, 5, 6, 7, 5, 7, 3, 3, 6, 4, 8, 5, 2, 3, 5, 3, 8, 6, 5,
+     5, 3, 6, 2, 3, 2, 4, 5, 4, 7, 3, 4, 4, 6, 3, 1, 4, 5, 3, 2, 8, 5, 6, 3,
+     7, 5, 4, 8, 8, 3, 4, 1, 2, 7, 1, 1, 1, 2, 5, 3, 4, 4, 1, 3, 2, 2, 4, 4,
+     6, 1, 6, 2, 1, 8, 2, 6, 8, 7, 6, 6, 1, 1, 5, 2, 4, 8, 8, 8, 6, 8, 8, 8,
+     1, 3, 4, 5, 5, 5, 4, 2, 4, 8, 2, 8, 2, 5, 2, 1, 3, 6, 5, 7, 2, 8, 7, 8,
+     7, 1, 4, 6, 7, 1, 8, 7, 6, 8, 3, 5, 4, 6, 8, 7, 3, 8, 3, 1, 4, 1, 2, 6,
+     1, 4, 6, 7, 6, 8, 8, 8, 3, 4, 5, 5, 2, 2, 1, 7, 6, 2, 4, 5, 1, 1, 1, 6,
+     3, 8, 8, 4, 1, 5, 5, 8, 4, 2, 3, 1, 3, 4, 5, 6, 7, 2, 8, 4, 8, 2, 1, 6,
+     8, 4, 2, 1, 1, 8, 6, 8, 8, 4, 6, 1, 8, 8, 8, 3, 7, 4, 1, 3, 6, 6, 7, 3,
+     6, 7, 2, 5, 7, 2, 8, 6, 3, 4, 1, 5, 2, 8, 4, 4, 4, 3, 7, 3, 3, 5, 7, 8,
+     8, 3, 6, 4, 7, 8, 8, 6, 5, 7, 1, 8, 3, 7, 4, 7, 7, 5, 3, 4, 8, 6, 8, 6,
+     4, 1, 8, 3, 5, 1, 7, 2, 5, 6, 7, 2, 5, 8, 6, 7, 6, 7, 7, 2, 6, 4, 5, 8,
+     7, 3, 5, 3, 1, 5, 4, 6, 6, 4, 1, 4, 6, 2, 2, 8, 4, 5, 2, 4, 1, 4, 2, 5,
+     8, 3, 8, 6, 4, 8, 4, 6, 3, 2, 1, 5, 5, 8, 2, 1, 4, 2, 2, 2, 6, 6, 3, 5,
+     2, 5, 1, 5, 3, 4, 4, 3, 1, 4, 6, 5, 4, 3, 4, 6, 2, 2, 1, 1, 6, 2, 4, 6,
+     6, 1, 7, 8, 4, 8, 2, 7, 3, 8, 3, 4, 2, 7, 1, 1, 8, 1, 8, 6, 7, 5, 7, 7,
+     1, 7, 8, 7, 6, 7, 8, 4, 7, 4, 6, 8, 6, 7, 4, 7, 8, 4, 1, 8, 3, 8, 7, 1,
+     2, 6, 1, 2, 5, 5, 7, 7, 8, 6, 5, 5, 2, 8, 8, 7, 1, 7, 1, 8, 4, 6, 5, 1,
+     3, 8, 7, 1, 7, 7, 5, 5, 8, 8, 6, 2, 4, 2, 1, 4, 4, 3, 3, 4, 3, 3, 5, 8,
+     2, 4, 3, 4, 3, 3, 2, 4, 6, 6, 4, 6, 1, 7, 5, 1, 1, 8, 6, 6, 2, 1, 5, 1,
+     4, 6, 4, 6, 8, 6, 3, 5, 7, 8, 8, 2, 2, 5, 1, 6, 3, 1, 5, 3, 2, 7, 5, 6,
+     5, 6, 6, 3, 2, 4, 8, 7, 5, 4, 8, 5, 8, 1, 5, 4, 6, 3, 8, 5, 7, 1, 6, 7,
+     6, 2, 6, 5, 2, 8, 5, 3, 1, 6, 8, 4, 6, 5, 5, 8, 4, 6, 4, 2, 8, 1, 4, 8,
+     8, 7, 7, 2, 1, 8, 4, 2, 1, 1, 1, 5, 8, 3, 8, 8, 1, 4, 7, 5, 3, 4, 8, 8,
+     5, 5, 7, 8, 1, 3, 8, 1, 5, 7, 8, 2, 5, 4, 8, 3, 6, 3, 7, 5, 8, 5, 1, 2,
+     3, 2, 4, 5, 6, 7, 6, 5, 6, 1, 6, 2, 4, 6, 2, 5, 5, 7, 1, 6, 4, 5, 8, 3,
+     1, 2, 4, 8, 4, 8, 2, 2, 3, 1, 4, 7, 7, 8, 6, 8, 5, 7, 8, 4, 5, 8, 2, 8,
+     6, 8, 3, 8, 8, 2, 2, 4, 5, 3, 5, 2, 8, 1, 1, 2, 2, 7, 2, 1, 8, 7, 3, 1,
+     5, 1, 3, 5, 4, 4, 3, 4, 2, 8, 7, 7];
+
+pub static KOREAN_CHARS: [u16; 804] =
+    [0xAC00, 0xAC01, 0xAC04, 0xAC08, 0xAC10, 0xAC11, 0xAC12, 0xAC14, 0xAC15, 0xAC16, 0xAC19,
+     0xAC1C, 0xAC1D, 0xAC2F, 0xAC31, 0xAC70, 0xAC74, 0xAC78, 0xAC80, 0xAC83, 0xAC8C, 0xAC9F,
+     0xACA0, 0xACA8, 0xACA9, 0xACAC, 0xACB0, 0xACB9, 0xACBC, 0xACBD, 0xACC4, 0xACE0, 0xACE4,
+     0xACE8, 0xACF1, 0xACF3, 0xACF5, 0xACFC, 0xAD00, 0xAD04, 0xAD34, 0xAD50, 0xAD6C, 0xAD6D,
+     0xAD70, 0xAD88, 0xAD8C, 0xADC0, 0xADDC, 0xADE0, 0xADF8, 0xADF9, 0xADFC, 0xAE00, 0xAE08,
+     0xAE09, 0xAE30, 0xAE34, 0xAE38, 0xAE3A, 0xAE40, 0xAE41, 0xAE43, 0xAE45, 0xAE4A, 0xAE4C,
+     0xAE54, 0xAE5C, 0xAE68, 0xAEBC, 0xAED8, 0xAF2D, 0xAF34, 0xAFB8, 0xAFBC, 0xAFC0, 0xAFC8,
+     0xAFC9, 0xAFD4, 0xAFE8, 0xB00C, 0xB010, 0xB01C, 0xB01D, 0xB044, 0xB04A, 0xB04C, 0xB054,
+     0xB055, 0xB05D, 0xB07C, 0xB098, 0xB09C, 0xB0A0, 0xB0A1, 0xB0A8, 0xB0A9, 0xB0AC, 0xB0AE,
+     0xB0B1, 0xB0B4, 0xB0B8, 0xB0BC, 0xB0C4, 0xB0C5, 0xB0C8, 0xB0D0, 0xB0E5, 0xB108, 0xB10C,
+     0xB110, 0xB113, 0xB118, 0xB123, 0xB124, 0xB137, 0xB144, 0xB150, 0xB178, 0xB179, 0xB17C,
+     0xB192, 0xB193, 0xB194, 0xB204, 0xB205, 0xB208, 0xB20C, 0xB215, 0xB274, 0xB284, 0xB290,
+     0xB294, 0xB298, 0xB2A5, 0xB2C8, 0xB2C9, 0xB2CC, 0xB2D0, 0xB2D8, 0xB2D9, 0xB2DB, 0xB2DD,
+     0xB2E4, 0xB2E5, 0xB2E8, 0xB2EB, 0xB2EC, 0xB2F4, 0xB2F5, 0xB2F9, 0xB300, 0xB354, 0xB358,
+     0xB35C, 0xB364, 0xB367, 0xB36E, 0xB370, 0xB371, 0xB374, 0xB378, 0xB3C4, 0xB3C5, 0xB3CC,
+     0xB3D9, 0xB410, 0xB418, 0xB41C, 0xB420, 0xB428, 0xB429, 0xB450, 0xB458, 0xB460, 0xB461,
+     0xB4A4, 0xB4B7, 0xB4C8, 0xB4DC, 0xB4DD, 0xB4E0, 0xB4E4, 0xB4E6, 0xB4EC, 0xB4ED, 0xB4EF,
+     0xB4F1, 0xB514, 0xB515, 0xB518, 0xB529, 0xB530, 0xB538, 0xB54C, 0xB584, 0xB5A0, 0xB5A4,
+     0xB5A8, 0xB5BB, 0xB5BC, 0xB610, 0xB6F0, 0xB6F8, 0xB700, 0xB701, 0xB728, 0xB73B, 0xB77C,
+     0xB77D, 0xB780, 0xB78C, 0xB78D, 0xB791, 0xB798, 0xB799, 0xB79C, 0xB7A8, 0xB7A9, 0xB7AB,
+     0xB7B5, 0xB7C9, 0xB7EC, 0xB7ED, 0xB7F0, 0xB7F4, 0xB7FC, 0xB7FD, 0xB800, 0xB807, 0xB808,
+     0xB809, 0xB80C, 0xB81B, 0xB824, 0xB825, 0xB828, 0xB82C, 0xB838, 0xB839, 0xB840, 0xB85C,
+     0xB85D, 0xB860, 0xB864, 0xB86C, 0xB86D, 0xB86F, 0xB8B0, 0xB8CC, 0xB8E8, 0xB8E9, 0xB8EC,
+     0xB8F0, 0xB8F9, 0xB958, 0xB960, 0xB968, 0xB974, 0xB978, 0xB97C, 0xB984, 0xB985, 0xB9AC,
+     0xB9AD, 0xB9B0, 0xB9B4, 0xB9BC, 0xB9BD, 0xB9BF, 0xB9C1, 0xB9C8, 0xB9C9, 0xB9CC, 0xB9CE,
+     0xB9D0, 0xB9DD, 0xB9DE, 0xB9E4, 0xB9E5, 0xB9E8, 0xB9F4, 0xB9F5, 0xB9F7, 0xBA38, 0xBA3C,
+     0xBA40, 0xBA48, 0xBA4B, 0xBA54, 0xBA55, 0xBA58, 0xBA64, 0xBA67, 0xBA70, 0xBA74, 0xBA85,
+     0xBA87, 0xBAA8, 0xBAA9, 0xBAAC, 0xBAB0, 0xBABB, 0xBABD, 0xBB18, 0xBB34, 0xBB35, 0xBB36,
+     0xBB38, 0xBB3C, 0xBB54, 0xBBAC, 0xBBC0, 0xBBF8, 0xBBF9, 0xBBFC, 0xBC00, 0xBC0B, 0xBC0D,
+     0xBC0F, 0xBC11, 0xBC14, 0xBC15, 0xBC16, 0xBC18, 0xBC1B, 0xBC1C, 0xBC1D, 0xBC29, 0xBC30,
+     0xBC31, 0xBC34, 0xBC84, 0xBC88, 0xBC8C, 0xBC94, 0xBC95, 0xBC97, 0xBCA0, 0xBCA1, 0xBCA4,
+     0xBCA8, 0xBCB3, 0xBCB5, 0xBCC0, 0xBCC4, 0xBCD1, 0xBCF4, 0xBCF5, 0xBCF8, 0xBCFC, 0xBD04,
+     0xBD05, 0xBD09, 0xBD80, 0xBD81, 0xBD84, 0xBD88, 0xBD99, 0xBDF0, 0xBE0C, 0xBE14, 0xBE44,
+     0xBE45, 0xBE48, 0xBE4C, 0xBE54, 0xBE59, 0xBE60, 0xBE68, 0xBE7C, 0xBE80, 0xBE84, 0xBF51,
+     0xBFD0, 0xC0AC, 0xC0AD, 0xC0B0, 0xC0B4, 0xC0BC, 0xC0BD, 0xC0C1, 0xC0C8, 0xC0C9, 0xC0CC,
+     0xC0D8, 0xC0DD, 0xC0E4, 0xC0F7, 0xC11C, 0xC11D, 0xC11E, 0xC120, 0xC124, 0xC12C, 0xC12D,
+     0xC131, 0xC138, 0xC139, 0xC13C, 0xC140, 0xC148, 0xC14B, 0xC154, 0xC158, 0xC15C, 0xC170,
+     0xC178, 0xC18C, 0xC18D, 0xC190, 0xC194, 0xC1A1, 0xC1C4, 0xC218, 0xC21C, 0xC220, 0xC228,
+     0xC22B, 0xC258, 0xC26C, 0xC27C, 0xC27D, 0xC288, 0xC2A4, 0xC2A8, 0xC2AC, 0xC2B5, 0xC2B7,
+     0xC2B9, 0xC2DC, 0xC2DD, 0xC2E0, 0xC2E4, 0xC2EC, 0xC2ED, 0xC2F1, 0xC2F6, 0xC30D, 0xC368,
+     0xC36C, 0xC37C, 0xC4F0, 0xC4F4, 0xC4F8, 0xC500, 0xC501, 0xC528, 0xC529, 0xC544, 0xC545,
+     0xC548, 0xC54A, 0xC54C, 0xC554, 0xC555, 0xC558, 0xC559, 0xC55E, 0xC560, 0xC561, 0xC568,
+     0xC570, 0xC571, 0xC57C, 0xC57D, 0xC580, 0xC584, 0xC587, 0xC591, 0xC595, 0xC5B4, 0xC5B5,
+     0xC5B8, 0xC5BB, 0xC5BC, 0xC5C4, 0xC5C5, 0xC5C6, 0xC5C7, 0xC5C8, 0xC5D0, 0xC5D1, 0xC5D4,
+     0xC5D8, 0xC5EC, 0xC5ED, 0xC5F0, 0xC5F4, 0xC5FD, 0xC600, 0xC601, 0xC606, 0xC608, 0xC61B,
+     0xC624, 0xC625, 0xC628, 0xC62C, 0xC62E, 0xC634, 0xC635, 0xC640, 0xC644, 0xC648, 0xC651,
+     0xC654, 0xC655, 0xC678, 0xC67C, 0xC694, 0xC6A9, 0xC6B0, 0xC6B4, 0xC6B8, 0xC6C0, 0xC6C1,
+     0xC6C3, 0xC6CC, 0xC6D0, 0xC6D4, 0xC6E0, 0xC6E8, 0xC6F0, 0xC6F9, 0xC704, 0xC708, 0xC717,
+     0xC720, 0xC721, 0xC724, 0xC728, 0xC73C, 0xC740, 0xC744, 0xC74C, 0xC751, 0xC758, 0xC774,
+     0xC775, 0xC778, 0xC77C, 0xC77D, 0xC783, 0xC784, 0xC785, 0xC787, 0xC788, 0xC789, 0xC790,
+     0xC791, 0xC794, 0xC798, 0xC7A0, 0xC7A1, 0xC7A5, 0xC7AC, 0xC7AD, 0xC800, 0xC801, 0xC804,
+     0xC808, 0xC810, 0xC811, 0xC815, 0xC81C, 0xC81D, 0xC820, 0xC82F, 0xC838, 0xC83C, 0xC84C,
+     0xC870, 0xC871, 0xC874, 0xC880, 0xC885, 0xC88B, 0xC88C, 0xC8C4, 0xC8FC, 0xC8FD, 0xC900,
+     0xC904, 0xC90C, 0xC90D, 0xC911, 0xC974, 0xC988, 0xC989, 0xC998, 0xC99D, 0xC9C0, 0xC9C1,
+     0xC9C4, 0xC9C8, 0xC9D0, 0xC9D1, 0xC9D3, 0xC9D5, 0xC9DC, 0xC9DD, 0xC9E7, 0xC9F8, 0xCA0C,
+     0xCABC, 0xCABD, 0xCC28, 0xCC29, 0xCC2C, 0xCC30, 0xCC38, 0xCC3D, 0xCC3E, 0xCC44, 0xCC45,
+     0xCC98, 0xCC9C, 0xCCA8, 0xCCA9, 0xCCAB, 0xCCAD, 0xCCB4, 0xCCD0, 0xCCE4, 0xCD08, 0xCD1D,
+     0xCD5C, 0xCD94, 0xCD95, 0xCD98, 0xCD9C, 0xCDA4, 0xCDA5, 0xCDA9, 0xCDB0, 0xCDE8, 0xCE04,
+     0xCE20, 0xCE21, 0xCE35, 0xCE58, 0xCE59, 0xCE5C, 0xCE60, 0xCE68, 0xCE69, 0xCE6D, 0xCE74,
+     0xCE78, 0xCE7C, 0xCE84, 0xCE90, 0xCE94, 0xCE98, 0xCEA1, 0xCEE4, 0xCEE8, 0xCEEC, 0xCEF4,
+     0xCF00, 0xCF13, 0xCF1C, 0xCF2C, 0xCF54, 0xCF58, 0xCF5C, 0xCF64, 0xCF69, 0xCF70, 0xCFE0,
+     0xCFFC, 0xD000, 0xD004, 0xD034, 0xD035, 0xD050, 0xD06C, 0xD070, 0xD074, 0xD07C, 0xD07D,
+     0xD0A4, 0xD0A8, 0xD0AC, 0xD0B4, 0xD0B5, 0xD0B7, 0xD0B9, 0xD0C0, 0xD0C4, 0xD0C8, 0xD0D0,
+     0xD0D1, 0xD0D5, 0xD0DC, 0xD0DD, 0xD0EC, 0xD0ED, 0xD130, 0xD134, 0xD138, 0xD14C, 0xD14D,
+     0xD150, 0xD154, 0xD15C, 0xD1A0, 0xD1A1, 0xD1B0, 0xD1B1, 0xD1B5, 0xD22C, 0xD280, 0xD29C,
+     0xD2B8, 0xD2B9, 0xD2C0, 0xD2F0, 0xD2F1, 0xD2F4, 0xD2F8, 0xD305, 0xD30C, 0xD310, 0xD314,
+     0xD31D, 0xD321, 0xD328, 0xD329, 0xD32C, 0xD37C, 0xD384, 0xD38C, 0xD398, 0xD399, 0xD3B4,
+     0xD3B8, 0xD3C9, 0xD3EC, 0xD3ED, 0xD3F4, 0xD3FC, 0xD45C, 0xD478, 0xD480, 0xD488, 0xD4E8,
+     0xD504, 0xD508, 0xD50C, 0xD53C, 0xD53D, 0xD540, 0xD544, 0xD551, 0xD558, 0xD559, 0xD55C,
+     0xD560, 0xD568, 0xD569, 0xD56D, 0xD574, 0xD575, 0xD578, 0xD588, 0xD589, 0xD5A5, 0xD5C8,
+     0xD5D8, 0xD5DD, 0xD5E4, 0xD5E8, 0xD604, 0xD608, 0xD611, 0xD614, 0xD615, 0xD638, 0xD639,
+     0xD63C, 0xD640, 0xD648, 0xD654, 0xD655, 0xD658, 0xD65C, 0xD669, 0xD68C, 0xD68D, 0xD69F,
+     0xD6A8, 0xD6C4, 0xD718, 0xD734, 0xD750, 0xD754, 0xD76C, 0xD788, 0xD78C, 0xD790, 0xD798,
+     0xD799];
+
+pub static KOREAN_WEIGHTS: [u8; 804] =
+    [8, 8, 8, 5, 6, 5, 8, 3, 6, 2, 7, 8, 5, 5, 4, 8, 8, 4, 8, 7, 7, 5, 4, 4,
+     6, 5, 8, 4, 2, 8, 8, 8, 2, 4, 2, 3, 8, 8, 7, 5, 1, 5, 8, 7, 3, 1, 7, 5,
+     7, 3, 8, 2, 7, 7, 6, 5, 8, 5, 7, 3, 4, 5, 5, 4, 4, 6, 2, 2, 3, 3, 6, 3,
+     6, 7, 2, 6, 5, 5, 2, 2, 5, 4, 2, 2, 4, 4, 3, 3, 3, 8, 2, 8, 4, 6, 2, 6,
+     5, 6, 3, 1, 8, 3, 5, 4, 6, 3, 3, 2, 8, 1, 7, 2, 5, 5, 6, 4, 4, 2, 6, 1,
+     5, 5, 4, 1, 6, 1, 2, 3, 2, 6, 1, 3, 8, 4, 8, 8, 4, 6, 2, 6, 7, 2, 1, 8,
+     3, 8, 6, 6, 2, 4, 8, 8, 8, 3, 3, 5, 1, 6, 8, 8, 4, 5, 8, 6, 6, 8, 2, 8,
+     8, 6, 7, 7, 7, 4, 1, 3, 7, 2, 6, 8, 3, 7, 8, 2, 2, 6, 1, 6, 8, 1, 1, 7,
+     7, 1, 8, 1, 2, 5, 1, 3, 3, 8, 3, 1, 6, 4, 2, 3, 8, 6, 5, 4, 4, 5, 8, 3,
+     8, 7, 3, 4, 5, 5, 8, 4, 7, 2, 8, 4, 2, 4, 8, 8, 2, 2, 8, 8, 5, 7, 4, 8,
+     1, 8, 8, 5, 6, 5, 4, 5, 3, 8, 6, 1, 2, 5, 7, 8, 4, 4, 8, 8, 8, 8, 5, 8,
+     6, 6, 7, 7, 6, 5, 7, 8, 7, 8, 6, 6, 4, 7, 7, 4, 4, 2, 5, 4, 5, 5, 4, 5,
+     1, 8, 1, 3, 4, 2, 6, 8, 8, 4, 8, 8, 3, 3, 8, 2, 2, 8, 4, 6, 8, 4, 1, 1,
+     5, 8, 1, 4, 6, 8, 4, 6, 3, 8, 3, 4, 8, 7, 7, 1, 8, 7, 7, 1, 8, 8, 1, 7,
+     8, 6, 8, 2, 5, 5, 3, 2, 8, 7, 7, 8, 8, 8, 7, 1, 3, 4, 8, 4, 8, 7, 6, 6,
+     8, 8, 8, 1, 6, 4, 4, 1, 6, 2, 4, 2, 2, 2, 2, 8, 7, 7, 5, 1, 2, 8, 8, 8,
+     1, 2, 7, 1, 3, 8, 7, 2, 8, 8, 5, 1, 8, 8, 5, 3, 5, 1, 6, 3, 8, 1, 3, 6,
+     8, 8, 5, 3, 5, 5, 8, 7, 5, 4, 7, 4, 3, 4, 2, 5, 8, 3, 6, 8, 3, 2, 8, 8,
+     7, 8, 6, 8, 5, 1, 3, 4, 4, 2, 7, 3, 7, 3, 5, 2, 3, 8, 2, 8, 8, 8, 7, 7,
+     8, 2, 6, 4, 4, 1, 1, 1, 8, 7, 1, 1, 1, 6, 3, 8, 3, 7, 4, 4, 3, 8, 8, 2,
+     8, 8, 1, 5, 4, 8, 7, 8, 8, 2, 3, 7, 1, 7, 5, 8, 1, 4, 7, 4, 5, 8, 8, 7,
+     1, 4, 3, 3, 7, 5, 8, 8, 8, 7, 5, 6, 4, 4, 6, 8, 4, 3, 6, 1, 2, 8, 5, 1,
+     8, 2, 1, 4, 8, 8, 8, 8, 5, 8, 8, 2, 8, 8, 8, 3, 8, 8, 1, 8, 1, 8, 8, 2,
+     8, 6, 5, 8, 8, 7, 8, 8, 8, 8, 6, 8, 8, 8, 7, 2, 6, 7, 3, 6, 8, 5, 7, 2,
+     7, 2, 3, 1, 8, 3, 8, 8, 5, 5, 8, 1, 3, 3, 3, 8, 8, 6, 8, 5, 5, 7, 3, 4,
+     5, 4, 4, 7, 2, 1, 7, 5, 2, 3, 1, 8, 6, 8, 5, 5, 8, 3, 2, 3, 5, 7, 8, 3,
+     2, 7, 4, 8, 8, 7, 3, 8, 5, 3, 6, 2, 6, 2, 2, 3, 2, 8, 6, 3, 4, 5, 3, 4,
+     7, 3, 8, 1, 6, 3, 5, 1, 8, 6, 6, 5, 6, 6, 3, 1, 8, 6, 6, 3, 2, 1, 4, 6,
+     6, 1, 1, 1, 3, 8, 6, 7, 5, 5, 8, 4, 2, 3, 2, 4, 3, 8, 4, 5, 5, 2, 2, 8,
+     7, 4, 5, 8, 7, 2, 8, 7, 3, 1, 8, 7, 3, 3, 3, 7, 6, 2, 5, 8, 6, 5, 7, 1,
+     4, 3, 4, 8, 4, 3, 2, 1, 8, 3, 1, 8, 1, 1, 8, 1, 3, 6, 4, 8, 5, 6, 4, 8,
+     5, 4, 2, 2, 8, 1, 7, 6, 5, 4, 8, 6, 8, 2, 8, 8, 8, 8, 8, 8, 1, 4, 8, 8,
+     6, 7, 4, 3, 7, 1, 8, 1, 1, 2, 8, 8, 5, 4, 3, 4, 8, 8, 8, 7, 4, 4, 5, 4,
+     6, 6, 2, 4, 3, 1, 2, 6, 3, 1, 1, 2];
+
@@ -50,6 +50,32 @@ impl EucJpDecoder {
         VariantDecoder::EucJp(EucJpDecoder { pending: EucJpPending::None })
     }
 
+    pub fn has_pending_state(&self) -> bool {
+        !self.pending.is_none()
+    }
+
+    /// Writes the bytes of the incomplete sequence, if any.
+    #[cfg(feature = "alloc")]
+    pub fn snapshot_state(&self, state: &mut Vec<u8>) -> bool {
+        match self.pending {
+            EucJpPending::None => {}
+            EucJpPending::Jis0208Lead(jis0208_lead_minus_offset) => {
+                state.push(jis0208_lead_minus_offset + 0xA1);
+            }
+            EucJpPending::Jis0212Shift => {
+                state.push(0x8F);
+            }
+            EucJpPending::Jis0212Lead(jis0212_lead_minus_offset) => {
+                state.push(0x8F);
+                state.push(jis0212_lead_minus_offset + 0xA1);
+            }
+            EucJpPending::HalfWidthKatakana => {
+                state.push(0x8E);
+            }
+        }
+        true
+    }
+
     fn plus_one_if_lead(&self, byte_length: usize) -> Option<usize> {
         byte_length.checked_add(if self.pending.is_none() { 0 } else { 1 })
     }
@@ -387,6 +413,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_jis0208_decode_all() {
         let input = include_bytes!("test_data/jis0208_in.txt");
         let expectation = include_str!("test_data/jis0208_in_ref.txt");
@@ -396,6 +423,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_jis0208_encode_all() {
         let input = include_str!("test_data/jis0208_out.txt");
         let expectation = include_bytes!("test_data/jis0208_out_ref.txt");
@@ -406,6 +434,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_jis0212_decode_all() {
         let input = include_bytes!("test_data/jis0212_in.txt");
         let expectation = include_str!("test_data/jis0212_in_ref.txt");
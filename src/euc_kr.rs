@@ -24,6 +24,19 @@ impl EucKrDecoder {
         VariantDecoder::EucKr(EucKrDecoder { lead: None })
     }
 
+    pub fn has_pending_state(&self) -> bool {
+        self.lead.is_some()
+    }
+
+    /// Writes the lead byte, if any.
+    #[cfg(feature = "alloc")]
+    pub fn snapshot_state(&self, state: &mut Vec<u8>) -> bool {
+        if let Some(lead_minus_offset) = self.lead {
+            state.push(lead_minus_offset + 0x81);
+        }
+        true
+    }
+
     fn plus_one_if_lead(&self, byte_length: usize) -> Option<usize> {
         byte_length.checked_add(
             match self.lead {
@@ -362,6 +375,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_euc_kr_decode_all() {
         let input = include_bytes!("test_data/euc_kr_in.txt");
         let expectation = include_str!("test_data/euc_kr_in_ref.txt");
@@ -371,6 +385,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_euc_kr_encode_all() {
         let input = include_str!("test_data/euc_kr_out.txt");
         let expectation = include_bytes!("test_data/euc_kr_out_ref.txt");
@@ -61,6 +61,35 @@ impl Gb18030Decoder {
         )
     }
 
+    pub fn has_pending_state(&self) -> bool {
+        !self.pending.is_none() || self.pending_ascii.is_some()
+    }
+
+    /// Writes the bytes of the incomplete sequence, if any. Fails if there is
+    /// an ASCII byte waiting to be output.
+    #[cfg(feature = "alloc")]
+    pub fn snapshot_state(&self, state: &mut Vec<u8>) -> bool {
+        if self.pending_ascii.is_some() {
+            return false;
+        }
+        match self.pending {
+            Gb18030Pending::None => {}
+            Gb18030Pending::One(first_minus_offset) => {
+                state.push(first_minus_offset + 0x81);
+            }
+            Gb18030Pending::Two(first_minus_offset, second_minus_offset) => {
+                state.push(first_minus_offset + 0x81);
+                state.push(second_minus_offset + 0x30);
+            }
+            Gb18030Pending::Three(first_minus_offset, second_minus_offset, third_minus_offset) => {
+                state.push(first_minus_offset + 0x81);
+                state.push(second_minus_offset + 0x30);
+                state.push(third_minus_offset + 0x81);
+            }
+        }
+        true
+    }
+
     fn extra_from_state(&self, byte_length: usize) -> Option<usize> {
         byte_length.checked_add(
             self.pending.count() +
@@ -681,6 +710,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_gb18030_decode_all() {
         let input = include_bytes!("test_data/gb18030_in.txt");
         let expectation = include_str!("test_data/gb18030_in_ref.txt");
@@ -690,6 +720,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_gb18030_encode_all() {
         let input = include_str!("test_data/gb18030_out.txt");
         let expectation = include_bytes!("test_data/gb18030_out_ref.txt");
@@ -695,12 +695,12 @@ impl<'a> Utf8Destination<'a> {
     pub fn copy_utf8_up_to_invalid_from(&mut self, source: &mut ByteSource) {
         let src_remaining = &source.slice[source.pos..];
         let mut dst_remaining = &mut self.slice[self.pos..];
-        let min_len = ::std::cmp::min(src_remaining.len(), dst_remaining.len());
+        let min_len = ::core::cmp::min(src_remaining.len(), dst_remaining.len());
         // Validate first, then memcpy to let memcpy do its thing even for
         // non-ASCII. (And potentially do something better than SSE2 for ASCII.)
         let valid_len = utf8_valid_up_to(&src_remaining[..min_len]);
         unsafe {
-            ::std::ptr::copy_nonoverlapping(
+            ::core::ptr::copy_nonoverlapping(
                 src_remaining.as_ptr(),
                 dst_remaining.as_mut_ptr(),
                 valid_len,
@@ -744,7 +744,7 @@ impl<'a> Utf16Source<'a> {
         self.pos += 1;
         let unit_minus_surrogate_start = unit.wrapping_sub(0xD800);
         if unit_minus_surrogate_start > (0xDFFF - 0xD800) {
-            return unsafe { ::std::mem::transmute(unit) };
+            return unsafe { ::core::mem::transmute(unit) };
         }
         if unit_minus_surrogate_start <= (0xDFFF - 0xDBFF) {
             // high surrogate
@@ -755,7 +755,7 @@ impl<'a> Utf16Source<'a> {
                     // The next code unit is a low surrogate. Advance position.
                     self.pos += 1;
                     return unsafe {
-                               ::std::mem::transmute(
+                               ::core::mem::transmute(
                             (unit << 10) + second -
                             (((0xD800u32 << 10) - 0x10000u32) + 0xDC00u32)
                         )
@@ -794,7 +794,7 @@ impl<'a> Utf16Source<'a> {
                     return Unicode::NonAscii(
                         NonAscii::Astral(
                             unsafe {
-                                ::std::mem::transmute(
+                                ::core::mem::transmute(
                                     ((unit as u32) << 10) + (second as u32) -
                                     (((0xD800u32 << 10) - 0x10000u32) + 0xDC00u32)
                                 )
@@ -865,7 +865,7 @@ impl<'a> Utf16Source<'a> {
                                     self.pos += 1;
                                     NonAscii::Astral(
                                         unsafe {
-                                            ::std::mem::transmute(
+                                            ::core::mem::transmute(
                                                 ((unit as u32) << 10) + (second as u32) -
                                                 (((0xD800u32 << 10) - 0x10000u32) + 0xDC00u32)
                                             )
@@ -942,7 +942,7 @@ impl<'a> Utf16Source<'a> {
                                     self.pos += 1;
                                     NonAscii::Astral(
                                         unsafe {
-                                            ::std::mem::transmute(
+                                            ::core::mem::transmute(
                                                 ((unit as u32) << 10) + (second as u32) -
                                                 (((0xD800u32 << 10) - 0x10000u32) + 0xDC00u32)
                                             )
@@ -1059,25 +1059,25 @@ impl<'a> Utf8Source<'a> {
         let unit = self.slice[self.pos] as u32;
         if unit < 0x80u32 {
             self.pos += 1;
-            return unsafe { ::std::mem::transmute(unit) };
+            return unsafe { ::core::mem::transmute(unit) };
         }
         if unit < 0xE0u32 {
             let point = ((unit & 0x1Fu32) << 6) | (self.slice[self.pos + 1] as u32 & 0x3Fu32);
             self.pos += 2;
-            return unsafe { ::std::mem::transmute(point) };
+            return unsafe { ::core::mem::transmute(point) };
         }
         if unit < 0xF0u32 {
             let point = ((unit & 0xFu32) << 12) |
                         ((self.slice[self.pos + 1] as u32 & 0x3Fu32) << 6) |
                         (self.slice[self.pos + 2] as u32 & 0x3Fu32);
             self.pos += 3;
-            return unsafe { ::std::mem::transmute(point) };
+            return unsafe { ::core::mem::transmute(point) };
         }
         let point = ((unit & 0x7u32) << 18) | ((self.slice[self.pos + 1] as u32 & 0x3Fu32) << 12) |
                     ((self.slice[self.pos + 2] as u32 & 0x3Fu32) << 6) |
                     (self.slice[self.pos + 3] as u32 & 0x3Fu32);
         self.pos += 4;
-        unsafe { ::std::mem::transmute(point) }
+        unsafe { ::core::mem::transmute(point) }
     }
     #[inline(always)]
     fn read_enum(&mut self) -> Unicode {
@@ -1105,7 +1105,7 @@ impl<'a> Utf8Source<'a> {
                     ((self.slice[self.pos + 2] as u32 & 0x3Fu32) << 6) |
                     (self.slice[self.pos + 3] as u32 & 0x3Fu32);
         self.pos += 4;
-        Unicode::NonAscii(NonAscii::Astral(unsafe { ::std::mem::transmute(point) }))
+        Unicode::NonAscii(NonAscii::Astral(unsafe { ::core::mem::transmute(point) }))
     }
     #[inline(always)]
     fn unread(&mut self) -> usize {
@@ -1160,7 +1160,7 @@ impl<'a> Utf8Source<'a> {
                                     ((self.slice[self.pos + 2] as u32 & 0x3Fu32) << 6) |
                                     (self.slice[self.pos + 3] as u32 & 0x3Fu32);
                         self.pos += 4;
-                        NonAscii::Astral(unsafe { ::std::mem::transmute(point) })
+                        NonAscii::Astral(unsafe { ::core::mem::transmute(point) })
                     }
                 }
             }
@@ -1211,7 +1211,7 @@ impl<'a> Utf8Source<'a> {
                                         ((self.slice[self.pos + 2] as u32 & 0x3Fu32) << 6) |
                                         (self.slice[self.pos + 3] as u32 & 0x3Fu32);
                             self.pos += 4;
-                            NonAscii::Astral(unsafe { ::std::mem::transmute(point) })
+                            NonAscii::Astral(unsafe { ::core::mem::transmute(point) })
                         }
                     } else {
                         return CopyAsciiResult::Stop(
@@ -1267,7 +1267,7 @@ impl<'a> Utf8Source<'a> {
                                         ((self.slice[self.pos + 2] as u32 & 0x3Fu32) << 6) |
                                         (self.slice[self.pos + 3] as u32 & 0x3Fu32);
                             self.pos += 4;
-                            NonAscii::Astral(unsafe { ::std::mem::transmute(point) })
+                            NonAscii::Astral(unsafe { ::core::mem::transmute(point) })
                         }
                     } else {
                         return CopyAsciiResult::Stop(
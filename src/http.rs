@@ -0,0 +1,428 @@
+// Copyright 2017 Mozilla Foundation. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! This module implements extracting the encoding from an HTTP
+//! `Content-Type` header value according to the WHATWG Fetch and MIME
+//! Sniffing Standards and choosing an encoding according to an HTTP
+//! `Accept-Charset` header value.
+
+use super::*;
+
+fn is_http_whitespace(byte: u8) -> bool {
+    matches!(byte, 0x09u8 | 0x0Au8 | 0x0Du8 | 0x20u8)
+}
+
+fn is_http_tab_or_space(byte: u8) -> bool {
+    byte == 0x09u8 || byte == 0x20u8
+}
+
+fn is_http_token(byte: u8) -> bool {
+    match byte {
+        b'!' | b'#' | b'$' | b'%' | b'&' | b'\'' | b'*' | b'+' | b'-' | b'.' | b'^' | b'_' |
+        b'`' | b'|' | b'~' => true,
+        _ => byte.is_ascii_alphanumeric(),
+    }
+}
+
+fn is_http_quoted_string_token(byte: u8) -> bool {
+    byte == 0x09u8 || (byte >= 0x20u8 && byte != 0x7Fu8)
+}
+
+fn trim(bytes: &[u8], predicate: fn(u8) -> bool) -> &[u8] {
+    let start = bytes
+        .iter()
+        .position(|b| !predicate(*b))
+        .unwrap_or(bytes.len());
+    let end = bytes
+        .iter()
+        .rposition(|b| !predicate(*b))
+        .map_or(start, |i| i + 1);
+    &bytes[start..end]
+}
+
+/// Strips trailing bytes that match `predicate`.
+fn trim_end(bytes: &[u8], predicate: fn(u8) -> bool) -> &[u8] {
+    let end = bytes
+        .iter()
+        .rposition(|b| !predicate(*b))
+        .map_or(0, |i| i + 1);
+    &bytes[..end]
+}
+
+/// Implements "collect an HTTP quoted string". `bytes[*position]` must be
+/// `"`. If `extract_value` is `false`, the returned bytes include the quotes
+/// and backslashes as they appear in the input.
+fn collect_quoted_string(bytes: &[u8], position: &mut usize, extract_value: bool) -> Vec<u8> {
+    let start = *position;
+    let mut value = Vec::new();
+    *position += 1;
+    loop {
+        while *position < bytes.len() && bytes[*position] != b'"' && bytes[*position] != b'\\' {
+            value.push(bytes[*position]);
+            *position += 1;
+        }
+        if *position >= bytes.len() {
+            break;
+        }
+        let quote_or_backslash = bytes[*position];
+        *position += 1;
+        if quote_or_backslash == b'\\' {
+            if *position >= bytes.len() {
+                value.push(b'\\');
+                break;
+            }
+            value.push(bytes[*position]);
+            *position += 1;
+        } else {
+            break;
+        }
+    }
+    if extract_value {
+        value
+    } else {
+        bytes[start..*position].to_vec()
+    }
+}
+
+/// Implements "get, decode, and split" for a header value, i.e. splits it
+/// at commas that are not inside quoted strings.
+fn split_header_value(bytes: &[u8]) -> Vec<Vec<u8>> {
+    let mut values = Vec::new();
+    let mut position = 0usize;
+    let mut value = Vec::new();
+    loop {
+        while position < bytes.len() && bytes[position] != b'"' && bytes[position] != b',' {
+            value.push(bytes[position]);
+            position += 1;
+        }
+        if position < bytes.len() && bytes[position] == b'"' {
+            value.extend_from_slice(&collect_quoted_string(bytes, &mut position, false));
+            if position < bytes.len() {
+                continue;
+            }
+        }
+        values.push(trim(&value, is_http_tab_or_space).to_vec());
+        value.clear();
+        if position >= bytes.len() {
+            return values;
+        }
+        position += 1;
+    }
+}
+
+/// The parts of a parsed MIME type that matter for the encoding.
+struct MimeType {
+    /// The lower-cased type and subtype separated by a slash.
+    essence: Vec<u8>,
+    /// The value of the first valid `charset` parameter.
+    charset: Option<Vec<u8>>,
+}
+
+/// Implements "parse a MIME type" from the MIME Sniffing Standard keeping
+/// only the essence and the `charset` parameter.
+fn parse_mime_type(input: &[u8]) -> Option<MimeType> {
+    let input = trim(input, is_http_whitespace);
+    let slash = input.iter().position(|b| *b == b'/')?;
+    let type_ = &input[..slash];
+    if type_.is_empty() || !type_.iter().all(|b| is_http_token(*b)) {
+        return None;
+    }
+    let mut position = slash + 1;
+    let subtype_end = input[position..]
+        .iter()
+        .position(|b| *b == b';')
+        .map_or(input.len(), |i| position + i);
+    let subtype = trim_end(&input[position..subtype_end], is_http_whitespace);
+    if subtype.is_empty() || !subtype.iter().all(|b| is_http_token(*b)) {
+        return None;
+    }
+    position = subtype_end;
+    let mut essence = type_.to_ascii_lowercase();
+    essence.push(b'/');
+    essence.extend_from_slice(&subtype.to_ascii_lowercase());
+
+    let mut names: Vec<Vec<u8>> = Vec::new();
+    let mut charset = None;
+    while position < input.len() {
+        // Skip the semicolon.
+        position += 1;
+        while position < input.len() && is_http_whitespace(input[position]) {
+            position += 1;
+        }
+        let name_start = position;
+        while position < input.len() && input[position] != b';' && input[position] != b'=' {
+            position += 1;
+        }
+        let name = input[name_start..position].to_ascii_lowercase();
+        if position < input.len() {
+            if input[position] == b';' {
+                continue;
+            }
+            position += 1;
+        }
+        if position >= input.len() {
+            break;
+        }
+        let value = if input[position] == b'"' {
+            let value = collect_quoted_string(input, &mut position, true);
+            while position < input.len() && input[position] != b';' {
+                position += 1;
+            }
+            value
+        } else {
+            let value_start = position;
+            while position < input.len() && input[position] != b';' {
+                position += 1;
+            }
+            let value = trim_end(&input[value_start..position], is_http_whitespace);
+            if value.is_empty() {
+                continue;
+            }
+            value.to_vec()
+        };
+        if !name.is_empty() && name.iter().all(|b| is_http_token(*b)) &&
+           value.iter().all(|b| is_http_quoted_string_token(*b)) &&
+           !names.contains(&name) {
+            if name == b"charset" {
+                charset = Some(value);
+            }
+            names.push(name);
+        }
+    }
+    Some(MimeType { essence, charset })
+}
+
+/// Returns the encoding that a `Content-Type` header value declares.
+///
+/// The argument is the value of the `Content-Type` header. If the response
+/// had several `Content-Type` headers, their values should be joined with
+/// `", "` before calling this function.
+///
+/// This implements "extract a MIME type" from the Fetch Standard (which
+/// uses "parse a MIME type" from the MIME Sniffing Standard) followed by
+/// "get an encoding" on its `charset` parameter. Thus, parameter names are
+/// ASCII case-insensitive, parameter values may be quoted strings with
+/// backslash escapes, the first of duplicate parameters wins, a MIME type
+/// that fails to parse or is `*/*` is skipped and the last MIME type wins.
+/// If the last MIME type has no `charset` parameter, it gets the `charset` of
+/// the first MIME type of the run of MIME types with the same essence that it
+/// ends (not the `charset` of the MIME type right before it).
+///
+/// Returns `None` if there is no `charset` parameter or its value is not a
+/// label. Labels that map to the replacement encoding result in
+/// `REPLACEMENT` as with `Encoding::for_label()`.
+///
+/// Available to Rust only.
+pub fn encoding_for_content_type(content_type: &[u8]) -> Option<&'static Encoding> {
+    let mut essence: Option<Vec<u8>> = None;
+    // The `charset` of the MIME type that started the current run of MIME
+    // types with the same essence.
+    let mut charset: Option<Vec<u8>> = None;
+    let mut result: Option<Vec<u8>> = None;
+    for value in split_header_value(content_type) {
+        let mime_type = match parse_mime_type(&value) {
+            Some(mime_type) => mime_type,
+            None => {
+                continue;
+            }
+        };
+        if mime_type.essence == b"*/*" {
+            continue;
+        }
+        if essence.as_ref() != Some(&mime_type.essence) {
+            charset = mime_type.charset.clone();
+            essence = Some(mime_type.essence);
+            result = mime_type.charset;
+        } else {
+            result = mime_type.charset.or_else(|| charset.clone());
+        }
+    }
+    Encoding::for_label(&result?)
+}
+
+/// Parses a qvalue into thousandths.
+fn parse_qvalue(bytes: &[u8]) -> Option<u16> {
+    let (integer, fraction) = match bytes.iter().position(|b| *b == b'.') {
+        Some(dot) => (&bytes[..dot], &bytes[dot + 1..]),
+        None => (bytes, &b""[..]),
+    };
+    if fraction.len() > 3 || !fraction.iter().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let mut thousandths = 0u16;
+    for i in 0..3 {
+        thousandths *= 10;
+        if let Some(digit) = fraction.get(i) {
+            thousandths += u16::from(*digit - b'0');
+        }
+    }
+    match integer {
+        b"0" => Some(thousandths),
+        b"1" if thousandths == 0 => Some(1000),
+        _ => None,
+    }
+}
+
+/// Chooses an encoding for a response according to the value of the
+/// request's `Accept-Charset` header.
+///
+/// `available` lists the encodings that the server can produce in its
+/// order of preference. The header value is parsed according to RFC 7231:
+/// a comma-separated list of charset labels or `*`, each optionally
+/// followed by a `q` parameter. Labels are resolved with
+/// `Encoding::for_label_no_replacement()`, so e.g. `latin1` matches
+/// `WINDOWS_1252` and unknown labels are ignored. An encoding not listed
+/// gets the weight of `*` or zero if there is no `*`. If an encoding is
+/// listed more than once, the first occurrence counts.
+///
+/// Returns the available encoding with the highest non-zero weight
+/// preferring the earlier one in `available` in the case of a tie, `None`
+/// if the header rules out all the available encodings, or the first
+/// available encoding if the header has no well-formed elements (e.g. it is
+/// empty).
+///
+/// Available to Rust only.
+pub fn encoding_for_accept_charset(accept_charset: &[u8],
+                                   available: &[&'static Encoding])
+                                   -> Option<&'static Encoding> {
+    let mut weights: Vec<(&'static Encoding, u16)> = Vec::new();
+    let mut wildcard: Option<u16> = None;
+    let mut any_element = false;
+    for element in accept_charset.split(|b| *b == b',') {
+        let mut parts = element.split(|b| *b == b';');
+        let charset = trim(parts.next().unwrap_or(b""), is_http_tab_or_space);
+        if charset.is_empty() {
+            continue;
+        }
+        let mut q = Some(1000u16);
+        for parameter in parts {
+            let parameter = trim(parameter, is_http_tab_or_space);
+            if parameter.len() >= 2 && parameter[..2].eq_ignore_ascii_case(b"q=") {
+                q = parse_qvalue(&parameter[2..]);
+            }
+        }
+        let q = match q {
+            Some(q) => q,
+            None => {
+                continue;
+            }
+        };
+        any_element = true;
+        if charset == b"*" {
+            if wildcard.is_none() {
+                wildcard = Some(q);
+            }
+        } else if let Some(encoding) = Encoding::for_label_no_replacement(charset) {
+            if !weights.iter().any(|&(e, _)| e == encoding) {
+                weights.push((encoding, q));
+            }
+        }
+    }
+    if !any_element {
+        return available.first().cloned();
+    }
+    let mut best: Option<(&'static Encoding, u16)> = None;
+    for &encoding in available {
+        let q = weights
+            .iter()
+            .find(|&&(e, _)| e == encoding)
+            .map(|&(_, q)| q)
+            .or(wildcard)
+            .unwrap_or(0);
+        if q == 0 {
+            continue;
+        }
+        match best {
+            Some((_, best_q)) if best_q >= q => {}
+            _ => {
+                best = Some((encoding, q));
+            }
+        }
+    }
+    best.map(|(encoding, _)| encoding)
+}
+
+// Any copyright to the test code below this comment is dedicated to the
+// Public Domain. http://creativecommons.org/publicdomain/zero/1.0/
+
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+
+    #[test]
+    fn test_content_type() {
+        assert_eq!(encoding_for_content_type(b"text/html; charset=Shift_JIS"),
+                   Some(SHIFT_JIS));
+        assert_eq!(encoding_for_content_type(b"text/html; charset=\"Shift_JIS\""),
+                   Some(SHIFT_JIS));
+        assert_eq!(encoding_for_content_type(b" TEXT/HTML ;CHARSET = gbk"), None);
+        assert_eq!(encoding_for_content_type(b" TEXT/HTML ;CHARSET=gbk "), Some(GBK));
+        assert_eq!(encoding_for_content_type(b"text/ html;charset=gbk"), None);
+        assert_eq!(encoding_for_content_type(b"text/html ;charset= gbk"), Some(GBK));
+        assert_eq!(encoding_for_content_type(b"text/plain;charset=\"euc\\-kr\";x=y"),
+                   Some(EUC_KR));
+        assert_eq!(encoding_for_content_type(b"text/plain;charset=\"big5\" junk;x=y"),
+                   Some(BIG5));
+        assert_eq!(encoding_for_content_type(b"text/plain;charset=big5;charset=gbk"),
+                   Some(BIG5));
+        assert_eq!(encoding_for_content_type(b"text/plain;charset=;charset=gbk"),
+                   Some(GBK));
+        assert_eq!(encoding_for_content_type(b"text/plain;charset=bogus"), None);
+        assert_eq!(encoding_for_content_type(b"text/plain;charset=iso-2022-kr"),
+                   Some(REPLACEMENT));
+        assert_eq!(encoding_for_content_type(b"text/plain"), None);
+        assert_eq!(encoding_for_content_type(b"charset=gbk"), None);
+        assert_eq!(encoding_for_content_type(b""), None);
+    }
+
+    #[test]
+    fn test_content_type_multiple() {
+        assert_eq!(encoding_for_content_type(b"text/plain;charset=gbk, text/plain"),
+                   Some(GBK));
+        assert_eq!(encoding_for_content_type(b"text/plain;charset=gbk, text/html"),
+                   None);
+        assert_eq!(encoding_for_content_type(b"text/plain;charset=gbk, */*"),
+                   Some(GBK));
+        assert_eq!(encoding_for_content_type(b"text/plain;charset=gbk, bogus"),
+                   Some(GBK));
+        assert_eq!(encoding_for_content_type(b"text/plain;charset=\"gbk,big5\", text/plain"),
+                   None);
+        assert_eq!(encoding_for_content_type(b"text/html;charset=\"a,b\", text/html;charset=big5"),
+                   Some(BIG5));
+        assert_eq!(encoding_for_content_type(b"text/html;charset=gbk, text/html;charset=big5, text/html"),
+                   Some(GBK));
+    }
+
+    #[test]
+    fn test_accept_charset() {
+        let available = [UTF_8, SHIFT_JIS, EUC_JP];
+        assert_eq!(encoding_for_accept_charset(b"shift_jis, euc-jp;q=0.5", &available),
+                   Some(SHIFT_JIS));
+        assert_eq!(encoding_for_accept_charset(b"euc-jp;q=0.5, shift_jis;q=0.9", &available),
+                   Some(SHIFT_JIS));
+        assert_eq!(encoding_for_accept_charset(b"euc-jp, shift_jis", &available),
+                   Some(SHIFT_JIS));
+        assert_eq!(encoding_for_accept_charset(b"iso-8859-1, *;q=0.1", &available),
+                   Some(UTF_8));
+        assert_eq!(encoding_for_accept_charset(b"utf-8;q=0, *", &available),
+                   Some(SHIFT_JIS));
+        assert_eq!(encoding_for_accept_charset(b"utf-8;q=0, *;q=0", &available),
+                   None);
+        assert_eq!(encoding_for_accept_charset(b"latin1, gbk", &available), None);
+        assert_eq!(encoding_for_accept_charset(b"latin1", &[UTF_8, WINDOWS_1252]),
+                   Some(WINDOWS_1252));
+        assert_eq!(encoding_for_accept_charset(b"euc-jp;Q=1.000", &available),
+                   Some(EUC_JP));
+        assert_eq!(encoding_for_accept_charset(b"euc-jp;q=2, shift_jis;q=0.001", &available),
+                   Some(SHIFT_JIS));
+        assert_eq!(encoding_for_accept_charset(b"", &available), Some(UTF_8));
+        assert_eq!(encoding_for_accept_charset(b" , ;q=1", &available), Some(UTF_8));
+        assert_eq!(encoding_for_accept_charset(b"utf-8", &[]), None);
+    }
+}
@@ -0,0 +1,804 @@
+// Copyright 2017 Mozilla Foundation. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! This module provides adapters that connect the streaming API to the
+//! `std::io` traits.
+//!
+//! The buffer management is kept in `DecodeState` and `EncodeState`, which
+//! know nothing about where the bytes come from or go to, so that the
+//! blocking adapters here and the asynchronous adapters in `async_io` share
+//! the same draining behavior.
+
+use std::io;
+use std::io::BufRead;
+use std::io::Read;
+use std::io::Write;
+
+use super::*;
+
+/// The default size of the internal buffers. (Same as the default of
+/// `std::io::BufReader`.)
+pub const DEFAULT_BUF_SIZE: usize = 8 * 1024;
+
+/// The smallest output buffer that can always hold at least one character
+/// of UTF-8 output.
+const MIN_UTF8_BUF_SIZE: usize = 4;
+
+/// The length of the longest UTF-8 sequence.
+const MAX_UTF8_SEQUENCE_LENGTH: usize = 4;
+
+/// The input and output buffers of a decoding adapter.
+pub struct DecodeState {
+    decoder: Decoder,
+    in_buf: Box<[u8]>,
+    in_pos: usize,
+    in_len: usize,
+    out_buf: Box<[u8]>,
+    out_pos: usize,
+    out_len: usize,
+    eof: bool,
+    finished: bool,
+    had_errors: bool,
+}
+
+impl DecodeState {
+    pub fn new(decoder: Decoder, capacity: usize) -> DecodeState {
+        DecodeState {
+            decoder,
+            in_buf: vec![0u8; ::std::cmp::max(capacity, 1)].into_boxed_slice(),
+            in_pos: 0,
+            in_len: 0,
+            out_buf: vec![0u8; ::std::cmp::max(capacity, MIN_UTF8_BUF_SIZE)].into_boxed_slice(),
+            out_pos: 0,
+            out_len: 0,
+            eof: false,
+            finished: false,
+            had_errors: false,
+        }
+    }
+
+    pub fn encoding(&self) -> &'static Encoding {
+        self.decoder.encoding()
+    }
+
+    pub fn had_errors(&self) -> bool {
+        self.had_errors
+    }
+
+    /// Whether the caller needs to call `decode()` before `output()` can
+    /// return anything.
+    pub fn needs_decode(&self) -> bool {
+        self.out_pos == self.out_len && !self.finished
+    }
+
+    /// Whether the input buffer has been drained and more bytes need to be
+    /// read into `input_space()` before calling `decode()`.
+    pub fn needs_input(&self) -> bool {
+        self.in_pos == self.in_len && !self.eof
+    }
+
+    pub fn input_space(&mut self) -> &mut [u8] {
+        &mut self.in_buf[..]
+    }
+
+    /// Records that `read` bytes were read into `input_space()`. Zero means
+    /// EOF.
+    pub fn input_filled(&mut self, read: usize) {
+        self.in_pos = 0;
+        self.in_len = read;
+        if read == 0 {
+            self.eof = true;
+        }
+    }
+
+    /// Decodes from the input buffer into the drained output buffer.
+    pub fn decode(&mut self) {
+        debug_assert!(self.needs_decode());
+        let (result, read, written, had_errors) =
+            self.decoder
+                .decode_to_utf8(
+                    &self.in_buf[self.in_pos..self.in_len],
+                    &mut self.out_buf[..],
+                    self.eof,
+                );
+        self.in_pos += read;
+        self.out_pos = 0;
+        self.out_len = written;
+        self.had_errors |= had_errors;
+        match result {
+            CoderResult::InputEmpty => {
+                if self.eof {
+                    self.finished = true;
+                }
+            }
+            CoderResult::OutputFull => {}
+        }
+    }
+
+    pub fn output(&self) -> &[u8] {
+        &self.out_buf[self.out_pos..self.out_len]
+    }
+
+    pub fn consume(&mut self, amt: usize) {
+        self.out_pos = ::std::cmp::min(self.out_pos + amt, self.out_len);
+    }
+}
+
+/// A `Read` and `BufRead` adapter that decodes a byte stream in any
+/// encoding from an inner reader into UTF-8.
+///
+/// The bytes returned by `read()` and `fill_buf()`, taken as a whole from the
+/// start of the stream, are guaranteed to be valid UTF-8. (An individual
+/// `read()` call may end in the middle of a UTF-8 sequence if the caller's
+/// buffer is full at that point.) Malformed sequences are replaced with the
+/// REPLACEMENT CHARACTER.
+///
+/// The inner reader is read in chunks into an internal buffer, and the chunk
+/// boundaries may fall anywhere, including in the middle of a multi-byte
+/// sequence or a BOM. Partial sequences are held inside the `Decoder` until
+/// the rest arrives. When the inner reader reports EOF, the decoder is
+/// finished with `last` set to `true`, so a truncated sequence at the end of
+/// the stream turns into a REPLACEMENT CHARACTER.
+///
+/// It makes no sense to wrap a `DecodeReader` in a `std::io::BufReader`,
+/// because `DecodeReader` already is `BufRead`.
+///
+/// Available to Rust only.
+pub struct DecodeReader<R> {
+    inner: R,
+    state: DecodeState,
+}
+
+impl<R: Read> DecodeReader<R> {
+    /// Wraps `inner` with a decoder for `encoding` with BOM sniffing enabled.
+    ///
+    /// BOM sniffing may cause the decoder to morph into a decoder for UTF-8,
+    /// UTF-16LE or UTF-16BE instead of `encoding`. Use `encoding()` after
+    /// the first read to find out which encoding is actually used.
+    pub fn new(inner: R, encoding: &'static Encoding) -> DecodeReader<R> {
+        DecodeReader::with_decoder(inner, encoding.new_decoder())
+    }
+
+    /// Wraps `inner` with a caller-provided decoder.
+    ///
+    /// This allows the BOM handling mode to be chosen by obtaining the
+    /// decoder from `new_decoder_with_bom_removal()` or
+    /// `new_decoder_without_bom_handling()`. The decoder must not have been
+    /// used yet with `last` set to `true`.
+    pub fn with_decoder(inner: R, decoder: Decoder) -> DecodeReader<R> {
+        DecodeReader::with_decoder_and_capacity(inner, decoder, DEFAULT_BUF_SIZE)
+    }
+
+    /// Wraps `inner` with a caller-provided decoder using internal buffers of
+    /// `capacity` bytes each.
+    ///
+    /// The output buffer is made at least large enough to hold one character.
+    pub fn with_decoder_and_capacity(inner: R,
+                                     decoder: Decoder,
+                                     capacity: usize)
+                                     -> DecodeReader<R> {
+        DecodeReader {
+            inner,
+            state: DecodeState::new(decoder, capacity),
+        }
+    }
+
+    /// The `Encoding` of the underlying decoder.
+    ///
+    /// BOM sniffing can change the return value of this method during the life
+    /// of the reader.
+    pub fn encoding(&self) -> &'static Encoding {
+        self.state.encoding()
+    }
+
+    /// Returns `true` if malformed sequences have been replaced with the
+    /// REPLACEMENT CHARACTER so far.
+    pub fn had_errors(&self) -> bool {
+        self.state.had_errors()
+    }
+
+    /// Gets a reference to the inner reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the inner reader.
+    ///
+    /// Reading directly from the inner reader bypasses the decoder and the
+    /// bytes already buffered by this adapter.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Unwraps this adapter, returning the inner reader.
+    ///
+    /// Bytes buffered but not yet decoded or decoded but not yet read are
+    /// lost.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read> Read for DecodeReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = {
+            let available = self.fill_buf()?;
+            let n = ::std::cmp::min(available.len(), buf.len());
+            buf[..n].copy_from_slice(&available[..n]);
+            n
+        };
+        self.consume(n);
+        Ok(n)
+    }
+}
+
+impl<R: Read> BufRead for DecodeReader<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        // Inner readers may return short reads and the decoder may hold on to
+        // everything it got as partial state, so keep going until there is
+        // some output or the stream has ended.
+        while self.state.needs_decode() {
+            if self.state.needs_input() {
+                match self.inner.read(self.state.input_space()) {
+                    Ok(n) => self.state.input_filled(n),
+                    Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(e) => return Err(e),
+                }
+            }
+            self.state.decode();
+        }
+        Ok(self.state.output())
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.state.consume(amt);
+    }
+}
+
+/// The error wrapped in the `std::io::Error` returned by `EncodeWriter` when
+/// it has been created with `new_without_replacement()` and the input contains
+/// a character that the encoding cannot represent.
+///
+/// Available to Rust only.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct UnmappableError {
+    unmappable: char,
+}
+
+impl UnmappableError {
+    /// The character that could not be encoded.
+    pub fn unmappable(&self) -> char {
+        self.unmappable
+    }
+}
+
+impl ::std::fmt::Display for UnmappableError {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(f, "unmappable character U+{:04X}", self.unmappable as u32)
+    }
+}
+
+impl ::std::error::Error for UnmappableError {}
+
+/// The output buffer and the pending partial UTF-8 sequence of an encoding
+/// adapter.
+pub struct EncodeState {
+    encoder: Encoder,
+    replace: bool,
+    out_buf: Box<[u8]>,
+    out_pos: usize,
+    out_len: usize,
+    pending: [u8; MAX_UTF8_SEQUENCE_LENGTH],
+    pending_len: usize,
+    had_unmappables: bool,
+    error: Option<io::Error>,
+}
+
+impl EncodeState {
+    pub fn new(encoder: Encoder, replace: bool) -> EncodeState {
+        EncodeState {
+            encoder,
+            replace,
+            out_buf: vec![0u8; DEFAULT_BUF_SIZE].into_boxed_slice(),
+            out_pos: 0,
+            out_len: 0,
+            pending: [0u8; MAX_UTF8_SEQUENCE_LENGTH],
+            pending_len: 0,
+            had_unmappables: false,
+            error: None,
+        }
+    }
+
+    pub fn encoding(&self) -> &'static Encoding {
+        self.encoder.encoding()
+    }
+
+    pub fn had_unmappables(&self) -> bool {
+        self.had_unmappables
+    }
+
+    pub fn output(&self) -> &[u8] {
+        &self.out_buf[self.out_pos..self.out_len]
+    }
+
+    pub fn consume(&mut self, amt: usize) {
+        self.out_pos = ::std::cmp::min(self.out_pos + amt, self.out_len);
+        if self.out_pos == self.out_len {
+            self.out_pos = 0;
+            self.out_len = 0;
+        }
+    }
+
+    /// Holds an error that was found after some input had already been
+    /// consumed so that `encode()` or `finish()` returns it upon the next
+    /// call.
+    pub fn defer_error(&mut self, error: io::Error) {
+        self.error = Some(error);
+    }
+
+    /// Checks whether an error is waiting to be returned by the next call.
+    pub fn has_deferred_error(&self) -> bool {
+        self.error.is_some()
+    }
+
+    /// Encodes a prefix of `buf` into the output buffer, which must have been
+    /// drained. Returns the number of bytes of `buf` consumed, which is
+    /// non-zero if `buf` is non-empty.
+    ///
+    /// An error is returned only if no input was consumed. An unmappable
+    /// character is consumed and the error for it is deferred to the next
+    /// call, so that the output for the input before it can be written out
+    /// first.
+    pub fn encode(&mut self, buf: &[u8]) -> io::Result<usize> {
+        debug_assert_eq!(self.out_len, 0);
+        if let Some(error) = self.error.take() {
+            return Err(error);
+        }
+        let mut consumed = 0usize;
+        if self.pending_len != 0 {
+            // Complete the sequence that the previous write left unfinished.
+            let needed = utf8_sequence_length(self.pending[0]);
+            let take = ::std::cmp::min(needed - self.pending_len, buf.len());
+            self.pending[self.pending_len..self.pending_len + take]
+                .copy_from_slice(&buf[..take]);
+            self.pending_len += take;
+            consumed = take;
+            let pending = self.pending;
+            match ::std::str::from_utf8(&pending[..self.pending_len]) {
+                Ok(s) => {
+                    self.pending_len = 0;
+                    // A single character always fits in the empty buffer.
+                    let read = self.encode_str(s, false);
+                    debug_assert_eq!(read, s.len());
+                    if self.error.is_some() {
+                        return Ok(consumed);
+                    }
+                }
+                Err(e) => {
+                    if e.error_len().is_some() {
+                        // The invalid sequence started in an earlier call,
+                        // so report it without consuming anything from `buf`.
+                        self.pending_len = 0;
+                        return Err(invalid_utf8());
+                    }
+                    // Still incomplete, so `buf` has been exhausted.
+                    debug_assert_eq!(consumed, buf.len());
+                    return Ok(consumed);
+                }
+            }
+        }
+        let rest = &buf[consumed..];
+        let (valid, tail) = match ::std::str::from_utf8(rest) {
+            Ok(s) => (s, &rest[rest.len()..]),
+            Err(e) => {
+                let valid_up_to = e.valid_up_to();
+                if e.error_len().is_some() && valid_up_to == 0 && consumed == 0 {
+                    return Err(invalid_utf8());
+                }
+                // Either the input ends with a prefix of a valid sequence or
+                // the invalid sequence gets reported upon the next call.
+                let tail = if e.error_len().is_some() {
+                    &rest[rest.len()..]
+                } else {
+                    &rest[valid_up_to..]
+                };
+                (unsafe { ::std::str::from_utf8_unchecked(&rest[..valid_up_to]) }, tail)
+            }
+        };
+        let read = self.encode_str(valid, false);
+        consumed += read;
+        if self.error.is_some() {
+            return Ok(consumed);
+        }
+        if read == valid.len() && !tail.is_empty() {
+            self.pending[..tail.len()].copy_from_slice(tail);
+            self.pending_len = tail.len();
+            consumed += tail.len();
+        }
+        Ok(consumed)
+    }
+
+    /// Encodes the end of the stream into the output buffer, which must have
+    /// been drained. Returns `true` if done and `false` if the output buffer
+    /// needs to be drained and this method called again.
+    pub fn finish(&mut self) -> io::Result<bool> {
+        debug_assert_eq!(self.out_len, 0);
+        if let Some(error) = self.error.take() {
+            return Err(error);
+        }
+        if self.pending_len != 0 {
+            return Err(
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "stream ended in the middle of a UTF-8 sequence",
+                )
+            );
+        }
+        self.encode_str("", true);
+        if let Some(error) = self.error.take() {
+            return Err(error);
+        }
+        Ok(!self.encoder.has_pending_state())
+    }
+
+    /// Encodes as much of `src` as fits into the output buffer and returns
+    /// the number of bytes read. An unmappable character is included in the
+    /// count and recorded as the deferred error.
+    fn encode_str(&mut self, src: &str, last: bool) -> usize {
+        let (result, read, written) = if self.replace {
+            let (result, read, written, had_unmappables) =
+                self.encoder
+                    .encode_from_utf8(src, &mut self.out_buf[self.out_len..], last);
+            self.had_unmappables |= had_unmappables;
+            let result = match result {
+                CoderResult::InputEmpty => EncoderResult::InputEmpty,
+                CoderResult::OutputFull => EncoderResult::OutputFull,
+            };
+            (result, read, written)
+        } else {
+            self.encoder
+                .encode_from_utf8_without_replacement(src, &mut self.out_buf[self.out_len..], last)
+        };
+        self.out_len += written;
+        if let EncoderResult::Unmappable(unmappable) = result {
+            self.error = Some(io::Error::new(io::ErrorKind::InvalidData,
+                                             UnmappableError { unmappable }));
+        }
+        read
+    }
+}
+
+/// A `Write` adapter that encodes UTF-8 written to it into a legacy encoding
+/// and writes the result to an inner writer.
+///
+/// The bytes passed to `write()` must, taken as a whole from the start of
+/// the stream, be valid UTF-8, but the chunk boundaries may fall anywhere,
+/// including in the middle of a UTF-8 sequence. A trailing partial sequence
+/// is held by the adapter until the rest of it is written. A byte sequence
+/// that cannot become valid UTF-8 results in an error of the kind
+/// `InvalidData`.
+///
+/// Each call to `write()` encodes its input and passes all the resulting
+/// bytes to the inner writer before returning. That is, the adapter does not
+/// buffer output across calls, so wrapping a `std::io::BufWriter` around the
+/// inner writer makes sense if the inner writer is slow with small writes.
+///
+/// `finish()` must be called at the end of the stream. Otherwise, a
+/// trailing partial UTF-8 sequence goes unreported and ISO-2022-JP output may
+/// be left in a state other than the ASCII state.
+///
+/// Available to Rust only.
+pub struct EncodeWriter<W: Write> {
+    inner: W,
+    state: EncodeState,
+}
+
+impl<W: Write> EncodeWriter<W> {
+    /// Wraps `inner` with an encoder for the _output encoding_ of `encoding`
+    /// that replaces unmappable characters with HTML decimal numeric
+    /// character references.
+    pub fn new(inner: W, encoding: &'static Encoding) -> EncodeWriter<W> {
+        EncodeWriter {
+            inner,
+            state: EncodeState::new(encoding.new_encoder(), true),
+        }
+    }
+
+    /// Wraps `inner` with an encoder for the _output encoding_ of `encoding`
+    /// that fails with an error of the kind `InvalidData` wrapping an
+    /// `UnmappableError` when it encounters an unmappable character.
+    ///
+    /// The `write()` call that reaches the unmappable character writes the
+    /// output for the input before it to the inner writer and returns the
+    /// number of bytes up to and including the unmappable character. The
+    /// error is returned by the next call to `write()` or `finish()`. After
+    /// the error, the writer should not be used any further except for
+    /// retrieving the inner writer via `get_mut()`.
+    pub fn new_without_replacement(inner: W, encoding: &'static Encoding) -> EncodeWriter<W> {
+        EncodeWriter {
+            inner,
+            state: EncodeState::new(encoding.new_encoder(), false),
+        }
+    }
+
+    /// The `Encoding` of the underlying encoder.
+    pub fn encoding(&self) -> &'static Encoding {
+        self.state.encoding()
+    }
+
+    /// Returns `true` if unmappable characters have been replaced with
+    /// numeric character references so far.
+    pub fn had_unmappables(&self) -> bool {
+        self.state.had_unmappables()
+    }
+
+    /// Gets a reference to the inner writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the inner writer.
+    ///
+    /// Writing directly to the inner writer bypasses the encoder, which is
+    /// wrong when the encoder has pending ISO-2022-JP state.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Ends the stream, flushes the inner writer and returns it.
+    ///
+    /// If the encoder has pending state (i.e. ISO-2022-JP output is not in the
+    /// ASCII state), the bytes for transitioning back to the ASCII state are
+    /// written first. If the input ended in the middle of a UTF-8 sequence,
+    /// an error of the kind `InvalidData` is returned.
+    pub fn finish(mut self) -> io::Result<W> {
+        loop {
+            let done = self.state.finish()?;
+            self.drain()?;
+            if done {
+                break;
+            }
+        }
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+
+    fn drain(&mut self) -> io::Result<()> {
+        let len = self.state.output().len();
+        self.inner.write_all(self.state.output())?;
+        self.state.consume(len);
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for EncodeWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut consumed = 0usize;
+        loop {
+            match self.state.encode(&buf[consumed..]) {
+                Ok(n) => {
+                    self.drain()?;
+                    consumed += n;
+                    if consumed == buf.len() || self.state.has_deferred_error() {
+                        return Ok(consumed);
+                    }
+                }
+                Err(e) => {
+                    if consumed == 0 {
+                        return Err(e);
+                    }
+                    // Report the error once the caller has seen what was
+                    // consumed.
+                    self.state.defer_error(e);
+                    return Ok(consumed);
+                }
+            }
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+fn invalid_utf8() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "invalid UTF-8")
+}
+
+/// The length of the UTF-8 sequence that starts with `lead` for a byte that
+/// has already been checked to be a valid prefix.
+fn utf8_sequence_length(lead: u8) -> usize {
+    if lead < 0xE0 {
+        2
+    } else if lead < 0xF0 {
+        3
+    } else {
+        4
+    }
+}
+
+// Any copyright to the test code below this comment is dedicated to the
+// Public Domain. http://creativecommons.org/publicdomain/zero/1.0/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A reader that returns at most `chunk` bytes per `read()` call.
+    struct Trickle<'a> {
+        bytes: &'a [u8],
+        chunk: usize,
+    }
+
+    impl<'a> Read for Trickle<'a> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = ::std::cmp::min(::std::cmp::min(self.chunk, buf.len()), self.bytes.len());
+            buf[..n].copy_from_slice(&self.bytes[..n]);
+            self.bytes = &self.bytes[n..];
+            Ok(n)
+        }
+    }
+
+    fn decode_all(encoding: &'static Encoding, bytes: &[u8], chunk: usize, capacity: usize) -> String {
+        let mut reader = DecodeReader::with_decoder_and_capacity(
+            Trickle {
+                bytes,
+                chunk,
+            },
+            encoding.new_decoder(),
+            capacity,
+        );
+        let mut string = String::new();
+        reader.read_to_string(&mut string).unwrap();
+        string
+    }
+
+    #[test]
+    fn test_decode_reader_shift_jis() {
+        let bytes = b"\x83n\x83\x8D\x81[\x81E\x83\x8F\x81[\x83\x8B\x83h";
+        let expectation = "\u{30CF}\u{30ED}\u{30FC}\u{30FB}\u{30EF}\u{30FC}\u{30EB}\u{30C9}";
+        for chunk in 1..bytes.len() + 1 {
+            for capacity in 1..8 {
+                assert_eq!(decode_all(SHIFT_JIS, bytes, chunk, capacity), expectation);
+            }
+        }
+    }
+
+    #[test]
+    fn test_decode_reader_bom_sniffing() {
+        for chunk in 1..5 {
+            assert_eq!(decode_all(WINDOWS_1252, b"\xEF\xBB\xBF\xC3\xA4", chunk, 16), "\u{E4}");
+            assert_eq!(decode_all(WINDOWS_1252, b"\xFF\xFEa\x00", chunk, 16), "a");
+            assert_eq!(decode_all(WINDOWS_1252, b"\xEF\xBB", chunk, 16), "\u{EF}\u{BB}");
+        }
+        let mut reader = DecodeReader::new(&b"\xFE\xFF\x00a"[..], WINDOWS_1252);
+        let mut string = String::new();
+        reader.read_to_string(&mut string).unwrap();
+        assert_eq!(string, "a");
+        assert_eq!(reader.encoding(), UTF_16BE);
+    }
+
+    #[test]
+    fn test_decode_reader_truncated_at_eof() {
+        for chunk in 1..4 {
+            assert_eq!(decode_all(UTF_8, b"a\xE2\x82", chunk, 8), "a\u{FFFD}");
+            assert_eq!(decode_all(GB18030, b"a\x81\x30", chunk, 8), "a\u{FFFD}");
+        }
+        let mut reader = DecodeReader::new(&b"a\xE2\x82"[..], UTF_8);
+        let mut string = String::new();
+        reader.read_to_string(&mut string).unwrap();
+        assert!(reader.had_errors());
+    }
+
+    #[test]
+    fn test_decode_reader_buf_read() {
+        let mut reader = DecodeReader::new(&b"a\xE4\nb\xF6\n"[..], WINDOWS_1252);
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        assert_eq!(line, "a\u{E4}\n");
+        line.clear();
+        reader.read_line(&mut line).unwrap();
+        assert_eq!(line, "b\u{F6}\n");
+        line.clear();
+        assert_eq!(reader.read_line(&mut line).unwrap(), 0);
+        // Reading again after the end is fine.
+        assert_eq!(reader.read(&mut [0u8; 4]).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_decode_reader_empty() {
+        assert_eq!(decode_all(UTF_16LE, b"", 1, 8), "");
+    }
+
+    fn encode_all(encoding: &'static Encoding, bytes: &[u8], chunk: usize) -> Vec<u8> {
+        let mut writer = EncodeWriter::new(Vec::new(), encoding);
+        for piece in bytes.chunks(chunk) {
+            writer.write_all(piece).unwrap();
+        }
+        writer.finish().unwrap()
+    }
+
+    #[test]
+    fn test_encode_writer_shift_jis() {
+        let string = "\u{30CF}\u{30ED}\u{30FC}\u{30FB}\u{30EF}\u{30FC}\u{30EB}\u{30C9}";
+        let expectation = b"\x83n\x83\x8D\x81[\x81E\x83\x8F\x81[\x83\x8B\x83h";
+        for chunk in 1..string.len() + 1 {
+            assert_eq!(&encode_all(SHIFT_JIS, string.as_bytes(), chunk)[..], &expectation[..]);
+        }
+    }
+
+    #[test]
+    fn test_encode_writer_ncr() {
+        for chunk in 1..6 {
+            assert_eq!(
+                &encode_all(WINDOWS_1251, "a\u{0416}\u{1F4A9}b".as_bytes(), chunk)[..],
+                &b"a\xC6&#128169;b"[..]
+            );
+        }
+    }
+
+    #[test]
+    fn test_encode_writer_iso_2022_jp_finish() {
+        for chunk in 1..4 {
+            assert_eq!(
+                &encode_all(ISO_2022_JP, "\u{30CF}".as_bytes(), chunk)[..],
+                &b"\x1B$B%O\x1B(B"[..]
+            );
+        }
+    }
+
+    #[test]
+    fn test_encode_writer_utf_16_output_encoding() {
+        let writer = EncodeWriter::new(Vec::new(), UTF_16LE);
+        assert_eq!(writer.encoding(), UTF_8);
+    }
+
+    #[test]
+    fn test_encode_writer_unmappable_error() {
+        let mut writer = EncodeWriter::new_without_replacement(Vec::new(), EUC_KR);
+        let err = writer.write_all("a\u{1F4A9}b".as_bytes()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        let unmappable = err.get_ref()
+                            .unwrap()
+                            .downcast_ref::<UnmappableError>()
+                            .unwrap()
+                            .unmappable();
+        assert_eq!(unmappable, '\u{1F4A9}');
+        assert_eq!(&writer.get_mut()[..], b"a");
+
+        // The input before and including the unmappable character counts as
+        // written and the error comes with the next call.
+        let mut writer = EncodeWriter::new_without_replacement(Vec::new(), EUC_KR);
+        assert_eq!(writer.write("a\u{1F4A9}b".as_bytes()).unwrap(), 5);
+        assert_eq!(&writer.get_mut()[..], b"a");
+        assert_eq!(writer.write(b"b").unwrap_err().kind(), io::ErrorKind::InvalidData);
+        let mut writer = EncodeWriter::new_without_replacement(Vec::new(), EUC_KR);
+        assert_eq!(writer.write("a\u{1F4A9}".as_bytes()).unwrap(), 5);
+        assert_eq!(writer.finish().unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_encode_writer_invalid_utf8() {
+        let mut writer = EncodeWriter::new(Vec::new(), WINDOWS_1252);
+        assert_eq!(writer.write(b"a\xFF").unwrap(), 1);
+        assert_eq!(
+            writer.write(b"\xFF").unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+        let mut writer = EncodeWriter::new(Vec::new(), WINDOWS_1252);
+        writer.write_all(b"\xE2").unwrap();
+        assert_eq!(
+            writer.write(b"a").unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+        let mut writer = EncodeWriter::new(Vec::new(), WINDOWS_1252);
+        writer.write_all(b"a\xE2\x82").unwrap();
+        assert_eq!(writer.finish().unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -46,6 +46,73 @@ impl Iso2022JpDecoder {
         )
     }
 
+    pub fn has_pending_state(&self) -> bool {
+        if self.pending_prepended {
+            return true;
+        }
+        match self.decoder_state {
+            Iso2022JpDecoderState::TrailByte |
+            Iso2022JpDecoderState::EscapeStart |
+            Iso2022JpDecoderState::Escape => true,
+            _ => false,
+        }
+    }
+
+    /// Writes the output state and the output flag followed by the bytes of
+    /// the incomplete lead/trail pair or escape sequence, if any. Fails if
+    /// there is a prepended byte waiting to be processed.
+    #[cfg(feature = "alloc")]
+    pub fn snapshot_state(&self, state: &mut Vec<u8>) -> bool {
+        if self.pending_prepended {
+            return false;
+        }
+        state.push(
+            match self.output_state {
+                Iso2022JpDecoderState::Ascii => 0,
+                Iso2022JpDecoderState::Roman => 1,
+                Iso2022JpDecoderState::Katakana => 2,
+                _ => 3,
+            }
+        );
+        state.push(if self.output_flag { 1 } else { 0 });
+        match self.decoder_state {
+            Iso2022JpDecoderState::TrailByte => {
+                state.push(self.lead);
+            }
+            Iso2022JpDecoderState::EscapeStart => {
+                state.push(0x1B);
+            }
+            Iso2022JpDecoderState::Escape => {
+                state.push(0x1B);
+                state.push(self.lead);
+            }
+            _ => {}
+        }
+        true
+    }
+
+    /// Restores the output state and the output flag and returns the bytes
+    /// that need to be decoded in order to restore the rest of the state.
+    #[cfg(feature = "alloc")]
+    pub fn restore_state<'a>(&mut self, state: &'a [u8]) -> Option<&'a [u8]> {
+        if state.len() < 2 || state[1] > 1 {
+            return None;
+        }
+        let output_state = match state[0] {
+            0 => Iso2022JpDecoderState::Ascii,
+            1 => Iso2022JpDecoderState::Roman,
+            2 => Iso2022JpDecoderState::Katakana,
+            3 => Iso2022JpDecoderState::LeadByte,
+            _ => {
+                return None;
+            }
+        };
+        self.decoder_state = output_state;
+        self.output_state = output_state;
+        self.output_flag = state[1] == 1;
+        Some(&state[2..])
+    }
+
     fn extra_to_input_from_state(&self, byte_length: usize) -> Option<usize> {
         byte_length.checked_add(
             if self.lead == 0 || self.pending_prepended {
@@ -418,6 +485,44 @@ impl Iso2022JpEncoder {
         }
     }
 
+    /// Writes the current state.
+    #[cfg(feature = "alloc")]
+    pub fn snapshot_state(&self, state: &mut Vec<u8>) {
+        state.push(
+            match self.state {
+                Iso2022JpEncoderState::Ascii => 0,
+                Iso2022JpEncoderState::Roman => 1,
+                Iso2022JpEncoderState::Jis0208 => 2,
+            }
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn restore_state(&mut self, state: &[u8]) -> bool {
+        self.state = match state {
+            [0] => Iso2022JpEncoderState::Ascii,
+            [1] => Iso2022JpEncoderState::Roman,
+            [2] => Iso2022JpEncoderState::Jis0208,
+            _ => {
+                return false;
+            }
+        };
+        true
+    }
+
+    /// Switches from the Roman state to the ASCII state. Returns `true` if
+    /// the encoder was in the Roman state, in which case the caller must
+    /// write `ESC ( B`.
+    #[cfg(feature = "alloc")]
+    pub fn leave_roman_state(&mut self) -> bool {
+        match self.state {
+            Iso2022JpEncoderState::Roman => {
+                self.state = Iso2022JpEncoderState::Ascii;
+                true
+            }
+            _ => false,
+        }
+    }
 
     pub fn max_buffer_length_from_utf16_without_replacement(&self,
                                                             u16_length: usize)
@@ -894,6 +999,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_iso_2022_jp_decode_all() {
         let input = include_bytes!("test_data/iso_2022_jp_in.txt");
         let expectation = include_str!("test_data/iso_2022_jp_in_ref.txt");
@@ -903,6 +1009,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_iso_2022_jp_encode_all() {
         let input = include_str!("test_data/iso_2022_jp_out.txt");
         let expectation = include_bytes!("test_data/iso_2022_jp_out_ref.txt");
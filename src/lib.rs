@@ -41,6 +41,8 @@
 //! ```
 //! use encoding_rs::*;
 //!
+//! # #[cfg(feature = "alloc")]
+//! # fn main() {
 //! let expectation = "\u{30CF}\u{30ED}\u{30FC}\u{30FB}\u{30EF}\u{30FC}\u{30EB}\u{30C9}";
 //! let bytes = b"\x83n\x83\x8D\x81[\x81E\x83\x8F\x81[\x83\x8B\x83h";
 //!
@@ -48,6 +50,9 @@
 //! assert_eq!(&cow[..], expectation);
 //! assert_eq!(encoding_used, SHIFT_JIS);
 //! assert!(!had_errors);
+//! # }
+//! # #[cfg(not(feature = "alloc"))]
+//! # fn main() {}
 //! ```
 //!
 //! Decode using the streaming API with minimal `unsafe`:
@@ -173,9 +178,11 @@
 //! (UTF-16 the `[u16]` side of the API) is a goal.
 //!
 //! Despite the focus on the Web, encoding_rs may well be useful for decoding
-//! email, although you'll need to implement UTF-7 decoding and label handling
-//! by other means. (Due to the Web focus, patches to add UTF-7 are unwelcome
-//! in encoding_rs itself.) Also, despite the browser focus, the hope is that
+//! email. The optional `mime` module (enabled by the `mime` feature) decodes
+//! RFC 2047 encoded-words, RFC 2231 parameters and base64 or quoted-printable
+//! bodies, but you'll need to implement UTF-7 decoding by other means. (Due
+//! to the Web focus, patches to add UTF-7 are unwelcome in encoding_rs
+//! itself.) Also, despite the browser focus, the hope is that
 //! non-browser applications that wish to consume Web content or submit Web
 //! forms in a Web-compatible way will find encoding_rs useful.
 //!
@@ -228,6 +235,21 @@
 //! `Encoding` is always statically allocated. `Decoder` and `Encoder` need no
 //! `Drop` cleanup.
 //!
+//! # `no_std`
+//!
+//! The default `std` feature can be turned off to use the crate without the
+//! standard library. Without it, `Encoding`, `Decoder` and `Encoder` with the
+//! methods that work on caller-provided slices, i.e. `decode_to_utf8()`,
+//! `decode_to_utf16()`, `encode_from_utf8()`, `encode_from_utf16()` and their
+//! `_without_replacement` variants, as well as the `mem` module, depend on
+//! `core` only.
+//!
+//! The `alloc` feature (implied by `std`) adds the methods that use
+//! `String`, `Vec<u8>` or `Cow`, the malformed sequence and unmappable
+//! character policies, decoder and encoder snapshots, encoding detection and
+//! the HTML, HTTP and URL conveniences. The `std::io` adapters (and the
+//! `async-io` and `parallel-utf8` features) require `std`.
+//!
 //! # Buffer reading and writing behavior
 //!
 //! Based on experience gained with the `java.nio.charset` encoding converter
@@ -356,6 +378,26 @@
 //! want other replacement schemes for unmappable characters can likewise
 //! implement them on top of `encode_from_utf8_without_replacement()`.
 //!
+//! # Error Handling Policies
+//!
+//! Non-Web applications, such as tools for examining logs, may still need to
+//! see the malformed bytes instead of the REPLACEMENT CHARACTER or may need
+//! replacements for unmappable characters other than HTML decimal numeric
+//! character references. For those, the `_without_replacement` methods are
+//! wrapped in opt-in policies, which are available to Rust only:
+//!
+//! * `Encoding::decoder_options()` builds a `Decoder` whose methods that
+//!   otherwise emit the REPLACEMENT CHARACTER apply a `MalformedPolicy`
+//!   instead: skipping, a custom string, `\xNN` escapes or a closure.
+//! * `Encoding::encoder_options()` builds an `Encoder` that applies an
+//!   `UnmappablePolicy` instead of HTML decimal numeric character references:
+//!   a question mark, hexadecimal numeric character references, `\uXXXX`
+//!   escapes, the percent-encoded numeric character references that the URL
+//!   parser produces or a closure.
+//!
+//! These policies are never the default, and skipping malformed sequences
+//! comes with the security caveat explained above.
+//!
 //! # No Extensibility by Design
 //!
 //! The set of encodings supported by encoding_rs is not extensible by design.
@@ -481,24 +523,45 @@
 //! <tr><td><code><var>raw_encoder</var>.raw_finish(<var>dst</var>)</code></td><td><code><var>dst_vec</var>.reserve(<var>encoder</var>.max_buffer_length_from_utf8_without_replacement(0));<br><var>encoder</var>.encode_from_utf8_to_vec_without_replacement("", <var>dst</var>, true)</code></td></tr>
 //! <tr><td><code>encoding::DecoderTrap::Strict</code></td><td><code>decode*</code> methods that have <code>_without_replacement</code> in their name (and treating the `Malformed` result as fatal).</td></tr>
 //! <tr><td><code>encoding::DecoderTrap::Replace</code></td><td><code>decode*</code> methods that <i>do not</i> have <code>_without_replacement</code> in their name.</td></tr>
-//! <tr><td><code>encoding::DecoderTrap::Ignore</code></td><td>It is a bad idea to ignore errors due to security issues, but this could be implemented using <code>decode*</code> methods that have <code>_without_replacement</code> in their name.</td></tr>
-//! <tr><td><code>encoding::DecoderTrap::Call(DecoderTrapFunc)</code></td><td>Can be implemented using <code>decode*</code> methods that have <code>_without_replacement</code> in their name.</td></tr>
+//! <tr><td><code>encoding::DecoderTrap::Ignore</code></td><td>It is a bad idea to ignore errors due to security issues, but this is available via <code>decoder_options()</code> with <code>MalformedPolicy::Skip</code>.</td></tr>
+//! <tr><td><code>encoding::DecoderTrap::Call(DecoderTrapFunc)</code></td><td><code>decoder_options()</code> with <code>MalformedPolicy::Callback</code>.</td></tr>
 //! <tr><td><code>encoding::EncoderTrap::Strict</code></td><td><code>encode*</code> methods that have <code>_without_replacement</code> in their name (and treating the `Unmappable` result as fatal).</td></tr>
-//! <tr><td><code>encoding::EncoderTrap::Replace</code></td><td>Can be implemented using <code>encode*</code> methods that have <code>_without_replacement</code> in their name.</td></tr>
+//! <tr><td><code>encoding::EncoderTrap::Replace</code></td><td><code>encoder_options()</code> with <code>UnmappablePolicy::QuestionMark</code>.</td></tr>
 //! <tr><td><code>encoding::EncoderTrap::Ignore</code></td><td>It is a bad idea to ignore errors due to security issues, but this could be implemented using <code>encode*</code> methods that have <code>_without_replacement</code> in their name.</td></tr>
 //! <tr><td><code>encoding::EncoderTrap::NcrEscape</code></td><td><code>encode*</code> methods that <i>do not</i> have <code>_without_replacement</code> in their name.</td></tr>
-//! <tr><td><code>encoding::EncoderTrap::Call(EncoderTrapFunc)</code></td><td>Can be implemented using <code>encode*</code> methods that have <code>_without_replacement</code> in their name.</td></tr>
+//! <tr><td><code>encoding::EncoderTrap::Call(EncoderTrapFunc)</code></td><td><code>encoder_options()</code> with <code>UnmappablePolicy::Callback</code>.</td></tr>
 //! </tbody>
 //! </table>
 
+// The tests use `std` even when the library itself doesn't.
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 #![cfg_attr(feature = "simd-accel", feature(cfg_target_feature, platform_intrinsics))]
 
+#[cfg(any(feature = "std", test))]
+extern crate core;
+#[cfg(feature = "alloc")]
+#[macro_use]
+extern crate alloc;
+
 #[macro_use]
 extern crate cfg_if;
 
 #[cfg(feature = "simd-accel")]
 extern crate simd;
 
+#[cfg(feature = "async-io")]
+extern crate futures_core;
+#[cfg(feature = "async-io")]
+extern crate futures_io;
+
+#[cfg(all(test, feature = "async-io"))]
+extern crate futures;
+
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(all(test, feature = "serde"))]
+extern crate serde_json;
+
 #[macro_use]
 mod macros;
 
@@ -526,15 +589,136 @@ mod handles;
 mod data;
 mod variant;
 
+mod bomless;
+#[cfg(feature = "alloc")]
+mod detector;
+#[cfg(feature = "alloc")]
+mod detector_data;
+#[cfg(feature = "alloc")]
+mod http;
+#[cfg(feature = "std")]
+mod io;
+#[cfg(feature = "alloc")]
+mod policy;
+#[cfg(feature = "alloc")]
+mod prescan;
+#[cfg(feature = "alloc")]
+mod sniff;
+#[cfg(feature = "alloc")]
+mod snapshot;
+#[cfg(feature = "alloc")]
+mod urlencoded;
+mod xml;
+
+pub use bomless::sniff_bomless_unicode;
+pub use bomless::BomlessUnicode;
+
+#[cfg(feature = "alloc")]
+pub use detector::EncodingDetector;
+#[cfg(feature = "alloc")]
+pub use detector::JapaneseDetector;
+
+#[cfg(feature = "alloc")]
+pub use http::encoding_for_accept_charset;
+#[cfg(feature = "alloc")]
+pub use http::encoding_for_content_type;
+
+#[cfg(feature = "std")]
+pub use io::DecodeReader;
+#[cfg(feature = "std")]
+pub use io::EncodeWriter;
+#[cfg(feature = "std")]
+pub use io::UnmappableError;
+
+#[cfg(feature = "alloc")]
+pub use policy::DecoderOptions;
+#[cfg(feature = "alloc")]
+pub use policy::EncoderOptions;
+#[cfg(feature = "alloc")]
+pub use policy::MalformedCallback;
+#[cfg(feature = "alloc")]
+pub use policy::MalformedPolicy;
+#[cfg(feature = "alloc")]
+pub use policy::MalformedSequence;
+#[cfg(feature = "alloc")]
+pub use policy::UnmappableCallback;
+#[cfg(feature = "alloc")]
+pub use policy::UnmappableCharacter;
+#[cfg(feature = "alloc")]
+pub use policy::UnmappablePolicy;
+
+#[cfg(feature = "alloc")]
+pub use prescan::prescan_html;
+
+#[cfg(feature = "alloc")]
+pub use sniff::Confidence;
+#[cfg(feature = "alloc")]
+pub use sniff::EncodingSniffer;
+#[cfg(feature = "alloc")]
+pub use sniff::EncodingSource;
+#[cfg(feature = "alloc")]
+pub use sniff::Sniffed;
+
+#[cfg(feature = "alloc")]
+pub use snapshot::DecoderSnapshot;
+#[cfg(feature = "alloc")]
+pub use snapshot::EncoderSnapshot;
+
+#[cfg(feature = "alloc")]
+pub use urlencoded::form_urlencoded_parse;
+#[cfg(feature = "alloc")]
+pub use urlencoded::form_urlencoded_serialize;
+#[cfg(feature = "alloc")]
+pub use urlencoded::percent_encode_query;
+
+pub use utf_8::convert_utf16_to_wtf8;
+pub use utf_8::convert_wtf8_to_utf16;
+pub use utf_8::wtf8_chunks;
+pub use utf_8::wtf8_valid_up_to;
+pub use utf_8::Wtf8Chunk;
+pub use utf_8::Wtf8Chunks;
+pub use utf_8::Wtf8Decoder;
+pub use utf_8::Wtf8Encoder;
+
+pub use xml::xml_sniff;
+
+pub mod mem;
+
+#[cfg(feature = "mime")]
+pub mod mime;
+
+#[cfg(feature = "async-io")]
+mod async_io;
+
+#[cfg(feature = "async-io")]
+pub use async_io::AsyncDecodeReader;
+#[cfg(feature = "async-io")]
+pub use async_io::AsyncEncodeWriter;
+#[cfg(feature = "async-io")]
+pub use async_io::DecodeStream;
+
 use variant::*;
+#[cfg(feature = "alloc")]
+use policy::MalformedHandler;
+#[cfg(feature = "alloc")]
+use policy::UnmappableHandler;
 use utf_8::utf8_valid_up_to;
 use ascii::ascii_valid_up_to;
 use ascii::iso_2022_jp_ascii_valid_up_to;
 
-use std::borrow::Cow;
-use std::cmp::Ordering;
-use std::hash::Hash;
-use std::hash::Hasher;
+#[cfg(feature = "alloc")]
+use alloc::borrow::Cow;
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+#[cfg(feature = "alloc")]
+use alloc::string::ToString;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::hash::Hash;
+use core::hash::Hasher;
 
 /// This has to be the max length of an NCR instead of max
 /// minus one, because we can't rely on getting the minus
@@ -2279,6 +2463,78 @@ impl Encoding {
         }
     }
 
+    /// Returns the encoding that Firefox falls back to for unlabeled content
+    /// when the user interface locale is `locale`.
+    ///
+    /// The argument is a BCP 47 language tag such as `"ja"` or `"zh-TW"`.
+    /// POSIX-style locales such as `"zh_TW.UTF-8"` are accepted, too. The tag
+    /// is matched ASCII case-insensitively first as a whole and then by its
+    /// primary language subtag. Locales without a specific fallback get
+    /// `WINDOWS_1252`.
+    ///
+    /// The data comes from `fallbacks/locales.properties`.
+    ///
+    /// Available to Rust only.
+    pub fn fallback_for_locale(locale: &str) -> &'static Encoding {
+        let tag = locale.split(&['.', '@'][..]).next().unwrap_or("");
+        let language = tag.split(&['-', '_'][..]).next().unwrap_or("");
+        for candidate in &[tag, language] {
+            // Compare as if `_` had been replaced with `-` and the candidate
+            // had been lower-cased.
+            let normalized = candidate.bytes().map(|b| if b == b'_' {
+                                                       b'-'
+                                                   } else {
+                                                       b.to_ascii_lowercase()
+                                                   });
+            if let Ok(i) = data::LOCALE_FALLBACKS
+                   .binary_search_by(|&(l, _)| l.bytes().cmp(normalized.clone())) {
+                return data::LOCALE_FALLBACKS[i].1;
+            }
+        }
+        WINDOWS_1252
+    }
+
+    /// Returns the encoding that Firefox falls back to for unlabeled content
+    /// from a host under the top-level domain `tld`.
+    ///
+    /// The argument is the top-level domain without the dot (e.g. `b"ru"`)
+    /// in its ASCII (Punycode) form. If the argument contains dots, only the
+    /// part after the last dot (ignoring a trailing dot) is considered, so a
+    /// whole host name works, too. Matching is ASCII case-insensitive.
+    ///
+    /// Returns `None` for generic top-level domains such as `com` and for
+    /// country-code top-level domains that are used generically (e.g. `tv`).
+    /// In that case, the caller should use the locale fallback (e.g.
+    /// `Encoding::fallback_for_locale()`) instead. Other country-code
+    /// top-level domains without a specific fallback get `WINDOWS_1252`.
+    ///
+    /// The data comes from `fallbacks/domains.properties`.
+    ///
+    /// Available to Rust only.
+    pub fn fallback_for_tld(tld: &[u8]) -> Option<&'static Encoding> {
+        let tld = if tld.ends_with(b".") {
+            &tld[..tld.len() - 1]
+        } else {
+            tld
+        };
+        let tld = match tld.iter().rposition(|b| *b == b'.') {
+            Some(dot) => &tld[dot + 1..],
+            None => tld,
+        };
+        let lower_case = tld.iter().map(|b| b.to_ascii_lowercase());
+        match data::TLD_FALLBACKS
+                  .binary_search_by(|&(t, _)| t.bytes().cmp(lower_case.clone())) {
+            Ok(i) => data::TLD_FALLBACKS[i].1,
+            Err(_) => {
+                if tld.len() == 2 && tld.iter().all(|b| b.is_ascii_alphabetic()) {
+                    Some(WINDOWS_1252)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
     /// If the argument matches exactly (case-sensitively; no whitespace
     /// removal performed) the name of an encoding, returns
     /// `&'static Encoding` representing that encoding. Otherwise panics.
@@ -2396,6 +2652,7 @@ impl Encoding {
     /// `usize`.
     ///
     /// Available to Rust only.
+    #[cfg(feature = "alloc")]
     pub fn decode<'a>(&'static self, bytes: &'a [u8]) -> (Cow<'a, str>, &'static Encoding, bool) {
         let (encoding, without_bom) = match Encoding::for_bom(bytes) {
             Some((encoding, bom_length)) => (encoding, &bytes[bom_length..]),
@@ -2405,6 +2662,58 @@ impl Encoding {
         (cow, encoding, had_errors)
     }
 
+    /// Decode complete input to `Cow<'a, str>` _with BOM sniffing_ and with
+    /// malformed sequences replaced with the REPLACEMENT CHARACTER when the
+    /// entire input is available as a single buffer (i.e. the end of the
+    /// buffer marks the end of the stream) and report where the malformed
+    /// sequences were.
+    ///
+    /// This method is like `decode()` except the third item in the returned
+    /// tuple lists the malformed sequences with their byte offsets in `bytes`
+    /// (including the BOM if any) and the offsets of the corresponding
+    /// REPLACEMENT CHARACTERs in the output. The list is empty if and only if
+    /// `decode()` would have indicated that there were no errors.
+    ///
+    /// _Note:_ It is wrong to use this when the input buffer represents only
+    /// a segment of the input instead of the whole input. Use
+    /// `decoder_options()` with `record_malformed_sequences()` when decoding
+    /// segmented input.
+    ///
+    /// This method borrows and allocates like `decode()` when there are no
+    /// errors. In the presence of errors, the input is decoded a second time.
+    ///
+    /// # Panics
+    ///
+    /// If the size calculation for a heap-allocated backing buffer overflows
+    /// `usize`.
+    ///
+    /// Available to Rust only.
+    #[cfg(feature = "alloc")]
+    pub fn decode_with_malformed_sequences<'a>(&'static self,
+                                               bytes: &'a [u8])
+                                               -> (Cow<'a, str>,
+                                                   &'static Encoding,
+                                                   Vec<MalformedSequence>) {
+        let (cow, encoding, had_errors) = self.decode(bytes);
+        if !had_errors {
+            return (cow, encoding, Vec::new());
+        }
+        let mut decoder = self.decoder_options()
+                              .record_malformed_sequences()
+                              .build();
+        let mut string = String::with_capacity(
+            decoder
+                .max_utf8_buffer_length(bytes.len())
+                .unwrap()
+        );
+        let (result, read, _) = decoder.decode_to_string(bytes, &mut string, true);
+        debug_assert_eq!(result, CoderResult::InputEmpty);
+        debug_assert_eq!(read, bytes.len());
+        debug_assert_eq!(string, cow);
+        debug_assert_eq!(decoder.encoding(), encoding);
+        (Cow::Owned(string), encoding, decoder.take_malformed_sequences())
+    }
+
     /// Decode complete input to `Cow<'a, str>` _with BOM removal_ and with
     /// malformed sequences replaced with the REPLACEMENT CHARACTER when the
     /// entire input is available as a single buffer (i.e. the end of the
@@ -2438,6 +2747,7 @@ impl Encoding {
     /// `usize`.
     ///
     /// Available to Rust only.
+    #[cfg(feature = "alloc")]
     pub fn decode_with_bom_removal<'a>(&'static self, bytes: &'a [u8]) -> (Cow<'a, str>, bool) {
         let without_bom = if self == UTF_8 && bytes.starts_with(b"\xEF\xBB\xBF") {
             &bytes[3..]
@@ -2483,6 +2793,7 @@ impl Encoding {
     /// `usize`.
     ///
     /// Available to Rust only.
+    #[cfg(feature = "alloc")]
     pub fn decode_without_bom_handling<'a>(&'static self, bytes: &'a [u8]) -> (Cow<'a, str>, bool) {
         let (mut decoder, mut string, mut total_read) = if self.is_potentially_borrowable() {
             let valid_up_to = if self == UTF_8 {
@@ -2493,7 +2804,7 @@ impl Encoding {
                 ascii_valid_up_to(bytes)
             };
             if valid_up_to == bytes.len() {
-                let str: &str = unsafe { std::mem::transmute(bytes) };
+                let str: &str = unsafe { core::mem::transmute(bytes) };
                 return (Cow::Borrowed(str), false);
             }
             let decoder = self.new_decoder_without_bom_handling();
@@ -2517,7 +2828,7 @@ impl Encoding {
             unsafe {
                 let mut vec = string.as_mut_vec();
                 vec.set_len(valid_up_to);
-                std::ptr::copy_nonoverlapping(bytes.as_ptr(), vec.as_mut_ptr(), valid_up_to);
+                core::ptr::copy_nonoverlapping(bytes.as_ptr(), vec.as_mut_ptr(), valid_up_to);
             }
             (decoder, string, valid_up_to)
         } else {
@@ -2584,13 +2895,14 @@ impl Encoding {
     /// `usize`.
     ///
     /// Available to Rust only.
+    #[cfg(feature = "alloc")]
     pub fn decode_without_bom_handling_and_without_replacement<'a>(&'static self,
                                                                    bytes: &'a [u8])
                                                                    -> Option<Cow<'a, str>> {
         if self == UTF_8 {
             let valid_up_to = utf8_valid_up_to(bytes);
             if valid_up_to == bytes.len() {
-                let str: &str = unsafe { std::mem::transmute(bytes) };
+                let str: &str = unsafe { core::mem::transmute(bytes) };
                 return Some(Cow::Borrowed(str));
             }
             return None;
@@ -2602,7 +2914,7 @@ impl Encoding {
                 ascii_valid_up_to(bytes)
             };
             if valid_up_to == bytes.len() {
-                let str: &str = unsafe { std::mem::transmute(bytes) };
+                let str: &str = unsafe { core::mem::transmute(bytes) };
                 return Some(Cow::Borrowed(str));
             }
             let decoder = self.new_decoder_without_bom_handling();
@@ -2618,7 +2930,7 @@ impl Encoding {
             unsafe {
                 let mut vec = string.as_mut_vec();
                 vec.set_len(valid_up_to);
-                std::ptr::copy_nonoverlapping(bytes.as_ptr(), vec.as_mut_ptr(), valid_up_to);
+                core::ptr::copy_nonoverlapping(bytes.as_ptr(), vec.as_mut_ptr(), valid_up_to);
             }
             (decoder, string, &bytes[valid_up_to..])
         } else {
@@ -2661,6 +2973,9 @@ impl Encoding {
     /// unmappable characters (that were replaced with HTML numeric character
     /// references).
     ///
+    /// To replace unmappable characters with something else, use
+    /// `encoder_options()`.
+    ///
     /// _Note:_ It is wrong to use this when the input buffer represents only
     /// a segment of the input instead of the whole input. Use `new_encoder()`
     /// when encoding segmented output.
@@ -2680,6 +2995,7 @@ impl Encoding {
     /// `usize`.
     ///
     /// Available to Rust only.
+    #[cfg(feature = "alloc")]
     pub fn encode<'a>(&'static self, string: &'a str) -> (Cow<'a, [u8]>, &'static Encoding, bool) {
         let output_encoding = self.output_encoding();
         if output_encoding == UTF_8 {
@@ -2708,7 +3024,7 @@ impl Encoding {
         );
         unsafe {
             vec.set_len(valid_up_to);
-            std::ptr::copy_nonoverlapping(bytes.as_ptr(), vec.as_mut_ptr(), valid_up_to);
+            core::ptr::copy_nonoverlapping(bytes.as_ptr(), vec.as_mut_ptr(), valid_up_to);
         }
         let mut total_read = valid_up_to;
         let mut total_had_errors = false;
@@ -2739,6 +3055,287 @@ impl Encoding {
         }
     }
 
+    /// Decode complete input to `Vec<u16>` _with BOM sniffing_ and with
+    /// malformed sequences replaced with the REPLACEMENT CHARACTER when the
+    /// entire input is available as a single buffer (i.e. the end of the
+    /// buffer marks the end of the stream).
+    ///
+    /// This is the UTF-16 counterpart of `decode()`.
+    ///
+    /// The second item in the returned tuple is the encoding that was actually
+    /// used (which may differ from this encoding thanks to BOM sniffing).
+    ///
+    /// The third item in the returned tuple indicates whether there were
+    /// malformed sequences (that were replaced with the REPLACEMENT CHARACTER).
+    ///
+    /// _Note:_ It is wrong to use this when the input buffer represents only
+    /// a segment of the input instead of the whole input. Use `new_decoder()`
+    /// when decoding segmented input.
+    ///
+    /// This method performs a single heap allocation for the backing buffer of
+    /// the `Vec<u16>` sized for the worst case.
+    ///
+    /// # Panics
+    ///
+    /// If the size calculation for the heap-allocated backing buffer overflows
+    /// `usize`.
+    ///
+    /// Available to Rust only.
+    #[cfg(feature = "alloc")]
+    pub fn decode_to_utf16(&'static self, bytes: &[u8]) -> (Vec<u16>, &'static Encoding, bool) {
+        let (encoding, without_bom) = match Encoding::for_bom(bytes) {
+            Some((encoding, bom_length)) => (encoding, &bytes[bom_length..]),
+            None => (self, bytes),
+        };
+        let (vec, had_errors) = encoding.decode_to_utf16_without_bom_handling(without_bom);
+        (vec, encoding, had_errors)
+    }
+
+    /// Decode complete input to `Vec<u16>` _with BOM removal_ and with
+    /// malformed sequences replaced with the REPLACEMENT CHARACTER when the
+    /// entire input is available as a single buffer (i.e. the end of the
+    /// buffer marks the end of the stream).
+    ///
+    /// This is the UTF-16 counterpart of `decode_with_bom_removal()`.
+    ///
+    /// The second item in the returned pair indicates whether there were
+    /// malformed sequences (that were replaced with the REPLACEMENT CHARACTER).
+    ///
+    /// _Note:_ It is wrong to use this when the input buffer represents only
+    /// a segment of the input instead of the whole input. Use
+    /// `new_decoder_with_bom_removal()` when decoding segmented input.
+    ///
+    /// This method performs a single heap allocation for the backing buffer of
+    /// the `Vec<u16>` sized for the worst case.
+    ///
+    /// # Panics
+    ///
+    /// If the size calculation for the heap-allocated backing buffer overflows
+    /// `usize`.
+    ///
+    /// Available to Rust only.
+    #[cfg(feature = "alloc")]
+    pub fn decode_to_utf16_with_bom_removal(&'static self, bytes: &[u8]) -> (Vec<u16>, bool) {
+        let without_bom = if self == UTF_8 && bytes.starts_with(b"\xEF\xBB\xBF") {
+            &bytes[3..]
+        } else if (self == UTF_16LE && bytes.starts_with(b"\xFF\xFE")) ||
+                  (self == UTF_16BE && bytes.starts_with(b"\xFE\xFF")) {
+            &bytes[2..]
+        } else {
+            bytes
+        };
+        self.decode_to_utf16_without_bom_handling(without_bom)
+    }
+
+    /// Decode complete input to `Vec<u16>` _without BOM handling_ and
+    /// with malformed sequences replaced with the REPLACEMENT CHARACTER when
+    /// the entire input is available as a single buffer (i.e. the end of the
+    /// buffer marks the end of the stream).
+    ///
+    /// This is the UTF-16 counterpart of `decode_without_bom_handling()`.
+    ///
+    /// The second item in the returned pair indicates whether there were
+    /// malformed sequences (that were replaced with the REPLACEMENT CHARACTER).
+    ///
+    /// _Note:_ It is wrong to use this when the input buffer represents only
+    /// a segment of the input instead of the whole input. Use
+    /// `new_decoder_without_bom_handling()` when decoding segmented input.
+    ///
+    /// This method performs a single heap allocation for the backing buffer of
+    /// the `Vec<u16>` sized for the worst case.
+    ///
+    /// # Panics
+    ///
+    /// If the size calculation for the heap-allocated backing buffer overflows
+    /// `usize`.
+    ///
+    /// Available to Rust only.
+    #[cfg(feature = "alloc")]
+    pub fn decode_to_utf16_without_bom_handling(&'static self, bytes: &[u8]) -> (Vec<u16>, bool) {
+        let mut decoder = self.new_decoder_without_bom_handling();
+        let mut vec = vec![0u16; decoder.max_utf16_buffer_length(bytes.len()).unwrap()];
+        let (result, read, written, had_errors) = decoder.decode_to_utf16(bytes, &mut vec[..], true);
+        debug_assert_eq!(result, CoderResult::InputEmpty);
+        debug_assert_eq!(read, bytes.len());
+        vec.truncate(written);
+        (vec, had_errors)
+    }
+
+    /// Decode complete input to `Vec<u16>` _without BOM handling_ and
+    /// _with malformed sequences treated as fatal_ when the entire input is
+    /// available as a single buffer (i.e. the end of the buffer marks the end
+    /// of the stream).
+    ///
+    /// This is the UTF-16 counterpart of
+    /// `decode_without_bom_handling_and_without_replacement()`.
+    ///
+    /// Returns `None` if a malformed sequence was encountered and the result
+    /// of the decode as `Some(Vec<u16>)` otherwise.
+    ///
+    /// _Note:_ It is wrong to use this when the input buffer represents only
+    /// a segment of the input instead of the whole input. Use
+    /// `new_decoder_without_bom_handling()` when decoding segmented input.
+    ///
+    /// This method performs a single heap allocation for the backing buffer of
+    /// the `Vec<u16>` sized for the worst case.
+    ///
+    /// # Panics
+    ///
+    /// If the size calculation for the heap-allocated backing buffer overflows
+    /// `usize`.
+    ///
+    /// Available to Rust only.
+    #[cfg(feature = "alloc")]
+    pub fn decode_to_utf16_without_bom_handling_and_without_replacement(&'static self,
+                                                                        bytes: &[u8])
+                                                                        -> Option<Vec<u16>> {
+        let mut decoder = self.new_decoder_without_bom_handling();
+        let mut vec = vec![0u16; decoder.max_utf16_buffer_length(bytes.len()).unwrap()];
+        let (result, read, written) =
+            decoder.decode_to_utf16_without_replacement(bytes, &mut vec[..], true);
+        match result {
+            DecoderResult::InputEmpty => {
+                debug_assert_eq!(read, bytes.len());
+                vec.truncate(written);
+                Some(vec)
+            }
+            DecoderResult::Malformed(_, _) => None,
+            DecoderResult::OutputFull => unreachable!(),
+        }
+    }
+
+    /// Encode complete UTF-16 input to `Cow<'static, [u8]>` with unmappable
+    /// characters replaced with decimal numeric character references when the
+    /// entire input is available as a single buffer (i.e. the end of the
+    /// buffer marks the end of the stream).
+    ///
+    /// This is the UTF-16 counterpart of `encode()`. Unpaired surrogates in
+    /// the input are treated as U+FFFD. Since the output can never be a borrow
+    /// of UTF-16 input, the returned `Cow` is always owned. (It is a `Cow`
+    /// for symmetry with `encode()`.)
+    ///
+    /// The second item in the returned tuple is the encoding that was actually
+    /// used (which may differ from this encoding thanks to some encodings
+    /// having UTF-8 as their output encoding).
+    ///
+    /// The third item in the returned tuple indicates whether there were
+    /// unmappable characters (that were replaced with HTML numeric character
+    /// references).
+    ///
+    /// _Note:_ It is wrong to use this when the input buffer represents only
+    /// a segment of the input instead of the whole input. Use `new_encoder()`
+    /// when encoding segmented output.
+    ///
+    /// This method performs a single heap allocation for the backing buffer
+    /// of the `Vec<u8>` if there are no unmappable characters and potentially
+    /// multiple heap allocations if there are. These allocations are tuned
+    /// for jemalloc and may not be optimal when using a different allocator
+    /// that doesn't use power-of-two buckets.
+    ///
+    /// # Panics
+    ///
+    /// If the size calculation for a heap-allocated backing buffer overflows
+    /// `usize`.
+    ///
+    /// Available to Rust only.
+    #[cfg(feature = "alloc")]
+    pub fn encode_from_utf16(&'static self,
+                             string: &[u16])
+                             -> (Cow<'static, [u8]>, &'static Encoding, bool) {
+        let output_encoding = self.output_encoding();
+        let mut encoder = output_encoding.new_encoder();
+        let mut vec: Vec<u8> = Vec::with_capacity(
+            encoder
+                .max_buffer_length_from_utf16_if_no_unmappables(string.len())
+                .unwrap()
+                .next_power_of_two()
+        );
+        let mut total_read = 0usize;
+        let mut total_had_errors = false;
+        loop {
+            let (result, read, had_errors) = unsafe {
+                let old_len = vec.len();
+                let capacity = vec.capacity();
+                vec.set_len(capacity);
+                let (result, read, written, had_errors) =
+                    encoder.encode_from_utf16(&string[total_read..], &mut vec[old_len..], true);
+                vec.set_len(old_len + written);
+                (result, read, had_errors)
+            };
+            total_read += read;
+            total_had_errors |= had_errors;
+            match result {
+                CoderResult::InputEmpty => {
+                    debug_assert_eq!(total_read, string.len());
+                    return (Cow::Owned(vec), output_encoding, total_had_errors);
+                }
+                CoderResult::OutputFull => {
+                    // reserve_exact wants to know how much more on top of current
+                    // length--not current capacity.
+                    let needed =
+                        encoder.max_buffer_length_from_utf16_if_no_unmappables(
+                            string.len() - total_read,
+                        );
+                    let rounded = (checked_add(vec.capacity(), needed))
+                        .unwrap()
+                        .next_power_of_two();
+                    let additional = rounded - vec.len();
+                    vec.reserve_exact(additional);
+                }
+            }
+        }
+    }
+
+    /// Encode complete input to `Cow<'a, [u8]>` with unmappable characters
+    /// replaced with decimal numeric character references when the entire
+    /// input is available as a single buffer (i.e. the end of the buffer
+    /// marks the end of the stream) and report which characters were
+    /// unmappable.
+    ///
+    /// This method is like `encode()` except the third item in the returned
+    /// tuple lists the unmappable characters with their byte and character
+    /// offsets in `string`. The list is empty if and only if `encode()` would
+    /// have indicated that there were no unmappable characters.
+    ///
+    /// This method borrows and allocates like `encode()`. In the presence of
+    /// unmappable characters, the input is additionally processed as with
+    /// `unmappables()`.
+    ///
+    /// # Panics
+    ///
+    /// If the size calculation for a heap-allocated backing buffer overflows
+    /// `usize`.
+    ///
+    /// Available to Rust only.
+    #[cfg(feature = "alloc")]
+    pub fn encode_with_unmappables<'a>(&'static self,
+                                       string: &'a str)
+                                       -> (Cow<'a, [u8]>,
+                                           &'static Encoding,
+                                           Vec<UnmappableCharacter>) {
+        let (cow, encoding, had_unmappables) = self.encode(string);
+        if !had_unmappables {
+            return (cow, encoding, Vec::new());
+        }
+        (cow, encoding, self.unmappables(string))
+    }
+
+    /// Lists the characters in `string` that the _output encoding_ of this
+    /// encoding cannot represent together with their byte and character
+    /// offsets in `string` without building the encoded output.
+    ///
+    /// Returns an empty `Vec` without examining the input if the output
+    /// encoding is UTF-8.
+    ///
+    /// Available to Rust only.
+    #[cfg(feature = "alloc")]
+    pub fn unmappables(&'static self, string: &str) -> Vec<UnmappableCharacter> {
+        if self.output_encoding() == UTF_8 {
+            return Vec::new();
+        }
+        policy::unmappables(self.new_encoder(), string)
+    }
+
     fn new_variant_decoder(&'static self) -> VariantDecoder {
         self.variant.new_variant_decoder()
     }
@@ -2781,6 +3378,24 @@ impl Encoding {
         Decoder::new(self, self.new_variant_decoder(), BomHandling::Off)
     }
 
+    /// Starts building a decoder for this encoding with non-default BOM
+    /// handling and/or a non-default policy for malformed sequences.
+    ///
+    /// Available to Rust only.
+    #[cfg(feature = "alloc")]
+    pub fn decoder_options(&'static self) -> DecoderOptions {
+        DecoderOptions::new(self)
+    }
+
+    /// Starts building an encoder for the output encoding of this encoding
+    /// with a non-default policy for unmappable characters.
+    ///
+    /// Available to Rust only.
+    #[cfg(feature = "alloc")]
+    pub fn encoder_options(&'static self) -> EncoderOptions {
+        EncoderOptions::new(self)
+    }
+
     /// Instantiates a new encoder for the output encoding of this encoding.
     ///
     /// Available via the C wrapper.
@@ -2840,8 +3455,8 @@ impl Hash for Encoding {
     }
 }
 
-impl std::fmt::Debug for Encoding {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+impl core::fmt::Debug for Encoding {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         write!(f, "Encoding {{ {} }}", self.name)
     }
 }
@@ -2942,6 +3557,27 @@ pub enum DecoderResult {
     Malformed(u8, u8), // u8 instead of usize to avoid useless bloat
 }
 
+/// Result of `Decoder::switch_encoding()`.
+///
+/// Available to Rust only.
+#[must_use]
+#[derive(Debug, PartialEq, Eq)]
+pub enum SwitchResult {
+    /// The decoder now decodes the rest of the stream using the new
+    /// encoding. The output produced so far is what the new encoding would
+    /// have produced, too, so nothing needs to be redone.
+    Switched,
+
+    /// The decoder was left as is, because the output produced so far could
+    /// differ from what the new encoding would produce. The caller needs to
+    /// decode the stream again from the start with the new encoding.
+    ///
+    /// The wrapped integer is the offset into the stream of the first byte
+    /// whose decoding could differ, i.e. the output for the bytes before it
+    /// can be kept.
+    ReloadNeeded(u64),
+}
+
 /// A converter that decodes a byte stream into Unicode according to a
 /// character encoding in a streaming (incremental) manner.
 ///
@@ -2986,6 +3622,15 @@ pub enum DecoderResult {
 /// with the REPLACEMENT CHARACTER and errors do not cause the methods to
 /// return early.
 ///
+/// A decoder obtained via [`Encoding::decoder_options()`][5] may have a
+/// [`MalformedPolicy`][6] other than the REPLACEMENT CHARACTER. Such a
+/// decoder applies its policy in the methods whose name does not end with
+/// `*_without_replacement`. Since the replacement output can be longer than
+/// the REPLACEMENT CHARACTER, the output buffer lengths discussed below are not
+/// guaranteed to be enough for such a decoder. If the replacement output does
+/// not fit, the decoder holds on to the rest of it, returns indicating that
+/// more output space is needed and writes the rest upon the next call.
+///
 /// When decoding to UTF-8, the output buffer must have at least 4 bytes of
 /// space. When decoding to UTF-16, the output buffer must have at least two
 /// UTF-16 code units (`u16`) of space.
@@ -3031,6 +3676,8 @@ pub enum DecoderResult {
 /// [2]: #method.max_utf8_buffer_length_without_replacement
 /// [3]: #method.max_utf8_buffer_length
 /// [4]: #method.max_utf16_buffer_length
+/// [5]: struct.Encoding.html#method.decoder_options
+/// [6]: enum.MalformedPolicy.html
 ///
 /// # Infinite loops
 ///
@@ -3043,6 +3690,11 @@ pub struct Decoder {
     encoding: &'static Encoding,
     variant: VariantDecoder,
     life_cycle: DecoderLifeCycle,
+    #[cfg(feature = "alloc")]
+    malformed: Option<Box<MalformedHandler>>,
+    ascii_prefix: u64,
+    iso_2022_jp_ascii_prefix: u64,
+    seen_non_ascii: bool,
 }
 
 impl Decoder {
@@ -3053,19 +3705,94 @@ impl Decoder {
             life_cycle: match sniffing {
                 BomHandling::Off => DecoderLifeCycle::Converting,
                 BomHandling::Sniff => DecoderLifeCycle::AtStart,
-                BomHandling::Remove => {
-                    if enc == UTF_8 {
-                        DecoderLifeCycle::AtUtf8Start
-                    } else if enc == UTF_16BE {
-                        DecoderLifeCycle::AtUtf16BeStart
-                    } else if enc == UTF_16LE {
-                        DecoderLifeCycle::AtUtf16LeStart
-                    } else {
-                        DecoderLifeCycle::Converting
-                    }
-                }
+                BomHandling::Remove => Decoder::bom_removal_life_cycle(enc),
             },
+            #[cfg(feature = "alloc")]
+            malformed: None,
+            ascii_prefix: 0,
+            iso_2022_jp_ascii_prefix: 0,
+            seen_non_ascii: false,
+        }
+    }
+
+    fn bom_removal_life_cycle(enc: &'static Encoding) -> DecoderLifeCycle {
+        if enc == UTF_8 {
+            DecoderLifeCycle::AtUtf8Start
+        } else if enc == UTF_16BE {
+            DecoderLifeCycle::AtUtf16BeStart
+        } else if enc == UTF_16LE {
+            DecoderLifeCycle::AtUtf16LeStart
+        } else {
+            DecoderLifeCycle::Converting
+        }
+    }
+
+    /// Records how far from the start of the stream the input consumed by
+    /// the decode methods has been ASCII. Stops looking at the input upon
+    /// the first non-ASCII byte, so the cost is a single ASCII scan of the
+    /// ASCII prefix of the stream.
+    fn track_ascii(&mut self, consumed: &[u8]) {
+        if self.seen_non_ascii {
+            return;
+        }
+        if self.iso_2022_jp_ascii_prefix == self.ascii_prefix {
+            self.iso_2022_jp_ascii_prefix += iso_2022_jp_ascii_valid_up_to(consumed) as u64;
+        }
+        let valid = ascii_valid_up_to(consumed);
+        self.ascii_prefix += valid as u64;
+        if valid < consumed.len() {
+            self.seen_non_ascii = true;
+        }
+    }
+
+    /// Switches the decoder to another encoding in the middle of the stream
+    /// if doing so does not change the output produced so far.
+    ///
+    /// This is meant for HTML parsers that find a `meta` declaring the
+    /// encoding after having started decoding with a tentative encoding.
+    ///
+    /// The switch succeeds if all the input consumed so far was ASCII (as
+    /// determined by `Encoding::ascii_valid_up_to()`) and both the current
+    /// and the new encoding decode ASCII as ASCII. When ISO-2022-JP is
+    /// involved, the input must also have been representable in the ASCII
+    /// state of ISO-2022-JP (as determined by
+    /// `Encoding::iso_2022_jp_ascii_valid_up_to()`). A BOM counts as
+    /// non-ASCII input. UTF-16BE, UTF-16LE and replacement can only be
+    /// switched to or from before any input has been consumed.
+    ///
+    /// Otherwise, the decoder is left as is and the return value tells the
+    /// offset of the first byte of the stream whose decoding could differ.
+    /// The malformed sequence policy of the decoder (if any) is kept either
+    /// way.
+    ///
+    /// Available to Rust only.
+    pub fn switch_encoding(&mut self, encoding: &'static Encoding) -> SwitchResult {
+        if encoding == self.encoding {
+            return SwitchResult::Switched;
+        }
+        let safe = if !self.encoding.is_potentially_borrowable() ||
+                      !encoding.is_potentially_borrowable() {
+            0
+        } else if self.encoding == ISO_2022_JP || encoding == ISO_2022_JP {
+            self.iso_2022_jp_ascii_prefix
+        } else {
+            self.ascii_prefix
+        };
+        if self.seen_non_ascii || safe < self.ascii_prefix {
+            return SwitchResult::ReloadNeeded(safe);
+        }
+        match self.life_cycle {
+            DecoderLifeCycle::AtUtf8Start |
+            DecoderLifeCycle::AtUtf16BeStart |
+            DecoderLifeCycle::AtUtf16LeStart => {
+                self.life_cycle = Decoder::bom_removal_life_cycle(encoding);
+            }
+            DecoderLifeCycle::Finished => panic!("Must not use a decoder that has finished."),
+            _ => {}
         }
+        self.encoding = encoding;
+        self.variant = encoding.new_variant_decoder();
+        SwitchResult::Switched
     }
 
     /// The `Encoding` this `Decoder` is for.
@@ -3078,6 +3805,26 @@ impl Decoder {
         self.encoding
     }
 
+    /// Returns `true` if the decoder has seen a prefix of a byte order mark
+    /// or an incomplete byte sequence (or an escape sequence or other bytes
+    /// whose meaning depends on bytes that haven't been seen yet) and `false`
+    /// otherwise.
+    ///
+    /// I.e. returns `false` if ending the stream at this point would not
+    /// produce any more output.
+    ///
+    /// Available to Rust only.
+    pub fn has_pending_state(&self) -> bool {
+        match self.life_cycle {
+            DecoderLifeCycle::SeenUtf8First |
+            DecoderLifeCycle::SeenUtf8Second |
+            DecoderLifeCycle::SeenUtf16BeFirst |
+            DecoderLifeCycle::SeenUtf16LeFirst |
+            DecoderLifeCycle::ConvertingWithPendingBB => true,
+            _ => self.variant.has_pending_state(),
+        }
+    }
+
     /// Query the worst-case UTF-8 output size _with replacement_.
     ///
     /// Returns the size of the output buffer in UTF-8 code units (`u8`)
@@ -3106,7 +3853,7 @@ impl Decoder {
                             checked_div(byte_length.checked_add(1), 2),
                         ),
                     ) {
-                        let utf_bom = std::cmp::max(utf8_bom, utf16_bom);
+                        let utf_bom = core::cmp::max(utf8_bom, utf16_bom);
                         let encoding = self.encoding();
                         if encoding == UTF_8 || encoding == UTF_16LE || encoding == UTF_16BE {
                             // No need to consider the internal state of the underlying decoder,
@@ -3115,7 +3862,7 @@ impl Decoder {
                         } else if let Some(non_bom) = self.variant.max_utf8_buffer_length(
                             byte_length,
                         ) {
-                            return Some(std::cmp::max(utf_bom, non_bom));
+                            return Some(core::cmp::max(utf_bom, non_bom));
                         }
                     }
                 }
@@ -3134,7 +3881,7 @@ impl Decoder {
                             // because it is at start, because no data has reached it yet.
                             return Some(utf8_bom);
                         } else if let Some(non_bom) = self.variant.max_utf8_buffer_length(sum) {
-                            return Some(std::cmp::max(utf8_bom, non_bom));
+                            return Some(core::cmp::max(utf8_bom, non_bom));
 
                         }
                     }
@@ -3166,7 +3913,7 @@ impl Decoder {
                             // because it is at start, because no data has reached it yet.
                             return Some(utf16_bom);
                         } else if let Some(non_bom) = self.variant.max_utf8_buffer_length(sum) {
-                            return Some(std::cmp::max(utf16_bom, non_bom));
+                            return Some(core::cmp::max(utf16_bom, non_bom));
                         }
                     }
                 }
@@ -3207,7 +3954,7 @@ impl Decoder {
                             checked_div(byte_length.checked_add(1), 2),
                         ),
                     ) {
-                        let utf_bom = std::cmp::max(utf8_bom, utf16_bom);
+                        let utf_bom = core::cmp::max(utf8_bom, utf16_bom);
                         let encoding = self.encoding();
                         if encoding == UTF_8 || encoding == UTF_16LE || encoding == UTF_16BE {
                             // No need to consider the internal state of the underlying decoder,
@@ -3216,7 +3963,7 @@ impl Decoder {
                         } else if let Some(non_bom) =
                             self.variant
                                 .max_utf8_buffer_length_without_replacement(byte_length) {
-                            return Some(std::cmp::max(utf_bom, non_bom));
+                            return Some(core::cmp::max(utf_bom, non_bom));
                         }
                     }
                 }
@@ -3236,7 +3983,7 @@ impl Decoder {
                             return Some(utf8_bom);
                         } else if let Some(non_bom) =
                             self.variant.max_utf8_buffer_length_without_replacement(sum) {
-                            return Some(std::cmp::max(utf8_bom, non_bom));
+                            return Some(core::cmp::max(utf8_bom, non_bom));
                         }
                     }
                 }
@@ -3268,7 +4015,7 @@ impl Decoder {
                             return Some(utf16_bom);
                         } else if let Some(non_bom) =
                             self.variant.max_utf8_buffer_length_without_replacement(sum) {
-                            return Some(std::cmp::max(utf16_bom, non_bom));
+                            return Some(core::cmp::max(utf16_bom, non_bom));
                         }
                     }
                 }
@@ -3290,6 +4037,10 @@ impl Decoder {
                           dst: &mut [u8],
                           last: bool)
                           -> (CoderResult, usize, usize, bool) {
+        #[cfg(feature = "alloc")]
+        if self.malformed.is_some() {
+            return policy::decode_to_utf8_with_policy(self, src, dst, last);
+        }
         let mut had_errors = false;
         let mut total_read = 0usize;
         let mut total_written = 0usize;
@@ -3342,7 +4093,7 @@ impl Decoder {
                          dst: &mut str,
                          last: bool)
                          -> (CoderResult, usize, usize, bool) {
-        let bytes: &mut [u8] = unsafe { std::mem::transmute(dst) };
+        let bytes: &mut [u8] = unsafe { core::mem::transmute(dst) };
         let (result, read, written, replaced) = self.decode_to_utf8(src, bytes, last);
         let len = bytes.len();
         let mut trail = written;
@@ -3350,7 +4101,7 @@ impl Decoder {
         // bytes of trailing garbage. No need to optimize non-ASCII-compatible
         // encodings to avoid overwriting here.
         if self.encoding != UTF_8 {
-            let max = std::cmp::min(len, trail + ascii::STRIDE_SIZE);
+            let max = core::cmp::min(len, trail + ascii::STRIDE_SIZE);
             while trail < max {
                 bytes[trail] = 0;
                 trail += 1;
@@ -3380,6 +4131,7 @@ impl Decoder {
     /// methods collectively.
     ///
     /// Available to Rust only.
+    #[cfg(feature = "alloc")]
     pub fn decode_to_string(&mut self,
                             src: &[u8],
                             dst: &mut String,
@@ -3412,6 +4164,7 @@ impl Decoder {
                             decode_to_utf8_after_one_potential_bom_byte,
                             decode_to_utf8_after_two_potential_bom_bytes,
                             decode_to_utf8_checking_end_with_offset,
+                            decode_to_utf8_untracked,
                             u8);
 
     /// Incrementally decode a byte stream into UTF-8 with type system signaling
@@ -3430,7 +4183,7 @@ impl Decoder {
                                              dst: &mut str,
                                              last: bool)
                                              -> (DecoderResult, usize, usize) {
-        let bytes: &mut [u8] = unsafe { std::mem::transmute(dst) };
+        let bytes: &mut [u8] = unsafe { core::mem::transmute(dst) };
         let (result, read, written) = self.decode_to_utf8_without_replacement(src, bytes, last);
         let len = bytes.len();
         let mut trail = written;
@@ -3438,7 +4191,7 @@ impl Decoder {
         // bytes of trailing garbage. No need to optimize non-ASCII-compatible
         // encodings to avoid overwriting here.
         if self.encoding != UTF_8 {
-            let max = std::cmp::min(len, trail + ascii::STRIDE_SIZE);
+            let max = core::cmp::min(len, trail + ascii::STRIDE_SIZE);
             while trail < max {
                 bytes[trail] = 0;
                 trail += 1;
@@ -3466,6 +4219,7 @@ impl Decoder {
     /// methods collectively.
     ///
     /// Available to Rust only.
+    #[cfg(feature = "alloc")]
     pub fn decode_to_string_without_replacement(&mut self,
                                                 src: &[u8],
                                                 dst: &mut String,
@@ -3511,7 +4265,7 @@ impl Decoder {
                         1,
                         checked_div(byte_length.checked_add(1), 2),
                     ) {
-                        let utf_bom = std::cmp::max(utf8_bom, utf16_bom);
+                        let utf_bom = core::cmp::max(utf8_bom, utf16_bom);
                         let encoding = self.encoding();
                         if encoding == UTF_8 || encoding == UTF_16LE || encoding == UTF_16BE {
                             // No need to consider the internal state of the underlying decoder,
@@ -3520,7 +4274,7 @@ impl Decoder {
                         } else if let Some(non_bom) = self.variant.max_utf16_buffer_length(
                             byte_length,
                         ) {
-                            return Some(std::cmp::max(utf_bom, non_bom));
+                            return Some(core::cmp::max(utf_bom, non_bom));
                         }
                     }
                 }
@@ -3539,7 +4293,7 @@ impl Decoder {
                             // because it is at start, because no data has reached it yet.
                             return Some(utf8_bom);
                         } else if let Some(non_bom) = self.variant.max_utf16_buffer_length(sum) {
-                            return Some(std::cmp::max(utf8_bom, non_bom));
+                            return Some(core::cmp::max(utf8_bom, non_bom));
                         }
                     }
                 }
@@ -3564,7 +4318,7 @@ impl Decoder {
                             // because it is at start, because no data has reached it yet.
                             return Some(utf16_bom);
                         } else if let Some(non_bom) = self.variant.max_utf16_buffer_length(sum) {
-                            return Some(std::cmp::max(utf16_bom, non_bom));
+                            return Some(core::cmp::max(utf16_bom, non_bom));
                         }
                     }
                 }
@@ -3586,6 +4340,10 @@ impl Decoder {
                            dst: &mut [u16],
                            last: bool)
                            -> (CoderResult, usize, usize, bool) {
+        #[cfg(feature = "alloc")]
+        if self.malformed.is_some() {
+            return policy::decode_to_utf16_with_policy(self, src, dst, last);
+        }
         let mut had_errors = false;
         let mut total_read = 0usize;
         let mut total_written = 0usize;
@@ -3630,6 +4388,7 @@ impl Decoder {
                             decode_to_utf16_after_one_potential_bom_byte,
                             decode_to_utf16_after_two_potential_bom_bytes,
                             decode_to_utf16_checking_end_with_offset,
+                            decode_to_utf16_untracked,
                             u16);
 }
 
@@ -3661,7 +4420,7 @@ pub enum EncoderResult {
 
 impl EncoderResult {
     fn unmappable_from_bmp(bmp: u16) -> EncoderResult {
-        EncoderResult::Unmappable(::std::char::from_u32(bmp as u32).unwrap())
+        EncoderResult::Unmappable(::core::char::from_u32(bmp as u32).unwrap())
     }
 }
 
@@ -3705,6 +4464,16 @@ impl EncoderResult {
 /// with the corresponding numeric character references and unmappable
 /// characters do not cause the methods to return early.
 ///
+/// An encoder obtained via [`Encoding::encoder_options()`][6] may have an
+/// [`UnmappablePolicy`][7] other than decimal numeric character references.
+/// Such an encoder applies its policy in the methods whose name does not end
+/// with `*_without_replacement`. Since the replacement output can be longer
+/// than a numeric character reference, the output buffer lengths discussed
+/// below are not guaranteed to be enough for such an encoder. If the
+/// replacement output does not fit, the encoder holds on to the rest of it,
+/// returns indicating that more output space is needed and writes the rest
+/// upon the next call.
+///
 /// When encoding from UTF-8 without replacement, the methods are guaranteed
 /// not to return indicating that more output space is needed if the length
 /// of the output buffer is at least the length returned by
@@ -3768,6 +4537,8 @@ impl EncoderResult {
 /// [3]: #method.max_buffer_length_from_utf8_if_no_unmappables
 /// [4]: #method.max_buffer_length_from_utf16_without_replacement
 /// [5]: #method.max_buffer_length_from_utf16_if_no_unmappables
+/// [6]: struct.Encoding.html#method.encoder_options
+/// [7]: enum.UnmappablePolicy.html
 ///
 /// # Infinite loops
 ///
@@ -3778,6 +4549,8 @@ impl EncoderResult {
 pub struct Encoder {
     encoding: &'static Encoding,
     variant: VariantEncoder,
+    #[cfg(feature = "alloc")]
+    unmappable: Option<Box<UnmappableHandler>>,
 }
 
 impl Encoder {
@@ -3785,6 +4558,8 @@ impl Encoder {
         Encoder {
             encoding: enc,
             variant: encoder,
+            #[cfg(feature = "alloc")]
+            unmappable: None,
         }
     }
 
@@ -3848,6 +4623,10 @@ impl Encoder {
                             dst: &mut [u8],
                             last: bool)
                             -> (CoderResult, usize, usize, bool) {
+        #[cfg(feature = "alloc")]
+        if self.unmappable.is_some() {
+            return policy::encode_from_utf8_with_policy(self, src, dst, last);
+        }
         let dst_len = dst.len();
         let effective_dst_len = if self.encoding().can_encode_everything() {
             dst_len
@@ -3910,6 +4689,7 @@ impl Encoder {
     /// methods collectively.
     ///
     /// Available to Rust only.
+    #[cfg(feature = "alloc")]
     pub fn encode_from_utf8_to_vec(&mut self,
                                    src: &str,
                                    dst: &mut Vec<u8>,
@@ -3946,6 +4726,7 @@ impl Encoder {
     /// methods collectively.
     ///
     /// Available to Rust only.
+    #[cfg(feature = "alloc")]
     pub fn encode_from_utf8_to_vec_without_replacement(&mut self,
                                                        src: &str,
                                                        dst: &mut Vec<u8>,
@@ -4011,6 +4792,10 @@ impl Encoder {
                              dst: &mut [u8],
                              last: bool)
                              -> (CoderResult, usize, usize, bool) {
+        #[cfg(feature = "alloc")]
+        if self.unmappable.is_some() {
+            return policy::encode_from_utf16_with_policy(self, src, dst, last);
+        }
         let dst_len = dst.len();
         let effective_dst_len = if self.encoding().can_encode_everything() {
             dst_len
@@ -4182,16 +4967,18 @@ fn checked_div(opt: Option<usize>, num: usize) -> Option<usize> {
     }
 }
 
+#[cfg(feature = "alloc")]
 #[inline(always)]
 fn checked_next_power_of_two(opt: Option<usize>) -> Option<usize> {
     opt.map(|n| n.next_power_of_two())
 }
 
+#[cfg(feature = "alloc")]
 #[inline(always)]
 fn checked_min(one: Option<usize>, other: Option<usize>) -> Option<usize> {
     if let Some(a) = one {
         if let Some(b) = other {
-            Some(::std::cmp::min(a, b))
+            Some(::core::cmp::min(a, b))
         } else {
             Some(a)
         }
@@ -4208,6 +4995,7 @@ mod test_labels_names;
 #[cfg(test)]
 mod tests {
     use super::*;
+    #[cfg(feature = "alloc")]
     use std::borrow::Cow;
 
     fn sniff_to_utf16(initial_encoding: &'static Encoding,
@@ -4426,6 +5214,105 @@ mod tests {
         assert_eq!(WINDOWS_1252.new_encoder().encoding(), WINDOWS_1252);
     }
 
+    #[test]
+    fn test_fallback_for_locale() {
+        for pair in data::LOCALE_FALLBACKS.windows(2) {
+            assert!(pair[0].0 < pair[1].0);
+        }
+        assert_eq!(Encoding::fallback_for_locale("ja"), SHIFT_JIS);
+        assert_eq!(Encoding::fallback_for_locale("ja-JP"), SHIFT_JIS);
+        assert_eq!(Encoding::fallback_for_locale("ru_RU.UTF-8"), WINDOWS_1251);
+        assert_eq!(Encoding::fallback_for_locale("zh-CN"), GBK);
+        assert_eq!(Encoding::fallback_for_locale("zh-TW"), BIG5);
+        assert_eq!(Encoding::fallback_for_locale("zh_HK"), BIG5);
+        assert_eq!(Encoding::fallback_for_locale("sr@latin"), WINDOWS_1251);
+        assert_eq!(Encoding::fallback_for_locale("en-US"), WINDOWS_1252);
+        assert_eq!(Encoding::fallback_for_locale("zh"), WINDOWS_1252);
+        assert_eq!(Encoding::fallback_for_locale(""), WINDOWS_1252);
+    }
+
+    #[test]
+    fn test_fallback_for_tld() {
+        for pair in data::TLD_FALLBACKS.windows(2) {
+            assert!(pair[0].0 < pair[1].0);
+        }
+        assert_eq!(Encoding::fallback_for_tld(b"ru"), Some(WINDOWS_1251));
+        assert_eq!(Encoding::fallback_for_tld(b"JP"), Some(SHIFT_JIS));
+        assert_eq!(Encoding::fallback_for_tld(b"example.co.kr"), Some(EUC_KR));
+        assert_eq!(Encoding::fallback_for_tld(b"example.tw."), Some(BIG5));
+        assert_eq!(Encoding::fallback_for_tld(b"xn--p1ai"), Some(WINDOWS_1251));
+        assert_eq!(Encoding::fallback_for_tld(b"de"), Some(WINDOWS_1252));
+        assert_eq!(Encoding::fallback_for_tld(b"com"), None);
+        assert_eq!(Encoding::fallback_for_tld(b"tv"), None);
+        assert_eq!(Encoding::fallback_for_tld(b"127.0.0.1"), None);
+        assert_eq!(Encoding::fallback_for_tld(b""), None);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_switch_encoding() {
+        let mut decoder = WINDOWS_1252.new_decoder();
+        let mut string = String::with_capacity(30);
+        let (result, read, _) = decoder.decode_to_string(b"<meta charset=", &mut string, false);
+        assert_eq!(result, CoderResult::InputEmpty);
+        assert_eq!(read, 14);
+        assert_eq!(decoder.switch_encoding(SHIFT_JIS), SwitchResult::Switched);
+        assert_eq!(decoder.encoding(), SHIFT_JIS);
+        let (result, _, _) = decoder.decode_to_string(b"\x82\xA0", &mut string, true);
+        assert_eq!(result, CoderResult::InputEmpty);
+        assert_eq!(string, "<meta charset=\u{3042}");
+
+        let mut decoder = WINDOWS_1252.new_decoder();
+        let mut string = String::with_capacity(30);
+        let (result, _, _) = decoder.decode_to_string(b"ab\xE9c", &mut string, false);
+        assert_eq!(result, CoderResult::InputEmpty);
+        assert_eq!(decoder.switch_encoding(UTF_8), SwitchResult::ReloadNeeded(2));
+        assert_eq!(decoder.encoding(), WINDOWS_1252);
+        assert_eq!(decoder.switch_encoding(WINDOWS_1252), SwitchResult::Switched);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_switch_encoding_long_ascii() {
+        let mut decoder = WINDOWS_1252.new_decoder();
+        let mut string = String::with_capacity(4096);
+        let (result, _, _) = decoder.decode_to_string(&[b'a'; 1000], &mut string, false);
+        assert_eq!(result, CoderResult::InputEmpty);
+        let (result, _, _) = decoder.decode_to_string(&[b'a'; 1000], &mut string, false);
+        assert_eq!(result, CoderResult::InputEmpty);
+        assert_eq!(decoder.switch_encoding(UTF_8), SwitchResult::Switched);
+        let (result, _, _) = decoder.decode_to_string(b"a\xC3\xA9", &mut string, false);
+        assert_eq!(result, CoderResult::InputEmpty);
+        assert_eq!(string.len(), 2003);
+        assert_eq!(decoder.switch_encoding(WINDOWS_1252), SwitchResult::ReloadNeeded(2001));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_switch_encoding_special() {
+        let mut decoder = WINDOWS_1252.new_decoder();
+        let mut string = String::with_capacity(30);
+        let (result, _, _) = decoder.decode_to_string(b"ab\x1B(Bc", &mut string, false);
+        assert_eq!(result, CoderResult::InputEmpty);
+        assert_eq!(decoder.switch_encoding(ISO_2022_JP), SwitchResult::ReloadNeeded(2));
+        assert_eq!(decoder.switch_encoding(UTF_16LE), SwitchResult::ReloadNeeded(0));
+        assert_eq!(decoder.switch_encoding(UTF_8), SwitchResult::Switched);
+
+        // BOM
+        let mut decoder = WINDOWS_1252.new_decoder();
+        let (result, _, _) = decoder.decode_to_string(b"\xEF\xBB\xBFab", &mut string, false);
+        assert_eq!(result, CoderResult::InputEmpty);
+        assert_eq!(decoder.switch_encoding(WINDOWS_1252), SwitchResult::ReloadNeeded(0));
+
+        // Nothing decoded yet
+        let mut decoder = WINDOWS_1252.new_decoder();
+        assert_eq!(decoder.switch_encoding(UTF_16BE), SwitchResult::Switched);
+        let mut string = String::with_capacity(30);
+        let (result, _, _) = decoder.decode_to_string(b"\x00a", &mut string, true);
+        assert_eq!(result, CoderResult::InputEmpty);
+        assert_eq!(string, "a");
+    }
+
     #[test]
     fn test_label_resolution() {
         assert_eq!(Encoding::for_label(b"utf-8"), Some(UTF_8));
@@ -4464,6 +5351,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_decode_valid_windows_1257_to_cow() {
         let (cow, encoding, had_errors) = WINDOWS_1257.decode(b"abc\x80\xE4");
         match cow {
@@ -4477,6 +5365,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_decode_invalid_windows_1257_to_cow() {
         let (cow, encoding, had_errors) = WINDOWS_1257.decode(b"abc\x80\xA1\xE4");
         match cow {
@@ -4490,6 +5379,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_decode_ascii_only_windows_1257_to_cow() {
         let (cow, encoding, had_errors) = WINDOWS_1257.decode(b"abc");
         match cow {
@@ -4503,6 +5393,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_decode_bomful_valid_utf8_as_windows_1257_to_cow() {
         let (cow, encoding, had_errors) = WINDOWS_1257.decode(b"\xEF\xBB\xBF\xE2\x82\xAC\xC3\xA4");
         match cow {
@@ -4516,6 +5407,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_decode_bomful_invalid_utf8_as_windows_1257_to_cow() {
         let (cow, encoding, had_errors) =
             WINDOWS_1257.decode(b"\xEF\xBB\xBF\xE2\x82\xAC\x80\xC3\xA4");
@@ -4530,6 +5422,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_decode_bomful_valid_utf8_as_utf_8_to_cow() {
         let (cow, encoding, had_errors) = UTF_8.decode(b"\xEF\xBB\xBF\xE2\x82\xAC\xC3\xA4");
         match cow {
@@ -4543,6 +5436,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_decode_bomful_invalid_utf8_as_utf_8_to_cow() {
         let (cow, encoding, had_errors) = UTF_8.decode(b"\xEF\xBB\xBF\xE2\x82\xAC\x80\xC3\xA4");
         match cow {
@@ -4556,6 +5450,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_decode_bomful_valid_utf8_as_utf_8_to_cow_with_bom_removal() {
         let (cow, had_errors) = UTF_8.decode_with_bom_removal(b"\xEF\xBB\xBF\xE2\x82\xAC\xC3\xA4");
         match cow {
@@ -4568,6 +5463,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_decode_bomful_valid_utf8_as_windows_1257_to_cow_with_bom_removal() {
         let (cow, had_errors) = WINDOWS_1257
             .decode_with_bom_removal(b"\xEF\xBB\xBF\xE2\x82\xAC\xC3\xA4");
@@ -4585,6 +5481,7 @@ mod tests {
 
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_decode_valid_windows_1257_to_cow_with_bom_removal() {
         let (cow, had_errors) = WINDOWS_1257.decode_with_bom_removal(b"abc\x80\xE4");
         match cow {
@@ -4597,6 +5494,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_decode_invalid_windows_1257_to_cow_with_bom_removal() {
         let (cow, had_errors) = WINDOWS_1257.decode_with_bom_removal(b"abc\x80\xA1\xE4");
         match cow {
@@ -4609,6 +5507,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_decode_ascii_only_windows_1257_to_cow_with_bom_removal() {
         let (cow, had_errors) = WINDOWS_1257.decode_with_bom_removal(b"abc");
         match cow {
@@ -4621,6 +5520,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_decode_bomful_valid_utf8_to_cow_without_bom_handling() {
         let (cow, had_errors) =
             UTF_8.decode_without_bom_handling(b"\xEF\xBB\xBF\xE2\x82\xAC\xC3\xA4");
@@ -4634,6 +5534,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_decode_bomful_invalid_utf8_to_cow_without_bom_handling() {
         let (cow, had_errors) =
             UTF_8.decode_without_bom_handling(b"\xEF\xBB\xBF\xE2\x82\xAC\x80\xC3\xA4");
@@ -4647,6 +5548,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_decode_valid_windows_1257_to_cow_without_bom_handling() {
         let (cow, had_errors) = WINDOWS_1257.decode_without_bom_handling(b"abc\x80\xE4");
         match cow {
@@ -4659,6 +5561,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_decode_invalid_windows_1257_to_cow_without_bom_handling() {
         let (cow, had_errors) = WINDOWS_1257.decode_without_bom_handling(b"abc\x80\xA1\xE4");
         match cow {
@@ -4671,6 +5574,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_decode_ascii_only_windows_1257_to_cow_without_bom_handling() {
         let (cow, had_errors) = WINDOWS_1257.decode_without_bom_handling(b"abc");
         match cow {
@@ -4683,6 +5587,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_decode_bomful_valid_utf8_to_cow_without_bom_handling_and_without_replacement() {
         match UTF_8.decode_without_bom_handling_and_without_replacement(
             b"\xEF\xBB\xBF\xE2\x82\xAC\xC3\xA4",
@@ -4700,6 +5605,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_decode_bomful_invalid_utf8_to_cow_without_bom_handling_and_without_replacement() {
         assert!(
             UTF_8
@@ -4711,6 +5617,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_decode_valid_windows_1257_to_cow_without_bom_handling_and_without_replacement() {
         match WINDOWS_1257.decode_without_bom_handling_and_without_replacement(b"abc\x80\xE4") {
             Some(cow) => {
@@ -4726,6 +5633,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_decode_invalid_windows_1257_to_cow_without_bom_handling_and_without_replacement() {
         assert!(
             WINDOWS_1257
@@ -4735,6 +5643,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_decode_ascii_only_windows_1257_to_cow_without_bom_handling_and_without_replacement() {
         match WINDOWS_1257.decode_without_bom_handling_and_without_replacement(b"abc") {
             Some(cow) => {
@@ -4750,6 +5659,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_encode_ascii_only_windows_1257_to_cow() {
         let (cow, encoding, had_errors) = WINDOWS_1257.encode("abc");
         match cow {
@@ -4763,6 +5673,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_encode_valid_windows_1257_to_cow() {
         let (cow, encoding, had_errors) = WINDOWS_1257.encode("abc\u{20AC}\u{00E4}");
         match cow {
@@ -4775,6 +5686,126 @@ mod tests {
         assert!(!had_errors);
     }
 
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_decode_bomful_valid_utf8_as_windows_1257_to_utf16() {
+        let (vec, encoding, had_errors) =
+            WINDOWS_1257.decode_to_utf16(b"\xEF\xBB\xBF\xE2\x82\xAC\xC3\xA4");
+        assert_eq!(&vec[..], &[0x20ACu16, 0x00E4u16][..]);
+        assert_eq!(encoding, UTF_8);
+        assert!(!had_errors);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_decode_invalid_windows_1257_to_utf16() {
+        let (vec, encoding, had_errors) = WINDOWS_1257.decode_to_utf16(b"a\x80\xA1");
+        assert_eq!(&vec[..], &[0x0061u16, 0x20ACu16, 0xFFFDu16][..]);
+        assert_eq!(encoding, WINDOWS_1257);
+        assert!(had_errors);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_decode_bomful_utf16le_to_utf16_with_bom_removal() {
+        let (vec, had_errors) = UTF_16LE.decode_to_utf16_with_bom_removal(b"\xFF\xFEa\x00");
+        assert_eq!(&vec[..], &[0x0061u16][..]);
+        assert!(!had_errors);
+        let (vec, had_errors) = UTF_16LE.decode_to_utf16_with_bom_removal(b"\xFE\xFFa\x00");
+        assert_eq!(&vec[..], &[0xFFFEu16, 0x0061u16][..]);
+        assert!(!had_errors);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_decode_bomful_valid_utf8_to_utf16_without_bom_handling() {
+        let (vec, had_errors) = UTF_8.decode_to_utf16_without_bom_handling(b"\xEF\xBB\xBFa");
+        assert_eq!(&vec[..], &[0xFEFFu16, 0x0061u16][..]);
+        assert!(!had_errors);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_decode_to_utf16_without_bom_handling_and_without_replacement() {
+        assert_eq!(
+            SHIFT_JIS.decode_to_utf16_without_bom_handling_and_without_replacement(b"a\x83n"),
+            Some(vec![0x0061u16, 0x30CFu16])
+        );
+        assert_eq!(
+            SHIFT_JIS.decode_to_utf16_without_bom_handling_and_without_replacement(b"a\x83"),
+            None
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_encode_from_utf16_windows_1257() {
+        let (cow, encoding, had_errors) =
+            WINDOWS_1257.encode_from_utf16(&[0x0061u16, 0x20ACu16, 0x00E4u16]);
+        assert_eq!(&cow[..], b"a\x80\xE4");
+        assert_eq!(encoding, WINDOWS_1257);
+        assert!(!had_errors);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_encode_from_utf16_unmappable_and_unpaired_surrogate() {
+        let (cow, encoding, had_errors) =
+            WINDOWS_1257.encode_from_utf16(&[0xD83Du16, 0xDCA9u16, 0xDC00u16]);
+        assert_eq!(&cow[..], b"&#128169;&#65533;");
+        assert_eq!(encoding, WINDOWS_1257);
+        assert!(had_errors);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_encode_from_utf16_utf_16le() {
+        let (cow, encoding, had_errors) = UTF_16LE.encode_from_utf16(&[0x20ACu16]);
+        assert_eq!(&cow[..], b"\xE2\x82\xAC");
+        assert_eq!(encoding, UTF_8);
+        assert!(!had_errors);
+    }
+
+    #[test]
+    fn test_slice_conversions_without_alloc() {
+        // Only uses the methods that are available without the `alloc`
+        // feature.
+        let mut decoder = SHIFT_JIS.new_decoder();
+        let mut utf8 = [0u8; 16];
+        let (result, read, written, had_errors) =
+            decoder.decode_to_utf8(b"a\x82\xA0\xFF", &mut utf8[..], true);
+        assert_eq!(result, CoderResult::InputEmpty);
+        assert_eq!(read, 4);
+        assert_eq!(&utf8[..written], "a\u{3042}\u{FFFD}".as_bytes());
+        assert!(had_errors);
+
+        let mut decoder = UTF_8.new_decoder();
+        let mut utf16 = [0u16; 8];
+        let (result, read, written, had_errors) =
+            decoder.decode_to_utf16(b"\xEF\xBB\xBFa\xE2\x82\xAC", &mut utf16[..], true);
+        assert_eq!(result, CoderResult::InputEmpty);
+        assert_eq!(read, 7);
+        assert_eq!(&utf16[..written], &[0x61u16, 0x20AC][..]);
+        assert!(!had_errors);
+
+        let mut encoder = SHIFT_JIS.new_encoder();
+        let mut bytes = [0u8; 32];
+        let (result, read, written, had_errors) =
+            encoder.encode_from_utf8("a\u{3042}\u{1F4A9}", &mut bytes[..], true);
+        assert_eq!(result, CoderResult::InputEmpty);
+        assert_eq!(read, 8);
+        assert_eq!(&bytes[..written], b"a\x82\xA0&#128169;");
+        assert!(had_errors);
+
+        let mut encoder = WINDOWS_1252.new_encoder();
+        let (result, read, written, had_errors) =
+            encoder.encode_from_utf16(&[0x61u16, 0x20AC, 0xD800], &mut bytes[..], true);
+        assert_eq!(result, CoderResult::InputEmpty);
+        assert_eq!(read, 3);
+        assert_eq!(&bytes[..written], b"a\x80&#65533;");
+        assert!(had_errors);
+    }
+
     #[test]
     fn test_utf16_space_with_one_bom_byte() {
         let mut decoder = UTF_16LE.new_decoder();
@@ -1238,6 +1238,7 @@ macro_rules! public_decode_function{
      $decode_to_utf_after_one_potential_bom_byte:ident,
      $decode_to_utf_after_two_potential_bom_bytes:ident,
      $decode_to_utf_checking_end_with_offset:ident,
+     $decode_to_utf_untracked:ident,
      $code_unit:ty) => (
     $(#[$meta])*
     pub fn $decode_to_utf(&mut self,
@@ -1245,6 +1246,16 @@ macro_rules! public_decode_function{
                            dst: &mut [$code_unit],
                            last: bool)
                            -> (DecoderResult, usize, usize) {
+        let (result, read, written) = self.$decode_to_utf_untracked(src, dst, last);
+        self.track_ascii(&src[..read]);
+        (result, read, written)
+    }
+
+    fn $decode_to_utf_untracked(&mut self,
+                                src: &[u8],
+                                dst: &mut [$code_unit],
+                                last: bool)
+                                -> (DecoderResult, usize, usize) {
         let mut offset = 0usize;
         loop {
             match self.life_cycle {
@@ -0,0 +1,839 @@
+// Copyright 2017 Mozilla Foundation. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Functions for converting between different in-RAM representations of
+//! text without going through a `Decoder` or an `Encoder` and for quickly
+//! checking if the Unicode Bidirectional Algorithm can be avoided.
+//!
+//! By using slices for output, the functions here seek to enable by-register
+//! (ALU register or SIMD register as available) operations in order to
+//! outperform iterator-based conversions available in the Rust standard
+//! library. The functions share their ASCII kernels with the decoders and
+//! encoders of this crate.
+//!
+//! _Note:_ "Latin1" in this module refers to the Unicode range from U+0000 to
+//! U+00FF, inclusive, and does not refer to the windows-1252 range. This
+//! in-memory encoding is sometimes used as a storage optimization of text
+//! when UTF-16 indexing and length semantics are exposed.
+//!
+//! Available to Rust only.
+
+use super::*;
+use ascii::ascii_to_ascii;
+use ascii::ascii_to_basic_latin;
+use ascii::basic_latin_to_ascii;
+use ascii::validate_ascii;
+use ascii::validate_basic_latin;
+use ascii::validate_utf16_latin1;
+use utf_8::convert_utf8_to_utf16_up_to_invalid;
+use utf_8::Utf8Decoder;
+use utf_8::Utf8Encoder;
+
+/// Zeroes out the bytes after `written` that could make `bytes` as a whole
+/// invalid UTF-8: stride garbage and the continuation bytes of a character
+/// whose beginning was overwritten.
+fn zero_trailing_garbage(bytes: &mut [u8], written: usize) {
+    let len = bytes.len();
+    let mut trail = written;
+    let max = ::core::cmp::min(len, trail + ascii::STRIDE_SIZE);
+    while trail < max {
+        bytes[trail] = 0;
+        trail += 1;
+    }
+    while trail < len && ((bytes[trail] & 0xC0) == 0x80) {
+        bytes[trail] = 0;
+        trail += 1;
+    }
+}
+
+/// Checks whether the buffer is all-ASCII.
+///
+/// May read the entire buffer even if it isn't all-ASCII. (I.e. the function
+/// is not guaranteed to fail fast.)
+pub fn is_ascii(buffer: &[u8]) -> bool {
+    validate_ascii(buffer).is_none()
+}
+
+/// Checks whether the buffer is all-Basic Latin (i.e. UTF-16 representing
+/// only ASCII characters).
+///
+/// May read the entire buffer even if it isn't all-ASCII. (I.e. the function
+/// is not guaranteed to fail fast.)
+pub fn is_basic_latin(buffer: &[u16]) -> bool {
+    validate_basic_latin(buffer).is_none()
+}
+
+/// Checks whether the buffer is valid UTF-8 representing only code points
+/// less than or equal to U+00FF.
+///
+/// Fails fast. (I.e. returns before having read the whole buffer if UTF-8
+/// invalidity or code points above U+00FF are discovered.)
+pub fn is_utf8_latin1(buffer: &[u8]) -> bool {
+    utf8_latin1_up_to(buffer) == buffer.len()
+}
+
+/// Returns the index of the first byte that is not part of valid UTF-8
+/// representing a code point less than or equal to U+00FF or the length of
+/// the buffer if there is no such byte.
+fn utf8_latin1_up_to(buffer: &[u8]) -> usize {
+    let mut offset = 0usize;
+    loop {
+        match validate_ascii(&buffer[offset..]) {
+            None => {
+                return buffer.len();
+            }
+            Some((lead, num_ascii)) => {
+                offset += num_ascii;
+                if lead != 0xC2 && lead != 0xC3 {
+                    return offset;
+                }
+                match buffer.get(offset + 1) {
+                    Some(&trail) if (trail & 0xC0) == 0x80 => {
+                        offset += 2;
+                    }
+                    _ => {
+                        return offset;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Checks whether the buffer represents only code points less than or equal
+/// to U+00FF.
+///
+/// Fails fast. (I.e. returns before having read the whole buffer if code
+/// points above U+00FF are discovered.)
+pub fn is_str_latin1(buffer: &str) -> bool {
+    let mut bytes = buffer.as_bytes();
+    loop {
+        match validate_ascii(bytes) {
+            None => {
+                return true;
+            }
+            Some((lead, offset)) => {
+                // The input is valid UTF-8, so the lead byte alone tells
+                // whether the character is two bytes long and below U+0100.
+                if lead > 0xC3 {
+                    return false;
+                }
+                bytes = &bytes[offset + 2..];
+            }
+        }
+    }
+}
+
+/// Checks whether the buffer represents only code point less than or equal
+/// to U+00FF.
+///
+/// May read the entire buffer even if it isn't all-Latin1. (I.e. the function
+/// is not guaranteed to fail fast.)
+pub fn is_utf16_latin1(buffer: &[u16]) -> bool {
+    validate_utf16_latin1(buffer).is_none()
+}
+
+/// The result of `check_utf8_for_latin1_and_bidi()` and
+/// `check_utf16_for_latin1_and_bidi()`.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Latin1Bidi {
+    /// The input contains only code points from U+0000 to U+00FF.
+    Latin1,
+    /// The input contains code points above U+00FF but none that would
+    /// require running the Unicode Bidirectional Algorithm.
+    LeftToRight,
+    /// The input contains right-to-left characters or controls.
+    Bidi,
+}
+
+/// Whether the code point is in a right-to-left block or is a right-to-left
+/// control. This is the same as `is_char_bidi()` but doesn't require the
+/// argument to be a scalar value.
+fn is_code_point_bidi(code_point: u32) -> bool {
+    match code_point {
+        // Hebrew, Arabic, Syriac, Thaana, NKo, Samaritan, Mandaic and
+        // Arabic Extended-A
+        0x0590..=0x08FF |
+        // RLM, RLE, RLO and RLI
+        0x200F | 0x202B | 0x202E | 0x2067 |
+        // Hebrew and Arabic presentation forms
+        0xFB1D..=0xFDFF | 0xFE70..=0xFEFE |
+        // Right-to-left scripts in the Supplementary Multilingual Plane
+        0x10800..=0x10FFF | 0x1E800..=0x1EFFF => true,
+        _ => false,
+    }
+}
+
+/// Checks whether a scalar value triggers right-to-left processing.
+///
+/// The check is done on a Unicode block basis without regard to assigned
+/// vs. unassigned code points in the block. Additionally, the RIGHT-TO-LEFT
+/// MARK and the right-to-left embedding, override and isolate controls are
+/// considered right-to-left.
+pub fn is_char_bidi(c: char) -> bool {
+    is_code_point_bidi(c as u32)
+}
+
+/// Checks whether a UTF-16 code unit triggers right-to-left processing.
+///
+/// The check is done on a Unicode block basis without regard to assigned
+/// vs. unassigned code points in the block. Additionally, the RIGHT-TO-LEFT
+/// MARK and the right-to-left embedding, override and isolate controls are
+/// considered right-to-left. High surrogates that start a code point in a
+/// right-to-left block of the Supplementary Multilingual Plane are
+/// considered right-to-left.
+pub fn is_utf16_code_unit_bidi(u: u16) -> bool {
+    match u {
+        // U+10800...U+10FFF and U+1E800...U+1EFFF
+        0xD802..=0xD803 | 0xD83A..=0xD83B => true,
+        _ => is_code_point_bidi(u as u32),
+    }
+}
+
+/// Decodes the UTF-8 sequence that `bytes` starts with and returns the code
+/// point and the length of the sequence or U+FFFD and one if the sequence is
+/// malformed.
+fn decode_utf8_sequence(bytes: &[u8]) -> (u32, usize) {
+    let lead = bytes[0];
+    let (length, mut code_point, lower, upper) = match lead {
+        0xC2..=0xDF => (2, (lead & 0x1F) as u32, 0x80, 0xBF),
+        0xE0 => (3, 0, 0xA0, 0xBF),
+        0xE1..=0xEC | 0xEE..=0xEF => (3, (lead & 0xF) as u32, 0x80, 0xBF),
+        0xED => (3, 0xD, 0x80, 0x9F),
+        0xF0 => (4, 0, 0x90, 0xBF),
+        0xF1..=0xF3 => (4, (lead & 0x7) as u32, 0x80, 0xBF),
+        0xF4 => (4, 4, 0x80, 0x8F),
+        _ => {
+            return (0xFFFD, 1);
+        }
+    };
+    if bytes.len() < length {
+        return (0xFFFD, 1);
+    }
+    let second = bytes[1];
+    if second < lower || second > upper {
+        return (0xFFFD, 1);
+    }
+    code_point = (code_point << 6) | (second & 0x3F) as u32;
+    for &trail in &bytes[2..length] {
+        if (trail & 0xC0) != 0x80 {
+            return (0xFFFD, 1);
+        }
+        code_point = (code_point << 6) | (trail & 0x3F) as u32;
+    }
+    (code_point, length)
+}
+
+/// Checks whether a potentially invalid UTF-8 buffer contains code points
+/// that trigger right-to-left processing.
+///
+/// The check is done on a Unicode block basis without regard to assigned
+/// vs. unassigned code points in the block. Additionally, the RIGHT-TO-LEFT
+/// MARK and the right-to-left embedding, override and isolate controls are
+/// considered right-to-left. Malformed sequences are not right-to-left.
+///
+/// ASCII is skipped a stride at a time.
+///
+/// Returns `true` if the input contains an RTL character or an RTL control.
+pub fn is_utf8_bidi(buffer: &[u8]) -> bool {
+    let mut bytes = buffer;
+    loop {
+        let offset = match validate_ascii(bytes) {
+            None => {
+                return false;
+            }
+            Some((_, offset)) => offset,
+        };
+        let (code_point, length) = decode_utf8_sequence(&bytes[offset..]);
+        if is_code_point_bidi(code_point) {
+            return true;
+        }
+        bytes = &bytes[offset + length..];
+    }
+}
+
+/// Checks whether a potentially invalid UTF-16 buffer contains code points
+/// that trigger right-to-left processing.
+///
+/// The check is done on a Unicode block basis without regard to assigned
+/// vs. unassigned code points in the block. Additionally, the RIGHT-TO-LEFT
+/// MARK and the right-to-left embedding, override and isolate controls are
+/// considered right-to-left. High surrogates that start a code point in a
+/// right-to-left block of the Supplementary Multilingual Plane are
+/// considered right-to-left even when unpaired.
+///
+/// Latin1 is skipped a stride at a time.
+///
+/// Returns `true` if the input contains an RTL character or an RTL control.
+pub fn is_utf16_bidi(buffer: &[u16]) -> bool {
+    let mut units = buffer;
+    loop {
+        let offset = match validate_utf16_latin1(units) {
+            None => {
+                return false;
+            }
+            Some((_, offset)) => offset,
+        };
+        units = &units[offset..];
+        let mut i = 0usize;
+        while i < units.len() {
+            let u = units[i];
+            if u <= 0xFF {
+                break;
+            }
+            if is_utf16_code_unit_bidi(u) {
+                return true;
+            }
+            i += 1;
+        }
+        units = &units[i..];
+    }
+}
+
+/// Checks whether a valid UTF-8 buffer is Latin1 and, if not, whether it
+/// requires running the Unicode Bidirectional Algorithm.
+///
+/// Potentially invalid input is checked the same way as by
+/// `is_utf8_latin1()` and `is_utf8_bidi()`.
+pub fn check_utf8_for_latin1_and_bidi(buffer: &[u8]) -> Latin1Bidi {
+    let up_to = utf8_latin1_up_to(buffer);
+    if up_to == buffer.len() {
+        return Latin1Bidi::Latin1;
+    }
+    if is_utf8_bidi(&buffer[up_to..]) {
+        return Latin1Bidi::Bidi;
+    }
+    Latin1Bidi::LeftToRight
+}
+
+/// Checks whether a UTF-16 buffer is Latin1 and, if not, whether it
+/// requires running the Unicode Bidirectional Algorithm.
+///
+/// The buffer is checked the same way as by `is_utf16_latin1()` and
+/// `is_utf16_bidi()`.
+pub fn check_utf16_for_latin1_and_bidi(buffer: &[u16]) -> Latin1Bidi {
+    match validate_utf16_latin1(buffer) {
+        None => Latin1Bidi::Latin1,
+        Some((_, offset)) => {
+            if is_utf16_bidi(&buffer[offset..]) {
+                Latin1Bidi::Bidi
+            } else {
+                Latin1Bidi::LeftToRight
+            }
+        }
+    }
+}
+
+/// Converts bytes whose unsigned value is interpreted as Unicode code point
+/// (i.e. U+0000 to U+00FF, inclusive) to UTF-16.
+///
+/// The length of the destination buffer must be at least the length of the
+/// source buffer.
+///
+/// # Panics
+///
+/// Panics if the destination buffer is shorter than stated above.
+pub fn convert_latin1_to_utf16(src: &[u8], dst: &mut [u16]) {
+    assert!(dst.len() >= src.len(), "Destination must not be shorter than the source.");
+    let mut offset = 0usize;
+    loop {
+        let src_remaining = &src[offset..];
+        let dst_remaining = &mut dst[offset..];
+        match unsafe {
+                  ascii_to_basic_latin(
+                src_remaining.as_ptr(),
+                dst_remaining.as_mut_ptr(),
+                src_remaining.len(),
+            )
+              } {
+            None => {
+                return;
+            }
+            Some((non_ascii, consumed)) => {
+                offset += consumed;
+                dst[offset] = non_ascii as u16;
+                offset += 1;
+            }
+        }
+    }
+}
+
+/// Converts bytes whose unsigned value is interpreted as Unicode code point
+/// (i.e. U+0000 to U+00FF, inclusive) to UTF-8.
+///
+/// The length of the destination buffer must be at least the length of the
+/// source buffer times two.
+///
+/// Returns the number of bytes written.
+///
+/// # Panics
+///
+/// Panics if the destination buffer is shorter than stated above.
+pub fn convert_latin1_to_utf8(src: &[u8], dst: &mut [u8]) -> usize {
+    assert!(dst.len() / 2 >= src.len(),
+            "Destination must not be shorter than the source times two.");
+    let mut read = 0usize;
+    let mut written = 0usize;
+    loop {
+        // `written` is at most twice `read`, so the rest of `dst` is at least
+        // as long as the rest of `src`.
+        let src_remaining = &src[read..];
+        let dst_remaining = &mut dst[written..];
+        let length = src_remaining.len();
+        match unsafe {
+                  ascii_to_ascii(src_remaining.as_ptr(), dst_remaining.as_mut_ptr(), length)
+              } {
+            None => {
+                return written + length;
+            }
+            Some((non_ascii, consumed)) => {
+                read += consumed;
+                written += consumed;
+                dst[written] = (non_ascii >> 6) | 0xC0u8;
+                dst[written + 1] = (non_ascii & 0x3F) | 0x80u8;
+                read += 1;
+                written += 2;
+            }
+        }
+    }
+}
+
+/// Converts bytes whose unsigned value is interpreted as Unicode code point
+/// (i.e. U+0000 to U+00FF, inclusive) to UTF-8 with type system signaling
+/// of UTF-8 validity.
+///
+/// The length of the destination buffer must be at least the length of the
+/// source buffer times two.
+///
+/// Returns the number of bytes written. The bytes after that in `dst` may
+/// have been zeroed out.
+///
+/// # Panics
+///
+/// Panics if the destination buffer is shorter than stated above.
+pub fn convert_latin1_to_str(src: &[u8], dst: &mut str) -> usize {
+    let bytes = unsafe { dst.as_bytes_mut() };
+    let written = convert_latin1_to_utf8(src, bytes);
+    zero_trailing_garbage(bytes, written);
+    written
+}
+
+/// If the input is valid UTF-16 representing only Unicode code points from
+/// U+0000 to U+00FF, inclusive, converts the input into output that
+/// represents the value of each code point as the unsigned byte value of
+/// each output byte.
+///
+/// If the input does not fulfill the condition stated above, the output is
+/// garbage (each code unit is truncated to eight bits), but this function
+/// does not panic because of it. Use `is_utf16_latin1()` to check first if
+/// necessary.
+///
+/// The length of the destination buffer must be at least the length of the
+/// source buffer.
+///
+/// # Panics
+///
+/// Panics if the destination buffer is shorter than stated above.
+pub fn convert_utf16_to_latin1_lossy(src: &[u16], dst: &mut [u8]) {
+    assert!(dst.len() >= src.len(), "Destination must not be shorter than the source.");
+    let mut offset = 0usize;
+    loop {
+        let src_remaining = &src[offset..];
+        let dst_remaining = &mut dst[offset..];
+        match unsafe {
+                  basic_latin_to_ascii(
+                src_remaining.as_ptr(),
+                dst_remaining.as_mut_ptr(),
+                src_remaining.len(),
+            )
+              } {
+            None => {
+                return;
+            }
+            Some((non_ascii, consumed)) => {
+                offset += consumed;
+                dst[offset] = non_ascii as u8;
+                offset += 1;
+            }
+        }
+    }
+}
+
+/// If the input is valid UTF-8 representing only Unicode code points from
+/// U+0000 to U+00FF, inclusive, converts the input into output that
+/// represents the value of each code point as the unsigned byte value of
+/// each output byte.
+///
+/// If the input does not fulfill the condition stated above, the output is
+/// garbage, but this function does not panic because of it. Use
+/// `is_utf8_latin1()` to check first if necessary.
+///
+/// The length of the destination buffer must be at least the length of the
+/// source buffer.
+///
+/// Returns the number of bytes written.
+///
+/// # Panics
+///
+/// Panics if the destination buffer is shorter than stated above.
+pub fn convert_utf8_to_latin1_lossy(src: &[u8], dst: &mut [u8]) -> usize {
+    assert!(dst.len() >= src.len(), "Destination must not be shorter than the source.");
+    let mut read = 0usize;
+    let mut written = 0usize;
+    loop {
+        // `written` is at most `read`, so the rest of `dst` is at least as
+        // long as the rest of `src`.
+        let src_remaining = &src[read..];
+        let dst_remaining = &mut dst[written..];
+        let length = src_remaining.len();
+        match unsafe {
+                  ascii_to_ascii(src_remaining.as_ptr(), dst_remaining.as_mut_ptr(), length)
+              } {
+            None => {
+                return written + length;
+            }
+            Some((non_ascii, consumed)) => {
+                read += consumed;
+                written += consumed;
+                if read + 1 == src.len() {
+                    // A lone lead byte at the end.
+                    return written;
+                }
+                let trail = src[read + 1];
+                dst[written] = ((non_ascii & 0x3) << 6) | (trail & 0x3F);
+                read += 2;
+                written += 1;
+            }
+        }
+    }
+}
+
+/// Converts potentially-invalid UTF-16 to valid UTF-8 with errors replaced
+/// with the REPLACEMENT CHARACTER.
+///
+/// The length of the destination buffer must be at least the length of the
+/// source buffer times three _plus one_.
+///
+/// Returns the number of bytes written.
+///
+/// # Panics
+///
+/// Panics if the destination buffer is shorter than stated above.
+pub fn convert_utf16_to_utf8(src: &[u16], dst: &mut [u8]) -> usize {
+    match checked_add(1, src.len().checked_mul(3)) {
+        Some(needed) if dst.len() >= needed => {}
+        _ => panic!("Destination must not be shorter than the source times three plus one."),
+    }
+    let mut encoder = Utf8Encoder;
+    let (result, _, written) = encoder.encode_from_utf16_raw(src, dst, true);
+    debug_assert_eq!(result, EncoderResult::InputEmpty);
+    written
+}
+
+/// Converts potentially-invalid UTF-16 to valid UTF-8 with errors replaced
+/// with the REPLACEMENT CHARACTER with type system signaling of UTF-8
+/// validity.
+///
+/// The length of the destination buffer must be at least the length of the
+/// source buffer times three _plus one_.
+///
+/// Returns the number of bytes written. The bytes after that in `dst` may
+/// have been zeroed out.
+///
+/// # Panics
+///
+/// Panics if the destination buffer is shorter than stated above.
+pub fn convert_utf16_to_str(src: &[u16], dst: &mut str) -> usize {
+    let bytes = unsafe { dst.as_bytes_mut() };
+    let written = convert_utf16_to_utf8(src, bytes);
+    zero_trailing_garbage(bytes, written);
+    written
+}
+
+/// Converts potentially-invalid UTF-8 to valid UTF-16 with errors replaced
+/// with the REPLACEMENT CHARACTER.
+///
+/// The length of the destination buffer must be at least the length of the
+/// source buffer _plus one_.
+///
+/// Returns the number of `u16`s written.
+///
+/// # Panics
+///
+/// Panics if the destination buffer is shorter than stated above.
+pub fn convert_utf8_to_utf16(src: &[u8], dst: &mut [u16]) -> usize {
+    match src.len().checked_add(1) {
+        Some(needed) if dst.len() >= needed => {}
+        _ => panic!("Destination must not be shorter than the source plus one."),
+    }
+    let mut decoder = Utf8Decoder::new_inner();
+    let mut total_read = 0usize;
+    let mut total_written = 0usize;
+    loop {
+        let (result, read, written) =
+            decoder.decode_to_utf16_raw(&src[total_read..], &mut dst[total_written..], true);
+        total_read += read;
+        total_written += written;
+        match result {
+            DecoderResult::InputEmpty => {
+                return total_written;
+            }
+            DecoderResult::OutputFull => {
+                unreachable!("The length check at the top should have caught this.");
+            }
+            DecoderResult::Malformed(_, _) => {
+                // There is always space for the REPLACEMENT CHARACTER,
+                // because otherwise the result would have been OutputFull.
+                dst[total_written] = 0xFFFD;
+                total_written += 1;
+            }
+        }
+    }
+}
+
+/// Converts valid UTF-8 to valid UTF-16.
+///
+/// The length of the destination buffer must be at least the length of the
+/// source buffer.
+///
+/// Returns the number of `u16`s written.
+///
+/// # Panics
+///
+/// Panics if the destination buffer is shorter than stated above.
+pub fn convert_str_to_utf16(src: &str, dst: &mut [u16]) -> usize {
+    assert!(dst.len() >= src.len(), "Destination must not be shorter than the source.");
+    let bytes = src.as_bytes();
+    let (read, written) = convert_utf8_to_utf16_up_to_invalid(bytes, dst);
+    debug_assert_eq!(read, bytes.len());
+    written
+}
+
+/// Returns the index of the first unpaired surrogate or, if the input is
+/// valid UTF-16 in its entirety, the length of the input.
+///
+/// Basic Latin is skipped a stride at a time, like when encoding UTF-16 into
+/// UTF-8.
+pub fn utf16_valid_up_to(buffer: &[u16]) -> usize {
+    let mut offset = 0usize;
+    loop {
+        match validate_basic_latin(&buffer[offset..]) {
+            None => {
+                return buffer.len();
+            }
+            Some((_, num_ascii)) => {
+                offset += num_ascii;
+            }
+        }
+        // Go unit by unit until back in Basic Latin.
+        while offset < buffer.len() {
+            let unit = buffer[offset];
+            if unit < 0x80 {
+                break;
+            }
+            let unit_minus_surrogate_start = unit.wrapping_sub(0xD800);
+            if unit_minus_surrogate_start > (0xDFFF - 0xD800) {
+                offset += 1;
+                continue;
+            }
+            if unit_minus_surrogate_start <= (0xDBFF - 0xD800) {
+                // high surrogate
+                if let Some(&second) = buffer.get(offset + 1) {
+                    if second.wrapping_sub(0xDC00) <= (0xDFFF - 0xDC00) {
+                        offset += 2;
+                        continue;
+                    }
+                }
+            }
+            return offset;
+        }
+    }
+}
+
+/// Replaces unpaired surrogates in the input with the REPLACEMENT CHARACTER.
+///
+/// After calling this function, the buffer is valid UTF-16 and can be
+/// converted e.g. with `String::from_utf16()` without failure.
+pub fn ensure_utf16_validity(buffer: &mut [u16]) {
+    let mut offset = 0usize;
+    loop {
+        offset += utf16_valid_up_to(&buffer[offset..]);
+        if offset == buffer.len() {
+            return;
+        }
+        buffer[offset] = 0xFFFD;
+        offset += 1;
+    }
+}
+
+// Any copyright to the test code below this comment is dedicated to the
+// Public Domain. http://creativecommons.org/publicdomain/zero/1.0/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_ascii_and_latin1() {
+        assert!(is_ascii(b"abcdefghijklmnopqrstuvwxyz0123456789"));
+        assert!(!is_ascii(b"abcdefghijklmnopqrstuvwxyz\xE9"));
+        assert!(is_basic_latin(&[0x61u16; 40][..]));
+        assert!(!is_basic_latin(&[0x61u16, 0xE9]));
+        assert!(is_utf8_latin1(b"abc\xC3\xA9\xC2\x80"));
+        assert!(!is_utf8_latin1(b"abc\xC3"));
+        assert!(!is_utf8_latin1(b"abc\xC3a"));
+        assert!(!is_utf8_latin1(b"\xC4\x80"));
+        assert!(is_str_latin1("abc\u{E9}\u{FF}"));
+        assert!(!is_str_latin1("abc\u{100}"));
+        assert!(is_utf16_latin1(&[0x61u16, 0xFF]));
+        assert!(!is_utf16_latin1(&[0x61u16, 0x100]));
+    }
+
+    #[test]
+    fn test_convert_latin1() {
+        let latin1: Vec<u8> = (0u32..256).map(|b| b as u8).collect();
+        let expected: String = (0u32..256)
+            .map(|b| ::core::char::from_u32(b).unwrap())
+            .collect();
+
+        let mut utf16 = vec![0u16; 256];
+        convert_latin1_to_utf16(&latin1, &mut utf16);
+        assert_eq!(String::from_utf16(&utf16).unwrap(), expected);
+
+        let mut utf8 = vec![0u8; 512];
+        let written = convert_latin1_to_utf8(&latin1, &mut utf8);
+        assert_eq!(&utf8[..written], expected.as_bytes());
+
+        let mut string = "\u{10000}".repeat(128);
+        let written = convert_latin1_to_str(b"ab\xE9", &mut string);
+        assert_eq!(&string[..written], "ab\u{E9}");
+
+        let mut back = vec![0u8; 256];
+        convert_utf16_to_latin1_lossy(&utf16, &mut back);
+        assert_eq!(back, latin1);
+
+        let mut back = vec![0u8; 384];
+        let written = convert_utf8_to_latin1_lossy(expected.as_bytes(), &mut back);
+        assert_eq!(&back[..written], &latin1[..]);
+    }
+
+    #[test]
+    fn test_convert_utf16_utf8() {
+        let text = "a\u{E9}\u{20AC}\u{1F4A9}z";
+        let utf16: Vec<u16> = text.encode_utf16().collect();
+
+        let mut utf8 = vec![0u8; utf16.len() * 3 + 1];
+        let written = convert_utf16_to_utf8(&utf16, &mut utf8);
+        assert_eq!(&utf8[..written], text.as_bytes());
+        let mut utf8 = vec![0u8; 4];
+        let written = convert_utf16_to_utf8(&[0xD800u16], &mut utf8);
+        assert_eq!(&utf8[..written], "\u{FFFD}".as_bytes());
+
+        let mut string = "x".repeat(utf16.len() * 3 + 1);
+        let written = convert_utf16_to_str(&utf16, &mut string);
+        assert_eq!(&string[..written], text);
+
+        let mut back = vec![0u16; text.len() + 1];
+        let written = convert_utf8_to_utf16(text.as_bytes(), &mut back);
+        assert_eq!(&back[..written], &utf16[..]);
+        let written = convert_utf8_to_utf16(b"a\xFFb", &mut back);
+        assert_eq!(&back[..written], &[0x61u16, 0xFFFD, 0x62][..]);
+        let mut back = [0u16; 4];
+        let written = convert_utf8_to_utf16(b"a\xE2\x82", &mut back);
+        assert_eq!(&back[..written], &[0x61u16, 0xFFFD][..]);
+
+        let mut back = vec![0u16; text.len()];
+        let written = convert_str_to_utf16(text, &mut back);
+        assert_eq!(&back[..written], &utf16[..]);
+    }
+
+    #[test]
+    fn test_is_char_bidi() {
+        assert!(!is_char_bidi('a'));
+        assert!(!is_char_bidi('\u{058F}'));
+        assert!(is_char_bidi('\u{05D0}'));
+        assert!(is_char_bidi('\u{0627}'));
+        assert!(is_char_bidi('\u{200F}'));
+        assert!(!is_char_bidi('\u{200E}'));
+        assert!(is_char_bidi('\u{FEFC}'));
+        assert!(!is_char_bidi('\u{FEFF}'));
+        assert!(is_char_bidi('\u{10900}'));
+        assert!(!is_char_bidi('\u{1F4A9}'));
+        assert!(is_utf16_code_unit_bidi(0x05D0));
+        assert!(is_utf16_code_unit_bidi(0xD802));
+        assert!(!is_utf16_code_unit_bidi(0xD83D));
+        assert!(!is_utf16_code_unit_bidi(0xDC00));
+    }
+
+    #[test]
+    fn test_is_bidi() {
+        let ltr = "Latin1 \u{E9} and Cyrillic \u{0436} and CJK \u{65E5} and \u{1F4A9}";
+        let rtl = "Latin1 \u{E9} and Hebrew \u{05E9}\u{05DC}\u{05D5}\u{05DD}";
+        let astral = "Phoenician \u{10900}";
+        assert!(!is_utf8_bidi(ltr.as_bytes()));
+        assert!(is_utf8_bidi(rtl.as_bytes()));
+        assert!(is_utf8_bidi(astral.as_bytes()));
+        assert!(is_utf8_bidi("abc\u{202E}".as_bytes()));
+        // Overlong and malformed sequences are not bidi.
+        assert!(!is_utf8_bidi(b"\xE0\x96\x90\xD7"));
+        let utf16: Vec<u16> = ltr.encode_utf16().collect();
+        assert!(!is_utf16_bidi(&utf16));
+        let utf16: Vec<u16> = rtl.encode_utf16().collect();
+        assert!(is_utf16_bidi(&utf16));
+        let utf16: Vec<u16> = astral.encode_utf16().collect();
+        assert!(is_utf16_bidi(&utf16));
+    }
+
+    #[test]
+    fn test_check_for_latin1_and_bidi() {
+        for &(text, expected) in &[("abc\u{E9}", Latin1Bidi::Latin1),
+                                   ("abc\u{E9}\u{0436}", Latin1Bidi::LeftToRight),
+                                   ("abc\u{E9}\u{0436}\u{0627}", Latin1Bidi::Bidi)] {
+            assert_eq!(check_utf8_for_latin1_and_bidi(text.as_bytes()), expected);
+            let utf16: Vec<u16> = text.encode_utf16().collect();
+            assert_eq!(check_utf16_for_latin1_and_bidi(&utf16), expected);
+        }
+    }
+
+    #[test]
+    fn test_utf16_valid_up_to() {
+        let valid: Vec<u16> = "abcdefghijklmnop\u{E9}\u{1F4A9}qrstuvwxyz0123456789"
+            .encode_utf16()
+            .collect();
+        assert_eq!(utf16_valid_up_to(&valid), valid.len());
+        assert_eq!(utf16_valid_up_to(&[]), 0);
+        for i in 0..valid.len() {
+            let mut invalid = valid.clone();
+            invalid[i] = 0xDC00;
+            // Replacing the low half of the pair keeps the pair valid.
+            let expected = if i == 18 { valid.len() } else { i };
+            assert_eq!(utf16_valid_up_to(&invalid), expected);
+        }
+        // High surrogate at the end
+        assert_eq!(utf16_valid_up_to(&[0x61u16, 0xD83D]), 1);
+        // Two high surrogates
+        assert_eq!(utf16_valid_up_to(&[0xD83Du16, 0xD83D, 0xDCA9]), 0);
+    }
+
+    #[test]
+    fn test_ensure_utf16_validity() {
+        let mut buffer = [0x61u16, 0xDC00, 0xD83D, 0xD83D, 0xDCA9, 0x62, 0xD800];
+        ensure_utf16_validity(&mut buffer);
+        assert_eq!(buffer, [0x61u16, 0xFFFD, 0xFFFD, 0xD83D, 0xDCA9, 0x62, 0xFFFD]);
+        assert_eq!(String::from_utf16(&buffer).unwrap(),
+                   "a\u{FFFD}\u{FFFD}\u{1F4A9}b\u{FFFD}");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_convert_utf16_to_utf8_too_short() {
+        let mut utf8 = [0u8; 3];
+        convert_utf16_to_utf8(&[0x20ACu16], &mut utf8);
+    }
+}
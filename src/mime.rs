@@ -0,0 +1,846 @@
+// Copyright 2017 Mozilla Foundation. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Decoding of the character encoding layers of email.
+//!
+//! This module is available when the `mime` feature is enabled. It provides:
+//!
+//! * `decode_header()` for header values that contain RFC 2047
+//!   encoded-words (`=?charset?B?...?=` and `=?charset?Q?...?=`).
+//! * `decode_parameter()` for header parameters, including RFC 2231
+//!   continuations and charset-tagged values (`filename*0*=utf-8''...`).
+//! * `BodyDecoder` for bodies in the base64 or quoted-printable
+//!   content-transfer-encoding, which it decodes incrementally into a
+//!   `Decoder`.
+//!
+//! Charsets are resolved with `encoding_for_mime_charset()`, which uses
+//! `Encoding::for_label()` and additionally knows some aliases that occur in
+//! email but are not labels in the Encoding Standard.
+//!
+//! Email in the wild is often broken, so decoding is lenient: malformed
+//! base64 and quoted-printable is decoded on a best-effort basis, an
+//! encoded-word with an unknown charset is decoded using the fallback
+//! encoding supplied by the caller and adjacent encoded-words in the same
+//! charset are concatenated before decoding so that characters split across
+//! encoded-words decode correctly.
+//!
+//! UTF-7 is not supported, since the Encoding Standard doesn't support it.
+//! Labels that the Encoding Standard maps to the replacement encoding (e.g.
+//! `iso-2022-kr`) resolve to `REPLACEMENT`.
+
+use super::*;
+
+/// Charset aliases that occur in email but are not labels in the Encoding
+/// Standard. Must be sorted.
+static MIME_CHARSET_ALIASES: [(&'static [u8], &'static Encoding); 13] =
+    [(b"cp874", &WINDOWS_874_INIT),
+     (b"cp932", &SHIFT_JIS_INIT),
+     (b"cp936", &GBK_INIT),
+     (b"cp949", &EUC_KR_INIT),
+     (b"cp950", &BIG5_INIT),
+     (b"euc-cn", &GBK_INIT),
+     (b"latin-1", &WINDOWS_1252_INIT),
+     (b"ms874", &WINDOWS_874_INIT),
+     (b"ms936", &GBK_INIT),
+     (b"ms949", &EUC_KR_INIT),
+     (b"ms950", &BIG5_INIT),
+     (b"uhc", &EUC_KR_INIT),
+     (b"x-euc-kr", &EUC_KR_INIT)];
+
+/// Resolves a charset from email to an encoding.
+///
+/// An RFC 2231 language suffix (e.g. the `*en` in `us-ascii*en`) is
+/// ignored. The label is first resolved with `Encoding::for_label()`. If
+/// that fails, some aliases that occur in email (e.g. `cp932` and `uhc`) are
+/// recognized.
+///
+/// Available to Rust only.
+pub fn encoding_for_mime_charset(charset: &[u8]) -> Option<&'static Encoding> {
+    let charset = match charset.iter().position(|b| *b == b'*') {
+        Some(star) => &charset[..star],
+        None => charset,
+    };
+    if let Some(encoding) = Encoding::for_label(charset) {
+        return Some(encoding);
+    }
+    let lower = charset.trim_ascii().to_ascii_lowercase();
+    MIME_CHARSET_ALIASES
+        .binary_search_by(|&(alias, _)| alias.cmp(&lower[..]))
+        .ok()
+        .map(|i| MIME_CHARSET_ALIASES[i].1)
+}
+
+fn hex_value(byte: u8) -> Option<u8> {
+    match byte {
+        b'0'..=b'9' => Some(byte - b'0'),
+        b'A'..=b'F' => Some(byte - b'A' + 10),
+        b'a'..=b'f' => Some(byte - b'a' + 10),
+        _ => None,
+    }
+}
+
+fn base64_value(byte: u8) -> Option<u32> {
+    match byte {
+        b'A'..=b'Z' => Some(u32::from(byte - b'A')),
+        b'a'..=b'z' => Some(u32::from(byte - b'a') + 26),
+        b'0'..=b'9' => Some(u32::from(byte - b'0') + 52),
+        b'+' => Some(62),
+        b'/' => Some(63),
+        _ => None,
+    }
+}
+
+/// Incremental lenient base64 decoder. Bytes outside the base64 alphabet
+/// are ignored and missing padding is tolerated.
+#[derive(Default)]
+struct Base64 {
+    accumulator: u32,
+    sextets: u8,
+}
+
+impl Base64 {
+    fn decode(&mut self, src: &[u8], dst: &mut Vec<u8>) {
+        for &byte in src {
+            if byte == b'=' {
+                self.flush(dst);
+                continue;
+            }
+            if let Some(value) = base64_value(byte) {
+                self.accumulator = (self.accumulator << 6) | value;
+                self.sextets += 1;
+                if self.sextets == 4 {
+                    dst.push((self.accumulator >> 16) as u8);
+                    dst.push((self.accumulator >> 8) as u8);
+                    dst.push(self.accumulator as u8);
+                    self.accumulator = 0;
+                    self.sextets = 0;
+                }
+            }
+        }
+    }
+
+    /// Emits the bytes of a partial quantum. A lone trailing sextet carries
+    /// no complete byte and is dropped.
+    fn flush(&mut self, dst: &mut Vec<u8>) {
+        match self.sextets {
+            2 => {
+                dst.push((self.accumulator >> 4) as u8);
+            }
+            3 => {
+                dst.push((self.accumulator >> 10) as u8);
+                dst.push((self.accumulator >> 2) as u8);
+            }
+            _ => {}
+        }
+        self.accumulator = 0;
+        self.sextets = 0;
+    }
+}
+
+/// Incremental lenient quoted-printable decoder. An `=` that doesn't start
+/// a valid escape or soft line break is taken literally.
+#[derive(Default)]
+struct QuotedPrintable {
+    /// The bytes starting with an `=` whose meaning is not known yet.
+    pending: Vec<u8>,
+    /// Whether a soft line break ended in CR, so that an LF is to be skipped.
+    skip_lf: bool,
+}
+
+impl QuotedPrintable {
+    fn decode(&mut self, src: &[u8], dst: &mut Vec<u8>) {
+        for &byte in src {
+            if self.skip_lf {
+                self.skip_lf = false;
+                if byte == b'\n' {
+                    continue;
+                }
+            }
+            self.decode_byte(byte, dst);
+        }
+    }
+
+    fn decode_byte(&mut self, byte: u8, dst: &mut Vec<u8>) {
+        if self.pending.is_empty() {
+            if byte == b'=' {
+                self.pending.push(byte);
+            } else {
+                dst.push(byte);
+            }
+            return;
+        }
+        if self.pending.len() == 1 && hex_value(byte).is_some() {
+            self.pending.push(byte);
+            return;
+        }
+        if let Some(high) = self.pending.get(1).and_then(|b| hex_value(*b)) {
+            if let Some(low) = hex_value(byte) {
+                dst.push((high << 4) | low);
+                self.pending.clear();
+                return;
+            }
+        } else if byte == b' ' || byte == b'\t' {
+            // Possibly trailing whitespace before a soft line break.
+            self.pending.push(byte);
+            return;
+        } else if byte == b'\n' || byte == b'\r' {
+            self.pending.clear();
+            self.skip_lf = byte == b'\r';
+            return;
+        }
+        dst.extend_from_slice(&self.pending);
+        self.pending.clear();
+        self.decode_byte(byte, dst);
+    }
+
+    fn flush(&mut self, dst: &mut Vec<u8>) {
+        // `=` followed by nothing but whitespace at the end is a soft line
+        // break.
+        if self.pending.get(1).and_then(|b| hex_value(*b)).is_some() {
+            dst.extend_from_slice(&self.pending);
+        }
+        self.pending.clear();
+        self.skip_lf = false;
+    }
+}
+
+/// Decodes the Q encoding of RFC 2047.
+fn decode_q(src: &[u8], dst: &mut Vec<u8>) {
+    let mut i = 0usize;
+    while i < src.len() {
+        let byte = src[i];
+        if byte == b'=' && i + 2 < src.len() {
+            if let (Some(high), Some(low)) = (hex_value(src[i + 1]), hex_value(src[i + 2])) {
+                dst.push((high << 4) | low);
+                i += 3;
+                continue;
+            }
+        }
+        dst.push(if byte == b'_' { b' ' } else { byte });
+        i += 1;
+    }
+}
+
+/// Decodes the percent-encoding of RFC 2231 extended parameter values.
+fn decode_percent(src: &[u8], dst: &mut Vec<u8>) {
+    let mut i = 0usize;
+    while i < src.len() {
+        let byte = src[i];
+        if byte == b'%' && i + 2 < src.len() {
+            if let (Some(high), Some(low)) = (hex_value(src[i + 1]), hex_value(src[i + 2])) {
+                dst.push((high << 4) | low);
+                i += 3;
+                continue;
+            }
+        }
+        dst.push(byte);
+        i += 1;
+    }
+}
+
+/// Decodes bytes that carry no charset information: as UTF-8 if they are
+/// valid UTF-8 and using `fallback` otherwise.
+fn decode_unlabeled(bytes: &[u8], fallback: &'static Encoding, dst: &mut String) {
+    match ::core::str::from_utf8(bytes) {
+        Ok(s) => dst.push_str(s),
+        Err(_) => dst.push_str(&fallback.decode_without_bom_handling(bytes).0),
+    }
+}
+
+/// An encoded-word that has been undone down to the byte level.
+struct EncodedWord {
+    encoding: &'static Encoding,
+    bytes: Vec<u8>,
+    /// The length of the encoded-word in the input.
+    len: usize,
+}
+
+/// Parses the encoded-word that `bytes` starts with, if any.
+fn parse_encoded_word(bytes: &[u8], fallback: &'static Encoding) -> Option<EncodedWord> {
+    if !bytes.starts_with(b"=?") {
+        return None;
+    }
+    let charset_len = bytes[2..]
+        .iter()
+        .take(76)
+        .position(|b| *b == b'?' || *b <= b' ')?;
+    if charset_len == 0 || bytes[2 + charset_len] != b'?' {
+        return None;
+    }
+    let charset = &bytes[2..2 + charset_len];
+    let mut position = 2 + charset_len + 1;
+    let q = match bytes.get(position)? {
+        b'B' | b'b' => false,
+        b'Q' | b'q' => true,
+        _ => {
+            return None;
+        }
+    };
+    position += 1;
+    if *bytes.get(position)? != b'?' {
+        return None;
+    }
+    position += 1;
+    let text_start = position;
+    loop {
+        match *bytes.get(position)? {
+            b'?' if bytes.get(position + 1) == Some(&b'=') => {
+                break;
+            }
+            b'\r' | b'\n' => {
+                return None;
+            }
+            _ => {
+                position += 1;
+            }
+        }
+    }
+    let text = &bytes[text_start..position];
+    let mut decoded = Vec::with_capacity(text.len());
+    if q {
+        decode_q(text, &mut decoded);
+    } else {
+        let mut base64 = Base64::default();
+        base64.decode(text, &mut decoded);
+        base64.flush(&mut decoded);
+    }
+    Some(EncodedWord {
+        encoding: encoding_for_mime_charset(charset).unwrap_or(fallback),
+        bytes: decoded,
+        len: position + 2,
+    })
+}
+
+/// Removes the CRLF (or bare LF) of folded header lines.
+fn unfold(bytes: &[u8]) -> Vec<u8> {
+    let mut unfolded = Vec::with_capacity(bytes.len());
+    let mut i = 0usize;
+    while i < bytes.len() {
+        let newline_len = if bytes[i..].starts_with(b"\r\n") {
+            2
+        } else if bytes[i] == b'\n' {
+            1
+        } else {
+            0
+        };
+        if newline_len != 0 {
+            if let Some(&next) = bytes.get(i + newline_len) {
+                if next == b' ' || next == b'\t' {
+                    i += newline_len;
+                    continue;
+                }
+            }
+        }
+        unfolded.push(bytes[i]);
+        i += 1;
+    }
+    unfolded
+}
+
+/// Decodes a header value that may contain RFC 2047 encoded-words.
+///
+/// The header value is unfolded first. Whitespace between adjacent
+/// encoded-words is removed as required by RFC 2047. The bytes of adjacent
+/// encoded-words in the same charset are concatenated before decoding, so a
+/// character split across encoded-words by a broken encoder decodes
+/// correctly.
+///
+/// `fallback` is used for encoded-words whose charset is unknown and for
+/// text outside encoded-words that is not valid UTF-8. (Text outside
+/// encoded-words that is valid UTF-8 is decoded as UTF-8 per RFC 6532.)
+/// Typical choices are `UTF_8` or the legacy encoding of the user's locale.
+///
+/// Malformed byte sequences are replaced with the REPLACEMENT CHARACTER.
+///
+/// Available to Rust only.
+pub fn decode_header(header: &[u8], fallback: &'static Encoding) -> String {
+    let bytes = unfold(header);
+    let mut out = String::with_capacity(bytes.len());
+    let mut pending: Option<(&'static Encoding, Vec<u8>)> = None;
+    let mut raw_start = 0usize;
+    let mut i = 0usize;
+    while i < bytes.len() {
+        let word = match parse_encoded_word(&bytes[i..], fallback) {
+            Some(word) => word,
+            None => {
+                i += 1;
+                continue;
+            }
+        };
+        let raw = &bytes[raw_start..i];
+        let adjacent = pending.is_some() && raw.iter().all(|b| *b == b' ' || *b == b'\t');
+        if !adjacent {
+            if let Some((encoding, pending_bytes)) = pending.take() {
+                out.push_str(&encoding.decode_without_bom_handling(&pending_bytes).0);
+            }
+            decode_unlabeled(raw, fallback, &mut out);
+        }
+        match pending {
+            Some((encoding, ref mut pending_bytes)) if encoding == word.encoding => {
+                pending_bytes.extend_from_slice(&word.bytes);
+            }
+            _ => {
+                if let Some((encoding, pending_bytes)) = pending.take() {
+                    out.push_str(&encoding.decode_without_bom_handling(&pending_bytes).0);
+                }
+                pending = Some((word.encoding, word.bytes));
+            }
+        }
+        i += word.len;
+        raw_start = i;
+    }
+    if let Some((encoding, pending_bytes)) = pending {
+        out.push_str(&encoding.decode_without_bom_handling(&pending_bytes).0);
+    }
+    decode_unlabeled(&bytes[raw_start..], fallback, &mut out);
+    out
+}
+
+/// Splits a header value into its `;`-separated items respecting quoted
+/// strings.
+fn split_parameters(bytes: &[u8]) -> Vec<&[u8]> {
+    let mut items = Vec::new();
+    let mut start = 0usize;
+    let mut quoted = false;
+    let mut escaped = false;
+    for (i, &byte) in bytes.iter().enumerate() {
+        if escaped {
+            escaped = false;
+        } else if quoted && byte == b'\\' {
+            escaped = true;
+        } else if byte == b'"' {
+            quoted = !quoted;
+        } else if byte == b';' && !quoted {
+            items.push(&bytes[start..i]);
+            start = i + 1;
+        }
+    }
+    items.push(&bytes[start..]);
+    items
+}
+
+/// Removes the quotes and backslash escapes of a quoted string. Other values
+/// are returned as is.
+fn unquote(value: &[u8]) -> Vec<u8> {
+    if value.first() != Some(&b'"') {
+        return value.to_vec();
+    }
+    let mut unquoted = Vec::with_capacity(value.len());
+    let mut escaped = false;
+    for &byte in &value[1..] {
+        if escaped {
+            unquoted.push(byte);
+            escaped = false;
+        } else if byte == b'\\' {
+            escaped = true;
+        } else if byte == b'"' {
+            break;
+        } else {
+            unquoted.push(byte);
+        }
+    }
+    unquoted
+}
+
+/// Splits an RFC 2231 extended value into the charset and the
+/// percent-encoded rest, skipping the language.
+fn split_extended_value(value: &[u8]) -> (Option<&[u8]>, &[u8]) {
+    let mut parts = value.splitn(3, |b| *b == b'\'');
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(charset), Some(_), Some(rest)) => (Some(charset), rest),
+        _ => (None, value),
+    }
+}
+
+/// Decodes the value of the parameter `name` of a header value such as that
+/// of `Content-Type` or `Content-Disposition`.
+///
+/// The parameter name is matched ASCII case-insensitively. RFC 2231
+/// continuations (`name*0`, `name*1*`, ...) are reassembled and RFC 2231
+/// charset-tagged values (`name*=charset'language'percent-encoded`) are
+/// decoded using the charset. These take precedence over a plain `name`
+/// parameter. A plain value is decoded with `decode_header()`, since RFC 2047
+/// encoded-words in parameter values, while not allowed, are common.
+///
+/// `fallback` is used as with `decode_header()`, including for
+/// charset-tagged values whose charset is unknown.
+///
+/// Returns `None` if the parameter is not present.
+///
+/// Available to Rust only.
+pub fn decode_parameter(header: &[u8], name: &str, fallback: &'static Encoding) -> Option<String> {
+    let name = name.as_bytes();
+    let mut plain: Option<Vec<u8>> = None;
+    let mut extended: Option<Vec<u8>> = None;
+    let mut segments: Vec<(usize, bool, Vec<u8>)> = Vec::new();
+    // The first item is the MIME type or disposition type.
+    for item in split_parameters(header).into_iter().skip(1) {
+        let equals = match item.iter().position(|b| *b == b'=') {
+            Some(equals) => equals,
+            None => {
+                continue;
+            }
+        };
+        let item_name = item[..equals].trim_ascii().to_ascii_lowercase();
+        let value = unquote(item[equals + 1..].trim_ascii());
+        if item_name.len() < name.len() || !item_name[..name.len()].eq_ignore_ascii_case(name) {
+            continue;
+        }
+        let suffix = &item_name[name.len()..];
+        if suffix.is_empty() {
+            if plain.is_none() {
+                plain = Some(value);
+            }
+        } else if suffix == b"*" {
+            if extended.is_none() {
+                extended = Some(value);
+            }
+        } else if suffix[0] == b'*' {
+            let (digits, is_extended) = match suffix.last() {
+                Some(&b'*') => (&suffix[1..suffix.len() - 1], true),
+                _ => (&suffix[1..], false),
+            };
+            if digits.is_empty() || !digits.iter().all(|b| b.is_ascii_digit()) {
+                continue;
+            }
+            let index = match ::core::str::from_utf8(digits).ok().and_then(|s| s.parse().ok()) {
+                Some(index) => index,
+                None => {
+                    continue;
+                }
+            };
+            if !segments.iter().any(|&(i, _, _)| i == index) {
+                segments.push((index, is_extended, value));
+            }
+        }
+    }
+    if !segments.is_empty() {
+        segments.sort_by_key(|&(index, _, _)| index);
+        let mut charset = None;
+        let mut bytes = Vec::new();
+        for (expected, &(index, is_extended, ref value)) in segments.iter().enumerate() {
+            if index != expected {
+                break;
+            }
+            if is_extended {
+                let rest = if index == 0 {
+                    let (segment_charset, rest) = split_extended_value(value);
+                    charset = segment_charset;
+                    rest
+                } else {
+                    &value[..]
+                };
+                decode_percent(rest, &mut bytes);
+            } else {
+                bytes.extend_from_slice(value);
+            }
+        }
+        return Some(decode_tagged(charset, &bytes, fallback));
+    }
+    if let Some(value) = extended {
+        let (charset, rest) = split_extended_value(&value);
+        let mut bytes = Vec::new();
+        decode_percent(rest, &mut bytes);
+        return Some(decode_tagged(charset, &bytes, fallback));
+    }
+    plain.map(|value| decode_header(&value, fallback))
+}
+
+/// Decodes bytes that may have been tagged with a charset.
+fn decode_tagged(charset: Option<&[u8]>, bytes: &[u8], fallback: &'static Encoding) -> String {
+    let mut out = String::new();
+    match charset.filter(|c| !c.is_empty()) {
+        Some(charset) => {
+            let encoding = encoding_for_mime_charset(charset).unwrap_or(fallback);
+            out.push_str(&encoding.decode_without_bom_handling(bytes).0);
+        }
+        None => {
+            decode_unlabeled(bytes, fallback, &mut out);
+        }
+    }
+    out
+}
+
+/// A content-transfer-encoding of a MIME body part.
+///
+/// Available to Rust only.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum TransferEncoding {
+    /// `7bit`, `8bit` or `binary`: the bytes are taken as is.
+    Identity,
+    /// `base64`
+    Base64,
+    /// `quoted-printable`
+    QuotedPrintable,
+}
+
+impl TransferEncoding {
+    /// Resolves the value of a `Content-Transfer-Encoding` header ASCII
+    /// case-insensitively and ignoring surrounding whitespace.
+    ///
+    /// Returns `None` for unknown values. (Callers typically treat those as
+    /// `TransferEncoding::Identity`.)
+    ///
+    /// Available to Rust only.
+    pub fn for_label(label: &[u8]) -> Option<TransferEncoding> {
+        let label = label.trim_ascii();
+        if label.eq_ignore_ascii_case(b"base64") {
+            Some(TransferEncoding::Base64)
+        } else if label.eq_ignore_ascii_case(b"quoted-printable") {
+            Some(TransferEncoding::QuotedPrintable)
+        } else if label.eq_ignore_ascii_case(b"7bit") || label.eq_ignore_ascii_case(b"8bit") ||
+                  label.eq_ignore_ascii_case(b"binary") {
+            Some(TransferEncoding::Identity)
+        } else {
+            None
+        }
+    }
+}
+
+/// Incrementally decodes a MIME body part by first undoing its
+/// content-transfer-encoding and then decoding the resulting bytes with a
+/// `Decoder`.
+///
+/// Available to Rust only.
+pub struct BodyDecoder {
+    transfer_encoding: TransferEncoding,
+    base64: Base64,
+    quoted_printable: QuotedPrintable,
+    decoder: Decoder,
+    bytes: Vec<u8>,
+}
+
+impl BodyDecoder {
+    /// Instantiates a body decoder for the given content-transfer-encoding
+    /// and character decoder.
+    ///
+    /// The character decoder is typically obtained from
+    /// `encoding_for_mime_charset()` applied to the `charset` parameter of
+    /// the `Content-Type` of the body part.
+    ///
+    /// Available to Rust only.
+    pub fn new(transfer_encoding: TransferEncoding, decoder: Decoder) -> BodyDecoder {
+        BodyDecoder {
+            transfer_encoding,
+            base64: Base64::default(),
+            quoted_printable: QuotedPrintable::default(),
+            decoder,
+            bytes: Vec::new(),
+        }
+    }
+
+    /// The character decoder.
+    ///
+    /// Available to Rust only.
+    pub fn decoder(&self) -> &Decoder {
+        &self.decoder
+    }
+
+    /// Decodes all of `src` appending the result to `dst`, which is grown
+    /// as needed. `last` must be `true` for the last buffer of the body part
+    /// and `false` otherwise.
+    ///
+    /// Returns `true` if malformed sequences were replaced with the
+    /// REPLACEMENT CHARACTER.
+    ///
+    /// Available to Rust only.
+    pub fn decode_to_string(&mut self, src: &[u8], dst: &mut String, last: bool) -> bool {
+        self.bytes.clear();
+        match self.transfer_encoding {
+            TransferEncoding::Identity => {
+                self.bytes.extend_from_slice(src);
+            }
+            TransferEncoding::Base64 => {
+                self.base64.decode(src, &mut self.bytes);
+                if last {
+                    self.base64.flush(&mut self.bytes);
+                }
+            }
+            TransferEncoding::QuotedPrintable => {
+                self.quoted_printable.decode(src, &mut self.bytes);
+                if last {
+                    self.quoted_printable.flush(&mut self.bytes);
+                }
+            }
+        }
+        let mut had_replacements = false;
+        let mut read = 0usize;
+        loop {
+            let needed = self.decoder
+                .max_utf8_buffer_length(self.bytes.len() - read)
+                .unwrap_or(1024);
+            dst.reserve(needed);
+            let (result, decoder_read, replaced) =
+                self.decoder
+                    .decode_to_string(&self.bytes[read..], dst, last);
+            read += decoder_read;
+            had_replacements |= replaced;
+            if result == CoderResult::InputEmpty {
+                return had_replacements;
+            }
+        }
+    }
+}
+
+// Any copyright to the test code below this comment is dedicated to the
+// Public Domain. http://creativecommons.org/publicdomain/zero/1.0/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mime_charset_aliases() {
+        for window in MIME_CHARSET_ALIASES.windows(2) {
+            assert!(window[0].0 < window[1].0);
+        }
+        for &(alias, _) in MIME_CHARSET_ALIASES.iter() {
+            assert_eq!(Encoding::for_label(alias), None);
+        }
+        assert_eq!(encoding_for_mime_charset(b"CP932"), Some(SHIFT_JIS));
+        assert_eq!(encoding_for_mime_charset(b"us-ascii*en"), Some(WINDOWS_1252));
+        assert_eq!(encoding_for_mime_charset(b"iso-2022-kr"), Some(REPLACEMENT));
+        assert_eq!(encoding_for_mime_charset(b"utf-7"), None);
+    }
+
+    #[test]
+    fn test_decode_header() {
+        assert_eq!(decode_header(b"=?ISO-8859-1?Q?Andr=E9?= Pirard", UTF_8),
+                   "Andr\u{E9} Pirard");
+        assert_eq!(decode_header(b"=?utf-8?B?5pel5pys6Kqe?=", UTF_8),
+                   "\u{65E5}\u{672C}\u{8A9E}");
+        assert_eq!(decode_header(b"=?utf-8?q?a_b?= =?utf-8?q?c?=", UTF_8),
+                   "a bc");
+        assert_eq!(decode_header(b"=?utf-8?q?a?=\r\n  =?iso-8859-1?q?=E9?= d", UTF_8),
+                   "a\u{E9} d");
+        assert_eq!(decode_header(b"x =?utf-8?q?a?= y", UTF_8), "x a y");
+        assert_eq!(decode_header(b"Subject line\r\n folded", UTF_8),
+                   "Subject line folded");
+        assert_eq!(decode_header(b"=?shift_jis*ja?B?k/qWe4zq?=", UTF_8),
+                   "\u{65E5}\u{672C}\u{8A9E}");
+    }
+
+    #[test]
+    fn test_decode_header_lenient() {
+        // A character split across encoded-words.
+        assert_eq!(decode_header(b"=?utf-8?B?5pel5g==?= =?utf-8?B?nKzoqp4=?=", UTF_8),
+                   "\u{65E5}\u{672C}\u{8A9E}");
+        // Unknown charset and raw 8-bit bytes use the fallback.
+        assert_eq!(decode_header(b"=?x-bogus?Q?=E9?= \xE9", WINDOWS_1252),
+                   "\u{E9} \u{E9}");
+        // Raw UTF-8.
+        assert_eq!(decode_header("caf\u{E9}".as_bytes(), WINDOWS_1252),
+                   "caf\u{E9}");
+        // Missing base64 padding and stray characters.
+        assert_eq!(decode_header(b"=?utf-8?B?YWJj ZA?=", UTF_8), "abcd");
+        // Spaces inside Q text and invalid escapes.
+        assert_eq!(decode_header(b"=?utf-8?Q?a b=ZZ?=", UTF_8), "a b=ZZ");
+        // Not encoded-words.
+        assert_eq!(decode_header(b"=?utf-8?X?abc?= =?utf-8?Q?abc", UTF_8),
+                   "=?utf-8?X?abc?= =?utf-8?Q?abc");
+        assert_eq!(decode_header(b"=?utf-8?Q?=C3?=", UTF_8), "\u{FFFD}");
+    }
+
+    #[test]
+    fn test_decode_parameter() {
+        let header = b"attachment; filename=\"plain.txt\"; size=3";
+        assert_eq!(decode_parameter(header, "filename", UTF_8),
+                   Some("plain.txt".to_string()));
+        assert_eq!(decode_parameter(header, "FileName", UTF_8),
+                   Some("plain.txt".to_string()));
+        assert_eq!(decode_parameter(header, "name", UTF_8), None);
+        assert_eq!(decode_parameter(b"attachment; filename*=iso-8859-1'fr'caf%E9.txt",
+                                    "filename",
+                                    UTF_8),
+                   Some("caf\u{E9}.txt".to_string()));
+        assert_eq!(decode_parameter(b"attachment; filename*0*=utf-8''%E6%97%A5; \
+                                      filename*2=\".txt\"; filename*1*=%E6%9C%AC; \
+                                      filename=fallback.txt",
+                                    "filename",
+                                    UTF_8),
+                   Some("\u{65E5}\u{672C}.txt".to_string()));
+        assert_eq!(decode_parameter(b"attachment; filename*0=\"a;b\"; filename*2=c",
+                                    "filename",
+                                    UTF_8),
+                   Some("a;b".to_string()));
+        assert_eq!(decode_parameter(b"text/plain; name=\"=?utf-8?B?5pel?=.txt\"",
+                                    "name",
+                                    UTF_8),
+                   Some("\u{65E5}.txt".to_string()));
+        assert_eq!(decode_parameter(b"text/plain; name=\"a\\\"b\"", "name", UTF_8),
+                   Some("a\"b".to_string()));
+        assert_eq!(decode_parameter(b"attachment; filename*=caf%E9", "filename", WINDOWS_1252),
+                   Some("caf\u{E9}".to_string()));
+    }
+
+    #[test]
+    fn test_transfer_encoding_for_label() {
+        assert_eq!(TransferEncoding::for_label(b" Base64 "),
+                   Some(TransferEncoding::Base64));
+        assert_eq!(TransferEncoding::for_label(b"QUOTED-PRINTABLE"),
+                   Some(TransferEncoding::QuotedPrintable));
+        assert_eq!(TransferEncoding::for_label(b"8bit"),
+                   Some(TransferEncoding::Identity));
+        assert_eq!(TransferEncoding::for_label(b"x-uuencode"), None);
+    }
+
+    fn decode_body(transfer_encoding: TransferEncoding,
+                   encoding: &'static Encoding,
+                   body: &[u8],
+                   chunk: usize)
+                   -> (String, bool) {
+        let mut decoder = BodyDecoder::new(transfer_encoding, encoding.new_decoder());
+        let mut out = String::new();
+        let mut had_replacements = false;
+        let mut chunks = body.chunks(chunk).peekable();
+        while let Some(chunk) = chunks.next() {
+            had_replacements |= decoder.decode_to_string(chunk, &mut out, chunks.peek().is_none());
+        }
+        if body.is_empty() {
+            had_replacements |= decoder.decode_to_string(b"", &mut out, true);
+        }
+        (out, had_replacements)
+    }
+
+    #[test]
+    fn test_body_base64() {
+        let body = b"5pel5pys6Kqe\r\n44Gn44GZ\r\n";
+        for chunk in 1..body.len() + 1 {
+            assert_eq!(decode_body(TransferEncoding::Base64, UTF_8, body, chunk),
+                       ("\u{65E5}\u{672C}\u{8A9E}\u{3067}\u{3059}".to_string(), false));
+        }
+        assert_eq!(decode_body(TransferEncoding::Base64, SHIFT_JIS, b"k/qWe4zq", 5),
+                   ("\u{65E5}\u{672C}\u{8A9E}".to_string(), false));
+        assert_eq!(decode_body(TransferEncoding::Base64, UTF_8, b"YWJjZA", 4),
+                   ("abcd".to_string(), false));
+        assert_eq!(decode_body(TransferEncoding::Base64, UTF_8, b"4w==", 4),
+                   ("\u{FFFD}".to_string(), true));
+    }
+
+    #[test]
+    fn test_body_quoted_printable() {
+        let body = b"caf=C3=A9 au =\r\nlait=20\r\n=3D =\nx =  \r\ny";
+        for chunk in 1..body.len() + 1 {
+            assert_eq!(decode_body(TransferEncoding::QuotedPrintable, UTF_8, body, chunk).0,
+                       "caf\u{E9} au lait \r\n= x y");
+        }
+        assert_eq!(decode_body(TransferEncoding::QuotedPrintable, WINDOWS_1252, b"=E9=", 1).0,
+                   "\u{E9}");
+        assert_eq!(decode_body(TransferEncoding::QuotedPrintable, UTF_8, b"a=ZZ =4 =4", 2).0,
+                   "a=ZZ =4 =4");
+    }
+
+    #[test]
+    fn test_body_identity() {
+        assert_eq!(decode_body(TransferEncoding::Identity, KOI8_R, b"\xF0\xD2\xC9", 1),
+                   ("\u{041F}\u{0440}\u{0438}".to_string(), false));
+        assert_eq!(decode_body(TransferEncoding::Identity, UTF_8, b"", 1),
+                   (String::new(), false));
+    }
+}
@@ -0,0 +1,1285 @@
+// Copyright 2017 Mozilla Foundation. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! This module implements error handling policies other than the
+//! REPLACEMENT CHARACTER and HTML decimal numeric character references on top
+//! of the `_without_replacement` methods, which every `VariantDecoder` and
+//! `VariantEncoder` already supports.
+
+use core::fmt::Write as FmtWrite;
+use core::ops::Range;
+
+use super::*;
+
+/// Lets `write!` append to a `Vec<u8>` without `std::io::Write`.
+struct ByteWriter<'a>(&'a mut Vec<u8>);
+
+impl<'a> FmtWrite for ByteWriter<'a> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        self.0.extend_from_slice(s.as_bytes());
+        Ok(())
+    }
+}
+
+/// What a `Decoder` does with a malformed byte sequence when decoding
+/// with one of the methods whose name does not end with
+/// `_without_replacement`.
+///
+/// The `_without_replacement` methods report malformed sequences to the
+/// caller regardless of the policy.
+///
+/// Available to Rust only.
+pub enum MalformedPolicy {
+    /// Emit one REPLACEMENT CHARACTER per malformed sequence. This is what the
+    /// Encoding Standard requires and what decoders do by default.
+    Replace,
+
+    /// Drop the malformed sequence from the output.
+    Skip,
+
+    /// Emit the given string in place of each malformed sequence.
+    ReplaceWith(String),
+
+    /// Emit each byte of the malformed sequence as a `\xNN` escape with
+    /// uppercase hexadecimal digits, e.g. `\xFF`.
+    ///
+    /// Note that the output does not escape backslashes that occur in the
+    /// input, so the escapes are meant for human readers.
+    Escape,
+
+    /// Call the closure with the bytes of the malformed sequence. Whatever
+    /// the closure appends to the `String` becomes the output in place of the
+    /// malformed sequence.
+    Callback(MalformedCallback),
+}
+
+/// The type of the closure wrapped by `MalformedPolicy::Callback`.
+///
+/// The closure must be `Send` and `Sync` so that `Decoder` stays `Send` and
+/// `Sync`.
+///
+/// Available to Rust only.
+pub type MalformedCallback = Box<dyn FnMut(&[u8], &mut String) + Send + Sync>;
+
+/// Gives `decoder` a `MalformedHandler` unless the options are the defaults.
+fn with_malformed_handler(mut decoder: Decoder, policy: MalformedPolicy, record: bool) -> Decoder {
+    match (policy, record) {
+        (MalformedPolicy::Replace, false) => {}
+        (policy, record) => {
+            decoder.malformed = Some(Box::new(MalformedHandler::new(policy, record)));
+        }
+    }
+    decoder
+}
+
+/// A builder for `Decoder`s with non-default BOM handling or error handling.
+///
+/// Obtained from `Encoding::decoder_options()`. By default, the decoder has
+/// BOM sniffing enabled and replaces malformed sequences with the
+/// REPLACEMENT CHARACTER, i.e. it is the decoder returned by
+/// `Encoding::new_decoder()`.
+///
+/// Available to Rust only.
+pub struct DecoderOptions {
+    encoding: &'static Encoding,
+    bom_handling: BomHandling,
+    policy: MalformedPolicy,
+    record: bool,
+}
+
+impl DecoderOptions {
+    /// Starts building a decoder for `encoding`. Same as
+    /// `encoding.decoder_options()`.
+    pub fn new(encoding: &'static Encoding) -> DecoderOptions {
+        DecoderOptions {
+            encoding,
+            bom_handling: BomHandling::Sniff,
+            policy: MalformedPolicy::Replace,
+            record: false,
+        }
+    }
+
+    /// Removes the BOM of the encoding instead of sniffing. See
+    /// `Encoding::new_decoder_with_bom_removal()`.
+    pub fn with_bom_removal(mut self) -> DecoderOptions {
+        self.bom_handling = BomHandling::Remove;
+        self
+    }
+
+    /// Disables BOM handling. See
+    /// `Encoding::new_decoder_without_bom_handling()`.
+    pub fn without_bom_handling(mut self) -> DecoderOptions {
+        self.bom_handling = BomHandling::Off;
+        self
+    }
+
+    /// Sets what to do with malformed sequences.
+    pub fn malformed_policy(mut self, policy: MalformedPolicy) -> DecoderOptions {
+        self.policy = policy;
+        self
+    }
+
+    /// Makes the decoder keep a `MalformedSequence` record of each malformed
+    /// sequence it handles in the methods whose name does not end with
+    /// `_without_replacement`. The records can be retrieved using
+    /// `Decoder::take_malformed_sequences()`.
+    pub fn record_malformed_sequences(mut self) -> DecoderOptions {
+        self.record = true;
+        self
+    }
+
+    /// Instantiates the decoder.
+    pub fn build(self) -> Decoder {
+        let DecoderOptions { encoding, bom_handling, policy, record } = self;
+        let decoder = Decoder::new(encoding, encoding.new_variant_decoder(), bom_handling);
+        with_malformed_handler(decoder, policy, record)
+    }
+
+    /// Instantiates a decoder that continues decoding the stream from where
+    /// the decoder that `snapshot` was taken from was, with the configured
+    /// malformed-sequence policy, or returns `None` if `snapshot` is invalid
+    /// or from an unsupported version. See `Decoder::from_snapshot()`.
+    ///
+    /// The encoding and the BOM handling come from the snapshot, so the
+    /// encoding that these options were created for and the BOM handling
+    /// options are ignored. The offsets of the `MalformedSequence`s recorded
+    /// by the new decoder count from where the snapshot was taken.
+    pub fn build_from_snapshot(self, snapshot: &DecoderSnapshot) -> Option<Decoder> {
+        Decoder::from_snapshot(snapshot)
+            .map(|decoder| with_malformed_handler(decoder, self.policy, self.record))
+    }
+
+    /// Decodes complete input to `String` with the configured BOM handling
+    /// and policy when the entire input is available as a single buffer
+    /// (i.e. the end of the buffer marks the end of the stream).
+    ///
+    /// The second item in the returned tuple is the encoding that was
+    /// actually used (which may differ from the original encoding thanks to
+    /// BOM sniffing).
+    ///
+    /// The third item in the returned tuple indicates whether there were
+    /// malformed sequences (that were handled according to the policy).
+    ///
+    /// # Panics
+    ///
+    /// If the size calculation for the heap-allocated backing buffer overflows
+    /// `usize`.
+    pub fn decode(self, bytes: &[u8]) -> (String, &'static Encoding, bool) {
+        let mut decoder = self.build();
+        let mut string = String::with_capacity(
+            decoder
+                .max_utf8_buffer_length(bytes.len())
+                .unwrap()
+        );
+        let mut total_read = 0usize;
+        let mut total_had_errors = false;
+        loop {
+            let (result, read, had_errors) =
+                decoder.decode_to_string(&bytes[total_read..], &mut string, true);
+            total_read += read;
+            total_had_errors |= had_errors;
+            match result {
+                CoderResult::InputEmpty => {
+                    debug_assert_eq!(total_read, bytes.len());
+                    return (string, decoder.encoding(), total_had_errors);
+                }
+                CoderResult::OutputFull => {
+                    let needed = checked_add(
+                        decoder.pending_replacement_len(),
+                        decoder.max_utf8_buffer_length(bytes.len() - total_read),
+                    );
+                    string.reserve(needed.unwrap());
+                }
+            }
+        }
+    }
+}
+
+/// The number of most recently consumed input bytes that need to be
+/// remembered in order to recover the bytes of a malformed sequence. (At
+/// most six per the documentation of `DecoderResult::Malformed`.)
+const HISTORY_LENGTH: usize = 8;
+
+/// A malformed byte sequence encountered by a decoder.
+///
+/// Obtained from `Encoding::decode_with_malformed_sequences()` or from a
+/// `Decoder` built with `DecoderOptions::record_malformed_sequences()`.
+///
+/// Available to Rust only.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct MalformedSequence {
+    input_start: usize,
+    input_end: usize,
+    bytes: [u8; 4],
+    output_offset: usize,
+}
+
+impl MalformedSequence {
+    /// The range of byte offsets of the malformed sequence counting from the
+    /// start of the input stream (including a BOM if any).
+    pub fn input_range(&self) -> Range<usize> {
+        self.input_start..self.input_end
+    }
+
+    /// The bytes of the malformed sequence.
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes[..self.input_end - self.input_start]
+    }
+
+    /// The offset counting from the start of the output stream where the
+    /// replacement for the malformed sequence starts.
+    ///
+    /// The offset is in UTF-8 code units (`u8`) if the decoder was decoding to
+    /// UTF-8 and in UTF-16 code units (`u16`) if the decoder was decoding to
+    /// UTF-16 when it encountered the malformed sequence.
+    pub fn output_offset(&self) -> usize {
+        self.output_offset
+    }
+}
+
+/// The state a `Decoder` needs for applying a `MalformedPolicy` other than
+/// `Replace` or for recording `MalformedSequence`s.
+pub struct MalformedHandler {
+    policy: MalformedPolicy,
+    history: [u8; HISTORY_LENGTH],
+    history_len: usize,
+    pending: String,
+    pending_pos: usize,
+    records: Option<Vec<MalformedSequence>>,
+    input_offset: usize,
+    output_offset: usize,
+}
+
+impl MalformedHandler {
+    fn new(policy: MalformedPolicy, record: bool) -> MalformedHandler {
+        MalformedHandler {
+            policy,
+            history: [0u8; HISTORY_LENGTH],
+            history_len: 0,
+            pending: String::new(),
+            pending_pos: 0,
+            records: if record { Some(Vec::new()) } else { None },
+            input_offset: 0,
+            output_offset: 0,
+        }
+    }
+
+    /// The number of bytes of UTF-8 replacement output that did not fit in
+    /// the previous output buffer.
+    pub fn pending_len(&self) -> usize {
+        self.pending.len() - self.pending_pos
+    }
+
+    /// Remembers the tail of the bytes consumed from the input.
+    fn record(&mut self, consumed: &[u8]) {
+        if consumed.len() >= HISTORY_LENGTH {
+            self.history
+                .copy_from_slice(&consumed[consumed.len() - HISTORY_LENGTH..]);
+            self.history_len = HISTORY_LENGTH;
+            return;
+        }
+        let keep = ::core::cmp::min(self.history_len, HISTORY_LENGTH - consumed.len());
+        let start = self.history_len - keep;
+        self.history.copy_within(start..start + keep, 0);
+        self.history[keep..keep + consumed.len()].copy_from_slice(consumed);
+        self.history_len = keep + consumed.len();
+    }
+
+    /// Appends the replacement for the malformed sequence reported as
+    /// `Malformed(malformed_len, consumed_after)` after `read` bytes have
+    /// been read and `written` code units have been written during the
+    /// current call to the pending output.
+    fn handle(&mut self, malformed_len: u8, consumed_after: u8, read: usize, written: usize) {
+        let end = self.history_len
+                      .saturating_sub(consumed_after as usize);
+        let start = end.saturating_sub(malformed_len as usize);
+        let bytes = &self.history[start..end];
+        if let Some(ref mut records) = self.records {
+            let input_end = self.input_offset + read - consumed_after as usize;
+            let mut record = MalformedSequence {
+                input_start: input_end - bytes.len(),
+                input_end,
+                bytes: [0u8; 4],
+                output_offset: self.output_offset + written,
+            };
+            record.bytes[..bytes.len()].copy_from_slice(bytes);
+            records.push(record);
+        }
+        if self.pending_pos == self.pending.len() {
+            self.pending.clear();
+            self.pending_pos = 0;
+        }
+        match self.policy {
+            MalformedPolicy::Replace => self.pending.push('\u{FFFD}'),
+            MalformedPolicy::Skip => {}
+            MalformedPolicy::ReplaceWith(ref replacement) => self.pending.push_str(replacement),
+            MalformedPolicy::Escape => {
+                for b in bytes {
+                    write!(self.pending, "\\x{:02X}", b).unwrap();
+                }
+            }
+            MalformedPolicy::Callback(ref mut callback) => callback(bytes, &mut self.pending),
+        }
+    }
+
+    /// Advances the stream offsets at the end of a call.
+    fn advance(&mut self, read: usize, written: usize) {
+        self.input_offset += read;
+        self.output_offset += written;
+    }
+
+    /// Writes as many complete characters of the pending output to `dst` as
+    /// fit and returns the number of bytes written.
+    fn flush_utf8(&mut self, dst: &mut [u8]) -> usize {
+        let pending = &self.pending[self.pending_pos..];
+        let mut len = ::core::cmp::min(pending.len(), dst.len());
+        while !pending.is_char_boundary(len) {
+            len -= 1;
+        }
+        dst[..len].copy_from_slice(&pending.as_bytes()[..len]);
+        self.pending_pos += len;
+        len
+    }
+
+    /// Writes as many complete characters of the pending output to `dst` as
+    /// fit and returns the number of code units written.
+    fn flush_utf16(&mut self, dst: &mut [u16]) -> usize {
+        let mut written = 0usize;
+        for c in self.pending[self.pending_pos..].chars() {
+            let len = c.len_utf16();
+            if written + len > dst.len() {
+                break;
+            }
+            c.encode_utf16(&mut dst[written..]);
+            written += len;
+            self.pending_pos += c.len_utf8();
+        }
+        written
+    }
+}
+
+macro_rules! decode_with_policy_function {
+    ($name:ident,
+     $decode_without_replacement:ident,
+     $flush:ident,
+     $code_unit:ty) => (
+    /// Decodes like the corresponding method with replacement but with
+    /// malformed sequences handled by the `MalformedHandler` of `decoder`.
+    pub fn $name(decoder: &mut Decoder,
+                 src: &[u8],
+                 dst: &mut [$code_unit],
+                 last: bool)
+                 -> (CoderResult, usize, usize, bool) {
+        let mut handler = decoder.malformed.take().unwrap();
+        let mut had_errors = false;
+        let mut total_read = 0usize;
+        let mut total_written = 0usize;
+        let result = loop {
+            total_written += handler.$flush(&mut dst[total_written..]);
+            if handler.pending_len() != 0 {
+                break CoderResult::OutputFull;
+            }
+            let (result, read, written) = decoder.$decode_without_replacement(
+                &src[total_read..],
+                &mut dst[total_written..],
+                last,
+            );
+            handler.record(&src[total_read..total_read + read]);
+            total_read += read;
+            total_written += written;
+            match result {
+                DecoderResult::InputEmpty => {
+                    break CoderResult::InputEmpty;
+                }
+                DecoderResult::OutputFull => {
+                    break CoderResult::OutputFull;
+                }
+                DecoderResult::Malformed(malformed_len, consumed_after) => {
+                    had_errors = true;
+                    handler.handle(malformed_len, consumed_after, total_read, total_written);
+                }
+            }
+        };
+        handler.advance(total_read, total_written);
+        decoder.malformed = Some(handler);
+        (result, total_read, total_written, had_errors)
+    });
+}
+
+decode_with_policy_function!(decode_to_utf8_with_policy,
+                             decode_to_utf8_without_replacement,
+                             flush_utf8,
+                             u8);
+
+decode_with_policy_function!(decode_to_utf16_with_policy,
+                             decode_to_utf16_without_replacement,
+                             flush_utf16,
+                             u16);
+
+impl Decoder {
+    /// The number of bytes of UTF-8 replacement output waiting for more
+    /// output space.
+    fn pending_replacement_len(&self) -> usize {
+        match self.malformed {
+            Some(ref handler) => handler.pending_len(),
+            None => 0,
+        }
+    }
+
+    /// Returns the `MalformedSequence`s recorded since the previous call to
+    /// this method if the decoder was built with
+    /// `DecoderOptions::record_malformed_sequences()` and an empty `Vec`
+    /// otherwise.
+    ///
+    /// Available to Rust only.
+    pub fn take_malformed_sequences(&mut self) -> Vec<MalformedSequence> {
+        match self.malformed {
+            Some(ref mut handler) => {
+                match handler.records {
+                    Some(ref mut records) => ::core::mem::take(records),
+                    None => Vec::new(),
+                }
+            }
+            None => Vec::new(),
+        }
+    }
+}
+
+/// What an `Encoder` does with a character that the encoding cannot
+/// represent when encoding with one of the methods whose name does not end
+/// with `_without_replacement`.
+///
+/// The `_without_replacement` methods report unmappable characters to the
+/// caller regardless of the policy.
+///
+/// When encoding ISO-2022-JP, the replacement bytes should be printable
+/// ASCII. They are written in the ASCII state or, if they contain neither `\`
+/// nor `~`, possibly in the Roman state, in which the other printable ASCII
+/// bytes mean the same as in ASCII. The encoder switches from the Roman
+/// state to the ASCII state before writing replacement bytes that contain
+/// `\` or `~`.
+///
+/// Available to Rust only.
+pub enum UnmappablePolicy {
+    /// Emit an HTML decimal numeric character reference such as `&#128169;`.
+    /// This is what the Encoding Standard requires and what encoders do by
+    /// default.
+    Ncr,
+
+    /// Emit a question mark.
+    QuestionMark,
+
+    /// Emit an HTML hexadecimal numeric character reference with uppercase
+    /// hexadecimal digits, e.g. `&#x1F4A9;`.
+    HexNcr,
+
+    /// Emit the character as JavaScript-style `\uXXXX` escapes with uppercase
+    /// hexadecimal digits, i.e. as a surrogate pair of escapes for characters
+    /// outside the Basic Multilingual Plane, e.g. `\uD83D\uDCA9`.
+    Escape,
+
+    /// Emit a percent-encoded HTML decimal numeric character reference, e.g.
+    /// `%26%23128169%3B`, which is what the URL parser produces for
+    /// unmappable characters in query strings.
+    PercentEncodedNcr,
+
+    /// Call the closure with the unmappable character. Whatever the closure
+    /// appends to the `Vec<u8>` becomes the output in place of the character.
+    Callback(UnmappableCallback),
+}
+
+/// The type of the closure wrapped by `UnmappablePolicy::Callback`.
+///
+/// The closure must be `Send` and `Sync` so that `Encoder` stays `Send` and
+/// `Sync`.
+///
+/// Available to Rust only.
+pub type UnmappableCallback = Box<dyn FnMut(char, &mut Vec<u8>) + Send + Sync>;
+
+/// Gives `encoder` an `UnmappableHandler` unless the options are the
+/// defaults.
+fn with_unmappable_handler(mut encoder: Encoder,
+                           policy: UnmappablePolicy,
+                           record: bool)
+                           -> Encoder {
+    match (policy, record) {
+        (UnmappablePolicy::Ncr, false) => {}
+        (policy, record) => {
+            encoder.unmappable = Some(Box::new(UnmappableHandler::new(policy, record)));
+        }
+    }
+    encoder
+}
+
+/// A builder for `Encoder`s with a non-default policy for unmappable
+/// characters.
+///
+/// Obtained from `Encoding::encoder_options()`. By default, the encoder
+/// replaces unmappable characters with HTML decimal numeric character
+/// references, i.e. it is the encoder returned by `Encoding::new_encoder()`.
+///
+/// Available to Rust only.
+pub struct EncoderOptions {
+    encoding: &'static Encoding,
+    policy: UnmappablePolicy,
+    record: bool,
+}
+
+impl EncoderOptions {
+    /// Starts building an encoder for the _output encoding_ of `encoding`.
+    /// Same as `encoding.encoder_options()`.
+    pub fn new(encoding: &'static Encoding) -> EncoderOptions {
+        EncoderOptions {
+            encoding,
+            policy: UnmappablePolicy::Ncr,
+            record: false,
+        }
+    }
+
+    /// Sets what to do with unmappable characters.
+    pub fn unmappable_policy(mut self, policy: UnmappablePolicy) -> EncoderOptions {
+        self.policy = policy;
+        self
+    }
+
+    /// Makes the encoder keep an `UnmappableCharacter` record of each
+    /// unmappable character it handles in the methods whose name does not end
+    /// with `_without_replacement`. The records can be retrieved using
+    /// `Encoder::take_unmappables()`.
+    pub fn record_unmappables(mut self) -> EncoderOptions {
+        self.record = true;
+        self
+    }
+
+    /// Instantiates the encoder.
+    pub fn build(self) -> Encoder {
+        with_unmappable_handler(self.encoding.new_encoder(), self.policy, self.record)
+    }
+
+    /// Instantiates an encoder that continues encoding the stream from where
+    /// the encoder that `snapshot` was taken from was, with the configured
+    /// unmappable-character policy, or returns `None` if `snapshot` is
+    /// invalid or from an unsupported version. See `Encoder::from_snapshot()`.
+    ///
+    /// The encoding comes from the snapshot, so the encoding that these
+    /// options were created for is ignored. The offsets of the
+    /// `UnmappableCharacter`s recorded by the new encoder count from where the
+    /// snapshot was taken.
+    pub fn build_from_snapshot(self, snapshot: &EncoderSnapshot) -> Option<Encoder> {
+        Encoder::from_snapshot(snapshot)
+            .map(|encoder| with_unmappable_handler(encoder, self.policy, self.record))
+    }
+
+    /// Encodes complete input to `Cow<'a, [u8]>` with the configured policy
+    /// when the entire input is available as a single buffer (i.e. the end of
+    /// the buffer marks the end of the stream).
+    ///
+    /// The second item in the returned tuple is the encoding that was actually
+    /// used (which may differ from the original encoding thanks to some
+    /// encodings having UTF-8 as their output encoding).
+    ///
+    /// The third item in the returned tuple indicates whether there were
+    /// unmappable characters (that were handled according to the policy).
+    ///
+    /// Like `Encoding::encode()`, this method borrows the input when
+    /// encoding to UTF-8 or when encoding an ASCII-only input to an
+    /// ASCII-compatible encoding.
+    ///
+    /// # Panics
+    ///
+    /// If the size calculation for a heap-allocated backing buffer overflows
+    /// `usize`.
+    pub fn encode<'a>(self, string: &'a str) -> (Cow<'a, [u8]>, &'static Encoding, bool) {
+        let mut encoder = self.build();
+        let output_encoding = encoder.encoding();
+        if output_encoding == UTF_8 {
+            return (Cow::Borrowed(string.as_bytes()), output_encoding, false);
+        }
+        let bytes = string.as_bytes();
+        let valid_up_to = if output_encoding == ISO_2022_JP {
+            iso_2022_jp_ascii_valid_up_to(bytes)
+        } else {
+            ascii_valid_up_to(bytes)
+        };
+        if valid_up_to == bytes.len() {
+            return (Cow::Borrowed(bytes), output_encoding, false);
+        }
+        let mut vec: Vec<u8> = Vec::with_capacity(
+            (checked_add(
+                valid_up_to,
+                encoder.max_buffer_length_from_utf8_if_no_unmappables(
+                    string.len() - valid_up_to,
+                ),
+            ))
+                    .unwrap()
+                    .next_power_of_two()
+        );
+        vec.extend_from_slice(&bytes[..valid_up_to]);
+        let mut total_read = valid_up_to;
+        let mut total_had_errors = false;
+        loop {
+            let (result, read, had_errors) =
+                encoder.encode_from_utf8_to_vec(&string[total_read..], &mut vec, true);
+            total_read += read;
+            total_had_errors |= had_errors;
+            match result {
+                CoderResult::InputEmpty => {
+                    debug_assert_eq!(total_read, string.len());
+                    return (Cow::Owned(vec), output_encoding, total_had_errors);
+                }
+                CoderResult::OutputFull => {
+                    let needed = checked_add(
+                        encoder.pending_replacement_len(),
+                        encoder.max_buffer_length_from_utf8_if_no_unmappables(
+                            string.len() - total_read,
+                        ),
+                    );
+                    let rounded = checked_next_power_of_two(checked_add(vec.capacity(), needed))
+                        .unwrap();
+                    let additional = rounded - vec.len();
+                    vec.reserve_exact(additional);
+                }
+            }
+        }
+    }
+}
+
+/// The state an `Encoder` needs for applying an `UnmappablePolicy` other
+/// than `Ncr`.
+pub struct UnmappableHandler {
+    policy: UnmappablePolicy,
+    pending: Vec<u8>,
+    pending_pos: usize,
+    records: Option<Vec<UnmappableCharacter>>,
+    input_offset: usize,
+    char_offset: usize,
+}
+
+impl UnmappableHandler {
+    fn new(policy: UnmappablePolicy, record: bool) -> UnmappableHandler {
+        UnmappableHandler {
+            policy,
+            pending: Vec::new(),
+            pending_pos: 0,
+            records: if record { Some(Vec::new()) } else { None },
+            input_offset: 0,
+            char_offset: 0,
+        }
+    }
+
+    /// Counts the characters in `consumed`, which was consumed from the
+    /// input, if recording.
+    fn count<T: CountChars + ?Sized>(&mut self, consumed: &T) {
+        if self.records.is_some() {
+            self.char_offset += consumed.count_chars();
+        }
+    }
+
+    /// Records `unmappable`, which ended after `read` code units have been
+    /// read during the current call, if recording.
+    fn record<T: CountChars + ?Sized>(&mut self, unmappable: char, read: usize) {
+        if let Some(ref mut records) = self.records {
+            records.push(
+                UnmappableCharacter {
+                    character: unmappable,
+                    input_offset: self.input_offset + read - T::units(unmappable),
+                    char_offset: self.char_offset - 1,
+                }
+            );
+        }
+    }
+
+    /// Advances the input offset at the end of a call.
+    fn advance(&mut self, read: usize) {
+        self.input_offset += read;
+    }
+
+    /// The number of bytes of replacement output that did not fit in the
+    /// previous output buffer.
+    pub fn pending_len(&self) -> usize {
+        self.pending.len() - self.pending_pos
+    }
+
+    /// Appends the replacement for `unmappable` to the pending output.
+    /// `variant` is the encoder that reported `unmappable`.
+    fn handle(&mut self, unmappable: char, variant: &mut VariantEncoder) {
+        if self.pending_pos == self.pending.len() {
+            self.pending.clear();
+            self.pending_pos = 0;
+        }
+        let start = self.pending.len();
+        match self.policy {
+            UnmappablePolicy::Ncr => {
+                let mut ncr = [0u8; NCR_EXTRA];
+                let len = write_ncr(unmappable, &mut ncr);
+                self.pending.extend_from_slice(&ncr[..len]);
+            }
+            UnmappablePolicy::QuestionMark => self.pending.push(b'?'),
+            UnmappablePolicy::HexNcr => {
+                write!(ByteWriter(&mut self.pending), "&#x{:X};", unmappable as u32).unwrap();
+            }
+            UnmappablePolicy::Escape => {
+                let mut units = [0u16; 2];
+                for unit in unmappable.encode_utf16(&mut units).iter() {
+                    write!(ByteWriter(&mut self.pending), "\\u{:04X}", unit).unwrap();
+                }
+            }
+            UnmappablePolicy::PercentEncodedNcr => {
+                write!(ByteWriter(&mut self.pending), "%26%23{}%3B", unmappable as u32).unwrap();
+            }
+            UnmappablePolicy::Callback(ref mut callback) => {
+                callback(unmappable, &mut self.pending)
+            }
+        }
+        // In the Roman state of ISO-2022-JP, `\` and `~` mean YEN SIGN and
+        // OVERLINE.
+        if let VariantEncoder::Iso2022Jp(ref mut iso_2022_jp) = *variant {
+            if self.pending[start..].iter().any(|b| *b == b'\\' || *b == b'~') &&
+               iso_2022_jp.leave_roman_state() {
+                self.pending.splice(start..start, b"\x1B(B".iter().cloned());
+            }
+        }
+    }
+
+    /// Writes as much of the pending output to `dst` as fits and returns the
+    /// number of bytes written.
+    fn flush(&mut self, dst: &mut [u8]) -> usize {
+        let len = ::core::cmp::min(self.pending_len(), dst.len());
+        dst[..len].copy_from_slice(&self.pending[self.pending_pos..self.pending_pos + len]);
+        self.pending_pos += len;
+        len
+    }
+}
+
+macro_rules! encode_with_policy_function {
+    ($name:ident,
+     $encode_without_replacement:ident,
+     $code_unit:ty,
+     $src:ty) => (
+    /// Encodes like the corresponding method with replacement but with
+    /// unmappable characters handled by the `UnmappableHandler` of `encoder`.
+    pub fn $name(encoder: &mut Encoder,
+                 src: &$src,
+                 dst: &mut [u8],
+                 last: bool)
+                 -> (CoderResult, usize, usize, bool) {
+        let mut handler = encoder.unmappable.take().unwrap();
+        let mut had_unmappables = false;
+        let mut total_read = 0usize;
+        let mut total_written = 0usize;
+        let mut counted = 0usize;
+        let result = loop {
+            total_written += handler.flush(&mut dst[total_written..]);
+            if handler.pending_len() != 0 {
+                break CoderResult::OutputFull;
+            }
+            let (result, read, written) = encoder.$encode_without_replacement(
+                &src[total_read..],
+                &mut dst[total_written..],
+                last,
+            );
+            total_read += read;
+            total_written += written;
+            match result {
+                EncoderResult::InputEmpty => {
+                    break CoderResult::InputEmpty;
+                }
+                EncoderResult::OutputFull => {
+                    break CoderResult::OutputFull;
+                }
+                EncoderResult::Unmappable(unmappable) => {
+                    had_unmappables = true;
+                    handler.count(&src[counted..total_read]);
+                    counted = total_read;
+                    handler.record::<$src>(unmappable, total_read);
+                    // Iso2022JpEncoder is responsible for transitioning to
+                    // the ASCII state when returning with Unmappable from the
+                    // jis0208 state. `handle()` takes care of the Roman state.
+                    handler.handle(unmappable, &mut encoder.variant);
+                }
+            }
+        };
+        handler.count(&src[counted..total_read]);
+        handler.advance(total_read);
+        encoder.unmappable = Some(handler);
+        (result, total_read, total_written, had_unmappables)
+    });
+}
+
+encode_with_policy_function!(encode_from_utf8_with_policy,
+                             encode_from_utf8_without_replacement,
+                             u8,
+                             str);
+
+encode_with_policy_function!(encode_from_utf16_with_policy,
+                             encode_from_utf16_without_replacement,
+                             u16,
+                             [u16]);
+
+impl Encoder {
+    /// The number of bytes of replacement output waiting for more output
+    /// space.
+    fn pending_replacement_len(&self) -> usize {
+        match self.unmappable {
+            Some(ref handler) => handler.pending_len(),
+            None => 0,
+        }
+    }
+
+    /// Returns the `UnmappableCharacter`s recorded since the previous call to
+    /// this method if the encoder was built with
+    /// `EncoderOptions::record_unmappables()` and an empty `Vec` otherwise.
+    ///
+    /// Available to Rust only.
+    pub fn take_unmappables(&mut self) -> Vec<UnmappableCharacter> {
+        match self.unmappable {
+            Some(ref mut handler) => {
+                match handler.records {
+                    Some(ref mut records) => ::core::mem::take(records),
+                    None => Vec::new(),
+                }
+            }
+            None => Vec::new(),
+        }
+    }
+}
+
+/// A character that an encoder could not represent.
+///
+/// Obtained from `Encoding::unmappables()`,
+/// `Encoding::encode_with_unmappables()` or from an `Encoder` built with
+/// `EncoderOptions::record_unmappables()`.
+///
+/// Available to Rust only.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct UnmappableCharacter {
+    character: char,
+    input_offset: usize,
+    char_offset: usize,
+}
+
+impl UnmappableCharacter {
+    /// The character that could not be encoded.
+    pub fn character(&self) -> char {
+        self.character
+    }
+
+    /// The offset of the character counting from the start of the input
+    /// stream in the code units of the input, i.e. in bytes when encoding
+    /// from UTF-8 and in `u16`s when encoding from UTF-16.
+    pub fn input_offset(&self) -> usize {
+        self.input_offset
+    }
+
+    /// The offset of the character counting from the start of the input
+    /// stream in Unicode scalar values. (When encoding from UTF-16, an unpaired
+    /// surrogate counts as one scalar value, because it is encoded as U+FFFD.
+    /// A surrogate pair split across input buffers counts as two.)
+    pub fn char_offset(&self) -> usize {
+        self.char_offset
+    }
+}
+
+/// Counting characters in the two input types of the encoders.
+trait CountChars {
+    /// The number of characters in `self` counting unpaired surrogates as
+    /// characters.
+    fn count_chars(&self) -> usize;
+
+    /// The number of code units of this type that `c` takes.
+    fn units(c: char) -> usize;
+}
+
+impl CountChars for str {
+    fn count_chars(&self) -> usize {
+        self.chars().count()
+    }
+
+    fn units(c: char) -> usize {
+        c.len_utf8()
+    }
+}
+
+impl CountChars for [u16] {
+    fn count_chars(&self) -> usize {
+        ::core::char::decode_utf16(self.iter().cloned()).count()
+    }
+
+    fn units(c: char) -> usize {
+        c.len_utf16()
+    }
+}
+
+/// Lists the characters in `string` that `encoder` cannot encode without
+/// building the output.
+pub fn unmappables(mut encoder: Encoder, string: &str) -> Vec<UnmappableCharacter> {
+    let mut unmappables = Vec::new();
+    let mut scratch = [0u8; 1024];
+    let mut total_read = 0usize;
+    let mut char_offset = 0usize;
+    loop {
+        let (result, read, _) =
+            encoder.encode_from_utf8_without_replacement(&string[total_read..], &mut scratch, true);
+        let consumed = &string[total_read..total_read + read];
+        total_read += read;
+        char_offset += consumed.count_chars();
+        match result {
+            EncoderResult::InputEmpty => {
+                return unmappables;
+            }
+            EncoderResult::OutputFull => {}
+            EncoderResult::Unmappable(unmappable) => {
+                unmappables.push(
+                    UnmappableCharacter {
+                        character: unmappable,
+                        input_offset: total_read - unmappable.len_utf8(),
+                        char_offset: char_offset - 1,
+                    }
+                );
+            }
+        }
+    }
+}
+
+// Any copyright to the test code below this comment is dedicated to the
+// Public Domain. http://creativecommons.org/publicdomain/zero/1.0/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::testing::*;
+
+    fn decode_utf16(decoder: &mut Decoder, bytes: &[u8], chunk: usize) -> String {
+        let mut output = Vec::new();
+        let mut dst = [0u16; 2];
+        let mut chunks = bytes.chunks(chunk).peekable();
+        let mut src: &[u8] = b"";
+        loop {
+            if src.is_empty() {
+                src = match chunks.next() {
+                    Some(c) => c,
+                    None => b"",
+                };
+            }
+            let last = chunks.peek().is_none();
+            let (result, read, written, _) = decoder.decode_to_utf16(src, &mut dst, last);
+            output.extend_from_slice(&dst[..written]);
+            src = &src[read..];
+            if result == CoderResult::InputEmpty && last {
+                return String::from_utf16(&output).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_policy_skip() {
+        let (string, encoding, had_errors) = SHIFT_JIS.decoder_options()
+                                                      .malformed_policy(MalformedPolicy::Skip)
+                                                      .decode(b"a\xA0b\x83");
+        assert_eq!(string, "ab");
+        assert_eq!(encoding, SHIFT_JIS);
+        assert!(had_errors);
+    }
+
+    #[test]
+    fn test_policy_replace_with() {
+        let policy = MalformedPolicy::ReplaceWith("<?>".to_string());
+        let (string, _, _) = UTF_8.decoder_options()
+                                  .malformed_policy(policy)
+                                  .decode(b"a\xFFb\xE2\x82");
+        assert_eq!(string, "a<?>b<?>");
+    }
+
+    #[test]
+    fn test_policy_escape() {
+        let (string, _, had_errors) = UTF_8.decoder_options()
+                                           .malformed_policy(MalformedPolicy::Escape)
+                                           .decode(b"a\xFFb\xE2\x82c\xE2\x82");
+        assert_eq!(string, "a\\xFFb\\xE2\\x82c\\xE2\\x82");
+        assert!(had_errors);
+        let (string, _, _) = EUC_JP.decoder_options()
+                                   .malformed_policy(MalformedPolicy::Escape)
+                                   .decode(b"\x8E\xFF\x8Ea");
+        assert_eq!(string, "\\x8E\\xFF\\x8Ea");
+    }
+
+    #[test]
+    fn test_policy_escape_across_buffers() {
+        let bytes = b"a\xE2\x82b\xA4\xA2\xA4";
+        for chunk in 1..bytes.len() + 1 {
+            let mut decoder = EUC_JP.decoder_options()
+                                    .malformed_policy(MalformedPolicy::Escape)
+                                    .build();
+            assert_eq!(decode_utf16(&mut decoder, bytes, chunk), "a\\xE2\\x82b\u{3042}\\xA4");
+        }
+    }
+
+    #[test]
+    fn test_policy_callback() {
+        let policy = MalformedPolicy::Callback(
+            Box::new(|bytes: &[u8], out: &mut String| {
+                         out.push_str(&format!("[{}]", bytes.len()));
+                     })
+        );
+        let (string, _, _) = UTF_16LE.decoder_options()
+                                     .without_bom_handling()
+                                     .malformed_policy(policy)
+                                     .decode(b"a\x00\x00\xD8b\x00c");
+        assert_eq!(string, "a[2]b[1]");
+    }
+
+    #[test]
+    fn test_policy_bom_removal() {
+        let (string, encoding, had_errors) = UTF_8.decoder_options()
+                                                  .with_bom_removal()
+                                                  .malformed_policy(MalformedPolicy::Skip)
+                                                  .decode(b"\xEF\xBB\xBF\xEF\xBB");
+        assert_eq!(string, "");
+        assert_eq!(encoding, UTF_8);
+        assert!(had_errors);
+    }
+
+    #[test]
+    fn test_policy_small_output_buffer() {
+        let new_decoder = || {
+            UTF_16BE.decoder_options()
+                    .malformed_policy(MalformedPolicy::ReplaceWith("\u{1F4A9}\u{1F4A9}".to_string()))
+                    .build()
+        };
+        assert_eq!(
+            decode_utf16(&mut new_decoder(), b"\xDC\x00\x00a", 2),
+            "\u{1F4A9}\u{1F4A9}a"
+        );
+        let mut decoder = new_decoder();
+        let mut dst = [0u8; 4];
+        let (result, read, written, had_errors) =
+            decoder.decode_to_utf8(b"\xDC\x00\x00a", &mut dst, true);
+        assert_eq!(result, CoderResult::OutputFull);
+        assert_eq!(read, 2);
+        assert_eq!(&dst[..written], "\u{1F4A9}".as_bytes());
+        assert!(had_errors);
+        let (result, read, written, had_errors) = decoder.decode_to_utf8(b"\x00a", &mut dst, true);
+        assert_eq!(result, CoderResult::OutputFull);
+        assert_eq!(read, 0);
+        assert_eq!(&dst[..written], "\u{1F4A9}".as_bytes());
+        assert!(!had_errors);
+        let (result, read, written, _) = decoder.decode_to_utf8(b"\x00a", &mut dst, true);
+        assert_eq!(result, CoderResult::InputEmpty);
+        assert_eq!(read, 2);
+        assert_eq!(&dst[..written], b"a");
+    }
+
+    #[test]
+    fn test_decode_with_malformed_sequences() {
+        let (cow, encoding, malformed) =
+            SHIFT_JIS.decode_with_malformed_sequences(b"\xEF\xBB\xBFa\xFFb\xE3\x81");
+        assert_eq!(cow, "a\u{FFFD}b\u{FFFD}");
+        assert_eq!(encoding, UTF_8);
+        assert_eq!(malformed.len(), 2);
+        assert_eq!(malformed[0].input_range(), 4..5);
+        assert_eq!(malformed[0].bytes(), b"\xFF");
+        assert_eq!(malformed[0].output_offset(), 1);
+        assert_eq!(malformed[1].input_range(), 6..8);
+        assert_eq!(malformed[1].bytes(), b"\xE3\x81");
+        assert_eq!(malformed[1].output_offset(), 5);
+
+        let (cow, encoding, malformed) = SHIFT_JIS.decode_with_malformed_sequences(b"abc");
+        match cow {
+            Cow::Borrowed(s) => assert_eq!(s, "abc"),
+            Cow::Owned(_) => unreachable!(),
+        }
+        assert_eq!(encoding, SHIFT_JIS);
+        assert!(malformed.is_empty());
+    }
+
+    #[test]
+    fn test_record_malformed_sequences_across_buffers() {
+        let mut decoder = UTF_16LE.decoder_options()
+                                  .malformed_policy(MalformedPolicy::Escape)
+                                  .record_malformed_sequences()
+                                  .build();
+        let mut dst = [0u16; 16];
+        let (_, _, written, _) = decoder.decode_to_utf16(b"a\x00\x00", &mut dst, false);
+        assert_eq!(written, 1);
+        assert!(decoder.take_malformed_sequences().is_empty());
+        let (_, _, written, _) = decoder.decode_to_utf16(b"\xDCb\x00c", &mut dst, true);
+        assert_eq!(&dst[..written], &utf16_from_utf8("\\x00\\xDCb\\x63")[..]);
+        let malformed = decoder.take_malformed_sequences();
+        assert_eq!(malformed.len(), 2);
+        assert_eq!(malformed[0].input_range(), 2..4);
+        assert_eq!(malformed[0].bytes(), b"\x00\xDC");
+        assert_eq!(malformed[0].output_offset(), 1);
+        assert_eq!(malformed[1].input_range(), 6..7);
+        assert_eq!(malformed[1].output_offset(), 10);
+        assert!(decoder.take_malformed_sequences().is_empty());
+    }
+
+    #[test]
+    fn test_decoder_is_send_and_sync() {
+        fn assert_send_and_sync<T: Send + Sync>() {}
+        assert_send_and_sync::<Decoder>();
+        assert_send_and_sync::<DecoderOptions>();
+    }
+
+    fn encode_policy(encoding: &'static Encoding, policy: UnmappablePolicy, string: &str) -> Vec<u8> {
+        let (cow, _, had_unmappables) = encoding.encoder_options()
+                                                .unmappable_policy(policy)
+                                                .encode(string);
+        assert!(had_unmappables);
+        cow.into_owned()
+    }
+
+    #[test]
+    fn test_encoder_is_send_and_sync() {
+        fn assert_send_and_sync<T: Send + Sync>() {}
+        assert_send_and_sync::<Encoder>();
+        assert_send_and_sync::<EncoderOptions>();
+    }
+
+    #[test]
+    fn test_unmappable_policies() {
+        let string = "a\u{1F4A9}b\u{3042}";
+        assert_eq!(
+            &encode_policy(EUC_KR, UnmappablePolicy::Ncr, string)[..],
+            b"a&#128169;b\xAA\xA2"
+        );
+        assert_eq!(
+            &encode_policy(WINDOWS_1252, UnmappablePolicy::QuestionMark, string)[..],
+            b"a?b?"
+        );
+        assert_eq!(
+            &encode_policy(WINDOWS_1252, UnmappablePolicy::HexNcr, string)[..],
+            b"a&#x1F4A9;b&#x3042;"
+        );
+        assert_eq!(
+            &encode_policy(WINDOWS_1252, UnmappablePolicy::Escape, string)[..],
+            b"a\\uD83D\\uDCA9b\\u3042"
+        );
+        assert_eq!(
+            &encode_policy(WINDOWS_1252, UnmappablePolicy::PercentEncodedNcr, string)[..],
+            b"a%26%23128169%3Bb%26%2312354%3B"
+        );
+        let policy = UnmappablePolicy::Callback(
+            Box::new(|c: char, out: &mut Vec<u8>| {
+                         out.extend_from_slice(format!("<{}>", c.len_utf8()).as_bytes());
+                     })
+        );
+        assert_eq!(&encode_policy(WINDOWS_1252, policy, string)[..], b"a<4>b<3>");
+    }
+
+    #[test]
+    fn test_unmappable_policy_borrows() {
+        let (cow, encoding, had_unmappables) =
+            UTF_16LE.encoder_options()
+                    .unmappable_policy(UnmappablePolicy::QuestionMark)
+                    .encode("\u{1F4A9}");
+        match cow {
+            Cow::Borrowed(_) => {}
+            Cow::Owned(_) => unreachable!(),
+        }
+        assert_eq!(encoding, UTF_8);
+        assert!(!had_unmappables);
+    }
+
+    #[test]
+    fn test_unmappable_policy_iso_2022_jp() {
+        assert_eq!(
+            &encode_policy(ISO_2022_JP, UnmappablePolicy::HexNcr, "\u{3042}\u{1F4A9}\u{3042}")[..],
+            b"\x1B$B$\"\x1B(B&#x1F4A9;\x1B$B$\"\x1B(B"
+        );
+    }
+
+    #[test]
+    fn test_unmappable_policy_iso_2022_jp_roman() {
+        let bytes = encode_policy(ISO_2022_JP, UnmappablePolicy::Escape, "\u{A5}\u{1F4A9}");
+        assert_eq!(&bytes[..], &b"\x1B(J\x5C\x1B(B\\uD83D\\uDCA9"[..]);
+        let (cow, had_errors) = ISO_2022_JP.decode_without_bom_handling(&bytes);
+        assert!(!had_errors);
+        assert_eq!(&cow[..], "\u{A5}\\uD83D\\uDCA9");
+
+        // Replacements without `\` and `~` don't leave the Roman state.
+        assert_eq!(
+            &encode_policy(ISO_2022_JP, UnmappablePolicy::HexNcr, "\u{A5}\u{1F4A9}\u{A5}")[..],
+            b"\x1B(J\x5C&#x1F4A9;\x5C\x1B(B"
+        );
+        let callback: UnmappableCallback = Box::new(|_, out: &mut Vec<u8>| out.push(b'~'));
+        let bytes = encode_policy(ISO_2022_JP, UnmappablePolicy::Callback(callback), "\u{A5}\u{1F4A9}");
+        assert_eq!(&bytes[..], &b"\x1B(J\x5C\x1B(B~"[..]);
+    }
+
+    #[test]
+    fn test_unmappables() {
+        let unmappables = EUC_KR.unmappables("a\u{1F4A9}\u{AC00}\u{3042}\u{00E4}b\u{0416}");
+        let found: Vec<(char, usize, usize)> =
+            unmappables.iter()
+                       .map(|u| (u.character(), u.input_offset(), u.char_offset()))
+                       .collect();
+        assert_eq!(found, vec![('\u{1F4A9}', 1, 1), ('\u{00E4}', 11, 4)]);
+        assert!(UTF_16LE.unmappables("\u{1F4A9}").is_empty());
+        assert!(EUC_KR.unmappables("abc").is_empty());
+    }
+
+    #[test]
+    fn test_encode_with_unmappables() {
+        let (cow, encoding, unmappables) = WINDOWS_1252.encode_with_unmappables("\u{3042}a\u{20AC}");
+        assert_eq!(&cow[..], b"&#12354;a\x80");
+        assert_eq!(encoding, WINDOWS_1252);
+        assert_eq!(unmappables.len(), 1);
+        assert_eq!(unmappables[0].character(), '\u{3042}');
+        assert_eq!(unmappables[0].input_offset(), 0);
+        assert_eq!(unmappables[0].char_offset(), 0);
+        let (_, _, unmappables) = WINDOWS_1252.encode_with_unmappables("a\u{20AC}");
+        assert!(unmappables.is_empty());
+    }
+
+    #[test]
+    fn test_record_unmappables_across_buffers() {
+        let mut encoder = WINDOWS_1252.encoder_options()
+                                      .unmappable_policy(UnmappablePolicy::QuestionMark)
+                                      .record_unmappables()
+                                      .build();
+        let mut dst = [0u8; 16];
+        let (_, _, written, _) = encoder.encode_from_utf16(&[0x0061u16, 0x3042u16], &mut dst, false);
+        assert_eq!(&dst[..written], b"a?");
+        let (_, _, written, _) =
+            encoder.encode_from_utf16(&[0xD83Du16, 0xDCA9u16, 0x00E4u16, 0x3042u16], &mut dst, true);
+        assert_eq!(&dst[..written], b"?\xE4?");
+        let found: Vec<(char, usize, usize)> =
+            encoder.take_unmappables()
+                   .iter()
+                   .map(|u| (u.character(), u.input_offset(), u.char_offset()))
+                   .collect();
+        assert_eq!(
+            found,
+            vec![('\u{3042}', 1, 1), ('\u{1F4A9}', 2, 2), ('\u{3042}', 5, 4)]
+        );
+        assert!(encoder.take_unmappables().is_empty());
+    }
+
+    #[test]
+    fn test_unmappable_policy_small_output_buffer() {
+        let mut encoder = SHIFT_JIS.encoder_options()
+                                   .unmappable_policy(UnmappablePolicy::PercentEncodedNcr)
+                                   .build();
+        let src = [0x0061u16, 0xD83Du16, 0xDCA9u16, 0x0062u16];
+        let mut output = Vec::new();
+        let mut dst = [0u8; 3];
+        let mut total_read = 0usize;
+        loop {
+            let (result, read, written, _) =
+                encoder.encode_from_utf16(&src[total_read..], &mut dst, true);
+            total_read += read;
+            output.extend_from_slice(&dst[..written]);
+            if result == CoderResult::InputEmpty {
+                break;
+            }
+        }
+        assert_eq!(&output[..], b"a%26%23128169%3Bb");
+    }
+}
@@ -0,0 +1,411 @@
+// Copyright 2017 Mozilla Foundation. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! This module implements the HTML Standard's algorithm to "prescan a byte
+//! stream to determine its encoding", i.e. looking for `<meta charset>` and
+//! `<meta http-equiv="Content-Type" content="...">` near the start of an
+//! HTML document.
+
+use super::*;
+
+/// The number of bytes at the start of the stream that the prescan looks at.
+const PRESCAN_LENGTH: usize = 1024;
+
+fn is_html_whitespace(byte: u8) -> bool {
+    match byte {
+        0x09u8 | 0x0Au8 | 0x0Cu8 | 0x0Du8 | 0x20u8 => true,
+        _ => false,
+    }
+}
+
+/// Returns `true` if `bytes` starts with `prefix` compared ASCII
+/// case-insensitively. `prefix` must be lower case.
+fn starts_with_ignore_case(bytes: &[u8], prefix: &[u8]) -> bool {
+    bytes.len() >= prefix.len() &&
+    bytes
+        .iter()
+        .zip(prefix.iter())
+        .all(|(a, b)| a.to_ascii_lowercase() == *b)
+}
+
+/// The outcome of looking at the attributes of a `meta` tag.
+enum Charset {
+    /// No attribute declared an encoding.
+    Null,
+    /// A `charset` attribute had a value that isn't a label.
+    Failure,
+    /// An encoding was declared.
+    Found(&'static Encoding),
+}
+
+/// The state of a prescan over a bounded byte slice.
+struct Prescan<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Prescan<'a> {
+    fn new(bytes: &'a [u8]) -> Prescan<'a> {
+        let end = ::core::cmp::min(bytes.len(), PRESCAN_LENGTH);
+        Prescan {
+            bytes: &bytes[..end],
+            position: 0,
+        }
+    }
+
+    fn rest(&self) -> &'a [u8] {
+        &self.bytes[self.position..]
+    }
+
+    /// Returns the byte at the current position or `None` if the position is
+    /// past the end, in which case the prescan is aborted.
+    fn byte(&self) -> Option<u8> {
+        self.bytes.get(self.position).cloned()
+    }
+
+    fn run(&mut self) -> Option<&'static Encoding> {
+        while self.position < self.bytes.len() {
+            let rest = self.rest();
+            if rest.starts_with(b"<!--") {
+                // Skip to the end of the comment. The `--` of the `-->` may
+                // overlap the `<!--`.
+                match rest[2..].windows(3).position(|w| w == b"-->") {
+                    Some(offset) => {
+                        self.position += 2 + offset + 2;
+                    }
+                    None => {
+                        return None;
+                    }
+                }
+            } else if starts_with_ignore_case(rest, b"<meta") && rest.len() > 5 &&
+                      (is_html_whitespace(rest[5]) || rest[5] == b'/') {
+                self.position += 6;
+                match self.meta() {
+                    Some(Some(encoding)) => {
+                        return Some(encoding);
+                    }
+                    Some(None) => {}
+                    None => {
+                        return None;
+                    }
+                }
+            } else if rest.len() > 2 && rest[0] == b'<' &&
+                      (rest[1].is_ascii_alphabetic() ||
+                       (rest[1] == b'/' && rest[2].is_ascii_alphabetic())) {
+                // A start or end tag: skip its name and its attributes.
+                match rest
+                          .iter()
+                          .position(|b| is_html_whitespace(*b) || *b == b'>') {
+                    Some(offset) => {
+                        self.position += offset;
+                    }
+                    None => {
+                        return None;
+                    }
+                }
+                loop {
+                    match self.attribute() {
+                        Some(Some(_)) => {}
+                        Some(None) => {
+                            break;
+                        }
+                        None => {
+                            return None;
+                        }
+                    }
+                }
+            } else if rest.starts_with(b"<!") || rest.starts_with(b"</") ||
+                      rest.starts_with(b"<?") {
+                match rest[2..].iter().position(|b| *b == b'>') {
+                    Some(offset) => {
+                        self.position += 2 + offset;
+                    }
+                    None => {
+                        return None;
+                    }
+                }
+            }
+            self.position += 1;
+        }
+        None
+    }
+
+    /// Processes the attributes of a `meta` tag. Returns `None` if the
+    /// prescan is aborted, `Some(None)` if the prescan continues and
+    /// `Some(Some(encoding))` if an encoding was found.
+    fn meta(&mut self) -> Option<Option<&'static Encoding>> {
+        let mut names: Vec<Vec<u8>> = Vec::new();
+        let mut got_pragma = false;
+        let mut need_pragma: Option<bool> = None;
+        let mut charset = Charset::Null;
+        while let Some((name, value)) = self.attribute()? {
+            if names.contains(&name) {
+                continue;
+            }
+            match &name[..] {
+                b"http-equiv" if value == b"content-type" => {
+                    got_pragma = true;
+                }
+                b"content" => {
+                    if let Charset::Null = charset {
+                        if let Some(encoding) = extract_from_content(&value) {
+                            charset = Charset::Found(encoding);
+                            need_pragma = Some(true);
+                        }
+                    }
+                }
+                b"charset" => {
+                    charset = match Encoding::for_label(&value) {
+                        Some(encoding) => Charset::Found(encoding),
+                        None => Charset::Failure,
+                    };
+                    need_pragma = Some(false);
+                }
+                _ => {}
+            }
+            names.push(name);
+        }
+        match need_pragma {
+            None => {
+                return Some(None);
+            }
+            Some(true) if !got_pragma => {
+                return Some(None);
+            }
+            _ => {}
+        }
+        match charset {
+            Charset::Found(encoding) => {
+                if encoding == UTF_16BE || encoding == UTF_16LE {
+                    Some(Some(UTF_8))
+                } else if encoding == X_USER_DEFINED {
+                    Some(Some(WINDOWS_1252))
+                } else {
+                    Some(Some(encoding))
+                }
+            }
+            _ => Some(None),
+        }
+    }
+
+    /// Implements "get an attribute". Returns `None` if the prescan is
+    /// aborted, `Some(None)` if there are no more attributes in the tag and
+    /// `Some(Some((name, value)))` otherwise. Names and values are
+    /// ASCII-lowercased.
+    fn attribute(&mut self) -> Option<Option<(Vec<u8>, Vec<u8>)>> {
+        loop {
+            let byte = self.byte()?;
+            if is_html_whitespace(byte) || byte == b'/' {
+                self.position += 1;
+                continue;
+            }
+            if byte == b'>' {
+                return Some(None);
+            }
+            break;
+        }
+        let mut name = Vec::new();
+        loop {
+            let byte = self.byte()?;
+            if byte == b'=' && !name.is_empty() {
+                self.position += 1;
+                return self.attribute_value(name);
+            }
+            if is_html_whitespace(byte) {
+                break;
+            }
+            if byte == b'/' || byte == b'>' {
+                return Some(Some((name, Vec::new())));
+            }
+            name.push(byte.to_ascii_lowercase());
+            self.position += 1;
+        }
+        while is_html_whitespace(self.byte()?) {
+            self.position += 1;
+        }
+        if self.byte()? != b'=' {
+            return Some(Some((name, Vec::new())));
+        }
+        self.position += 1;
+        self.attribute_value(name)
+    }
+
+    /// The part of "get an attribute" after the `=`.
+    fn attribute_value(&mut self, name: Vec<u8>) -> Option<Option<(Vec<u8>, Vec<u8>)>> {
+        let mut value = Vec::new();
+        while is_html_whitespace(self.byte()?) {
+            self.position += 1;
+        }
+        let byte = self.byte()?;
+        if byte == b'"' || byte == b'\'' {
+            loop {
+                self.position += 1;
+                let b = self.byte()?;
+                if b == byte {
+                    self.position += 1;
+                    return Some(Some((name, value)));
+                }
+                value.push(b.to_ascii_lowercase());
+            }
+        }
+        if byte == b'>' {
+            return Some(Some((name, value)));
+        }
+        loop {
+            let b = self.byte()?;
+            if is_html_whitespace(b) || b == b'>' {
+                return Some(Some((name, value)));
+            }
+            value.push(b.to_ascii_lowercase());
+            self.position += 1;
+        }
+    }
+}
+
+/// Implements the HTML Standard's algorithm for extracting a character
+/// encoding from the value of the `content` attribute of a `meta` element.
+fn extract_from_content(content: &[u8]) -> Option<&'static Encoding> {
+    let mut position = 0usize;
+    loop {
+        let offset = content[position..]
+            .windows(7)
+            .position(|w| starts_with_ignore_case(w, b"charset"))?;
+        position += offset + 7;
+        while position < content.len() && is_html_whitespace(content[position]) {
+            position += 1;
+        }
+        if position < content.len() && content[position] == b'=' {
+            position += 1;
+            break;
+        }
+    }
+    while position < content.len() && is_html_whitespace(content[position]) {
+        position += 1;
+    }
+    let rest = &content[position..];
+    match rest.first() {
+        None => None,
+        Some(&quote) if quote == b'"' || quote == b'\'' => {
+            let end = rest[1..].iter().position(|b| *b == quote)?;
+            Encoding::for_label(&rest[1..end + 1])
+        }
+        Some(_) => {
+            let end = rest.iter()
+                .position(|b| is_html_whitespace(*b) || *b == b';')
+                .unwrap_or(rest.len());
+            Encoding::for_label(&rest[..end])
+        }
+    }
+}
+
+/// Runs the HTML Standard's algorithm to "prescan a byte stream to determine
+/// its encoding" over the first 1024 bytes of `bytes`.
+///
+/// Returns the encoding declared by the first `<meta charset>` or
+/// `<meta http-equiv="Content-Type" content="...; charset=...">` that is
+/// found or `None` if no encoding declaration is found.
+///
+/// As required by the HTML Standard, a declaration of UTF-16BE or UTF-16LE
+/// results in `UTF_8` and a declaration of x-user-defined results in
+/// `WINDOWS_1252`.
+///
+/// This function doesn't look for a BOM. Callers that implement the whole
+/// HTML encoding sniffing algorithm should check for a BOM (e.g. with
+/// `Encoding::for_bom()`) before calling this function.
+///
+/// Available to Rust only.
+pub fn prescan_html(bytes: &[u8]) -> Option<&'static Encoding> {
+    Prescan::new(bytes).run()
+}
+
+// Any copyright to the test code below this comment is dedicated to the
+// Public Domain. http://creativecommons.org/publicdomain/zero/1.0/
+
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+
+    #[test]
+    fn test_prescan_charset() {
+        assert_eq!(prescan_html(b"<meta charset=shift_jis>"), Some(SHIFT_JIS));
+        assert_eq!(prescan_html(b"<META CHARSET='KOI8-R'>"), Some(KOI8_R));
+        assert_eq!(prescan_html(b"<meta charset=\" utf-8 \">"), Some(UTF_8));
+        assert_eq!(prescan_html(b"<!DOCTYPE html><html><head><meta charset=latin1>"),
+                   Some(WINDOWS_1252));
+        assert_eq!(prescan_html(b"<meta/charset=gbk>"), Some(GBK));
+        assert_eq!(prescan_html(b"<meta charset=bogus>"), None);
+        assert_eq!(prescan_html(b"<meta charset=bogus><meta charset=big5>"),
+                   Some(BIG5));
+        assert_eq!(prescan_html(b"<meta charset=big5 charset=gbk>"), Some(BIG5));
+        assert_eq!(prescan_html(b"<html>no declaration</html>"), None);
+        assert_eq!(prescan_html(b""), None);
+    }
+
+    #[test]
+    fn test_prescan_http_equiv() {
+        assert_eq!(prescan_html(b"<meta http-equiv=\"Content-Type\" content=\"text/html; charset=EUC-JP\">"),
+                   Some(EUC_JP));
+        assert_eq!(prescan_html(b"<meta content='text/html;charset=windows-1251' http-equiv=content-type>"),
+                   Some(WINDOWS_1251));
+        assert_eq!(prescan_html(b"<meta content=\"text/html; charset='iso-8859-2'\" http-equiv=Content-Type>"),
+                   Some(ISO_8859_2));
+        // No pragma.
+        assert_eq!(prescan_html(b"<meta content=\"text/html; charset=EUC-JP\">"),
+                   None);
+        // `charset` takes precedence over `content` and needs no pragma.
+        assert_eq!(prescan_html(b"<meta charset=euc-kr content=\"text/html; charset=EUC-JP\">"),
+                   Some(EUC_KR));
+        assert_eq!(prescan_html(b"<meta content=\"text/html; charset=koi8-r\" http-equiv=\"Content-Type\" charset=\"windows-1251\">"),
+                   Some(WINDOWS_1251));
+        assert_eq!(prescan_html(b"<meta content=\"text/html; charset=koi8-r\" http-equiv=\"Content-Type\" charset=bogus>"),
+                   None);
+        assert_eq!(prescan_html(b"<meta http-equiv=content-type content=\"charset; charset = big5\">"),
+                   Some(BIG5));
+        assert_eq!(prescan_html(b"<meta http-equiv=content-type content=\"charset='big5\">"),
+                   None);
+    }
+
+    #[test]
+    fn test_prescan_quirks() {
+        assert_eq!(prescan_html(b"<meta charset=utf-16>"), Some(UTF_8));
+        assert_eq!(prescan_html(b"<meta charset=utf-16be>"), Some(UTF_8));
+        assert_eq!(prescan_html(b"<meta charset=x-user-defined>"),
+                   Some(WINDOWS_1252));
+    }
+
+    #[test]
+    fn test_prescan_skipping() {
+        assert_eq!(prescan_html(b"<!-- <meta charset=gbk> --><meta charset=big5>"),
+                   Some(BIG5));
+        assert_eq!(prescan_html(b"<!--><meta charset=big5>"), Some(BIG5));
+        assert_eq!(prescan_html(b"<!-- <meta charset=gbk>"), None);
+        assert_eq!(prescan_html(b"<title data-x='<meta charset=gbk>'><meta charset=big5>"),
+                   Some(BIG5));
+        assert_eq!(prescan_html(b"<?xml version='1.0'?><!DOCTYPE html></p><meta charset=big5>"),
+                   Some(BIG5));
+        assert_eq!(prescan_html(b"<metacharset=big5>"), None);
+        assert_eq!(prescan_html(b"<meta charset=big5"), None);
+    }
+
+    #[test]
+    fn test_prescan_limit() {
+        let mut bytes = Vec::new();
+        bytes.resize(1000, b' ');
+        bytes.extend_from_slice(b"<meta charset=big5>");
+        assert_eq!(prescan_html(&bytes), Some(BIG5));
+        bytes.clear();
+        bytes.resize(1024, b' ');
+        bytes.extend_from_slice(b"<meta charset=big5>");
+        assert_eq!(prescan_html(&bytes), None);
+        bytes.clear();
+        bytes.resize(1010, b' ');
+        bytes.extend_from_slice(b"<meta charset=big5>");
+        assert_eq!(prescan_html(&bytes), None);
+    }
+}
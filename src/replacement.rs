@@ -19,6 +19,27 @@ impl ReplacementDecoder {
         VariantDecoder::Replacement(ReplacementDecoder { emitted: false })
     }
 
+    /// Writes whether the REPLACEMENT CHARACTER has already been emitted.
+    #[cfg(feature = "alloc")]
+    pub fn snapshot_state(&self, state: &mut Vec<u8>) -> bool {
+        state.push(if self.emitted { 1 } else { 0 });
+        true
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn restore_state<'a>(&mut self, state: &'a [u8]) -> Option<&'a [u8]> {
+        match state {
+            [0] => {}
+            [1] => {
+                self.emitted = true;
+            }
+            _ => {
+                return None;
+            }
+        }
+        Some(&state[1..])
+    }
+
     pub fn max_utf16_buffer_length(&self, _u16_length: usize) -> Option<usize> {
         Some(1)
     }
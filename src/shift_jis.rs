@@ -24,6 +24,23 @@ impl ShiftJisDecoder {
         VariantDecoder::ShiftJis(ShiftJisDecoder { lead: None })
     }
 
+    pub fn has_pending_state(&self) -> bool {
+        self.lead.is_some()
+    }
+
+    /// Writes the lead byte, if any.
+    #[cfg(feature = "alloc")]
+    pub fn snapshot_state(&self, state: &mut Vec<u8>) -> bool {
+        if let Some(lead_minus_offset) = self.lead {
+            if lead_minus_offset <= (0x9F - 0x81) {
+                state.push(lead_minus_offset + 0x81);
+            } else {
+                state.push(lead_minus_offset + 0xC1);
+            }
+        }
+        true
+    }
+
     fn plus_one_if_lead(&self, byte_length: usize) -> Option<usize> {
         byte_length.checked_add(
             match self.lead {
@@ -361,6 +378,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_shift_jis_decode_all() {
         let input = include_bytes!("test_data/shift_jis_in.txt");
         let expectation = include_str!("test_data/shift_jis_in_ref.txt");
@@ -370,6 +388,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_shift_jis_encode_all() {
         let input = include_str!("test_data/shift_jis_out.txt");
         let expectation = include_bytes!("test_data/shift_jis_out_ref.txt");
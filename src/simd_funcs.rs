@@ -24,8 +24,8 @@ extern "platform-intrinsic" {
 
 #[inline(always)]
 pub unsafe fn load16_unaligned(ptr: *const u8) -> u8x16 {
-    let mut simd = ::std::mem::uninitialized();
-    ::std::ptr::copy_nonoverlapping(ptr, &mut simd as *mut u8x16 as *mut u8, 16);
+    let mut simd = ::core::mem::uninitialized();
+    ::core::ptr::copy_nonoverlapping(ptr, &mut simd as *mut u8x16 as *mut u8, 16);
     simd
 }
 
@@ -36,7 +36,7 @@ pub unsafe fn load16_aligned(ptr: *const u8) -> u8x16 {
 
 #[inline(always)]
 pub unsafe fn store16_unaligned(ptr: *mut u8, s: u8x16) {
-    ::std::ptr::copy_nonoverlapping(&s as *const u8x16 as *const u8, ptr, 16);
+    ::core::ptr::copy_nonoverlapping(&s as *const u8x16 as *const u8, ptr, 16);
 }
 
 #[inline(always)]
@@ -46,8 +46,8 @@ pub unsafe fn store16_aligned(ptr: *mut u8, s: u8x16) {
 
 #[inline(always)]
 pub unsafe fn load8_unaligned(ptr: *const u16) -> u16x8 {
-    let mut simd = ::std::mem::uninitialized();
-    ::std::ptr::copy_nonoverlapping(ptr as *const u8, &mut simd as *mut u16x8 as *mut u8, 16);
+    let mut simd = ::core::mem::uninitialized();
+    ::core::ptr::copy_nonoverlapping(ptr as *const u8, &mut simd as *mut u16x8 as *mut u8, 16);
     simd
 }
 
@@ -58,7 +58,7 @@ pub unsafe fn load8_aligned(ptr: *const u16) -> u16x8 {
 
 #[inline(always)]
 pub unsafe fn store8_unaligned(ptr: *mut u16, s: u16x8) {
-    ::std::ptr::copy_nonoverlapping(&s as *const u16x8 as *const u8, ptr as *mut u8, 16);
+    ::core::ptr::copy_nonoverlapping(&s as *const u16x8 as *const u8, ptr as *mut u8, 16);
 }
 
 #[inline(always)]
@@ -70,7 +70,7 @@ pub unsafe fn store8_aligned(ptr: *mut u16, s: u16x8) {
 #[inline(always)]
 pub fn is_ascii(s: u8x16) -> bool {
     unsafe {
-        let signed: i8x16 = ::std::mem::transmute_copy(&s);
+        let signed: i8x16 = ::core::mem::transmute_copy(&s);
         x86_mm_movemask_epi8(signed) == 0
     }
 }
@@ -80,7 +80,7 @@ pub fn is_ascii(s: u8x16) -> bool {
 #[inline(always)]
 pub fn check_ascii(s: u8x16) -> Option<usize> {
     let mask = unsafe {
-        let signed: i8x16 = ::std::mem::transmute_copy(&s);
+        let signed: i8x16 = ::core::mem::transmute_copy(&s);
         x86_mm_movemask_epi8(signed)
     };
     if mask == 0 {
@@ -108,7 +108,7 @@ pub fn unpack(s: u8x16) -> (u16x8, u16x8) {
             u8x16::splat(0),
             [8, 24, 9, 25, 10, 26, 11, 27, 12, 28, 13, 29, 14, 30, 15, 31],
         );
-        (::std::mem::transmute_copy(&first), ::std::mem::transmute_copy(&second))
+        (::core::mem::transmute_copy(&first), ::core::mem::transmute_copy(&second))
     }
 }
 
@@ -122,14 +122,26 @@ pub unsafe fn pack_basic_latin(a: u16x8, b: u16x8) -> Option<u8x16> {
     // Sigh. Hence, check first.
     let above_ascii = u16x8::splat(0x80);
     if a.lt(above_ascii).all() && b.lt(above_ascii).all() {
-        let first: i16x8 = ::std::mem::transmute_copy(&a);
-        let second: i16x8 = ::std::mem::transmute_copy(&b);
+        let first: i16x8 = ::core::mem::transmute_copy(&a);
+        let second: i16x8 = ::core::mem::transmute_copy(&b);
         Some(x86_mm_packus_epi16(first, second))
     } else {
         None
     }
 }
 
+/// vec_all_lt in AltiVec.
+#[inline(always)]
+pub fn is_basic_latin(s: u16x8) -> bool {
+    s.lt(u16x8::splat(0x80)).all()
+}
+
+/// vec_all_lt in AltiVec.
+#[inline(always)]
+pub fn is_utf16_latin1(s: u16x8) -> bool {
+    s.lt(u16x8::splat(0x100)).all()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -238,7 +250,7 @@ mod tests {
                                0x72, 0x73, 0x74, 0x75, 0x76];
         let mut alu = 0u64;
         unsafe {
-            ::std::ptr::copy_nonoverlapping(input.as_ptr(), &mut alu as *mut u64 as *mut u8, 8);
+            ::core::ptr::copy_nonoverlapping(input.as_ptr(), &mut alu as *mut u64 as *mut u8, 8);
         }
         let masked = alu & 0x8080808080808080;
         assert_eq!(masked.trailing_zeros(), 39);
@@ -369,7 +369,7 @@ impl SingleByteEncoder {
                                     }
                                     // The next code unit is a low surrogate.
                                     let astral: char = unsafe {
-                                        ::std::mem::transmute(
+                                        ::core::mem::transmute(
                                             ((non_ascii as u32) << 10) + second -
                                             (((0xD800u32 << 10) - 0x10000u32) + 0xDC00u32)
                                         )
@@ -385,7 +385,7 @@ impl SingleByteEncoder {
                                             converted);
                                 }
                                 let thirty_two = non_ascii as u32;
-                                let bmp: char = unsafe { ::std::mem::transmute(thirty_two) };
+                                let bmp: char = unsafe { ::core::mem::transmute(thirty_two) };
                                 return (EncoderResult::Unmappable(bmp),
                                         converted + 1, // +1 `for non_ascii`
                                         converted);
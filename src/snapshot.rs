@@ -0,0 +1,603 @@
+// Copyright 2017 Mozilla Foundation. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! This module implements checkpointing `Decoder`s and `Encoder`s in the
+//! middle of a stream and resuming from the checkpoint later, possibly in
+//! another process.
+//!
+//! The variant-specific part of a snapshot consists of the bytes of the
+//! incomplete byte sequence that the decoder has consumed without producing
+//! output for (preceded by the modal state for ISO-2022-JP). A snapshot is
+//! restored by decoding those bytes with a fresh decoder, so the format does
+//! not depend on how the decoders represent their state internally and a
+//! malicious snapshot cannot put a decoder into a state that decoding some
+//! input couldn't.
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::*;
+
+/// The version of the snapshot format. Incremented whenever the format
+/// changes incompatibly.
+const SNAPSHOT_VERSION: u8 = 1;
+
+fn encoding_for_name(name: &str) -> Option<&'static Encoding> {
+    // UTF-8 is not in the array, since `Encoding::for_name()` checks for it
+    // first.
+    if name == "UTF-8" {
+        return Some(UTF_8);
+    }
+    ENCODINGS_SORTED_BY_NAME
+        .iter()
+        .find(|encoding| encoding.name() == name)
+        .cloned()
+}
+
+fn life_cycle_to_u8(life_cycle: &DecoderLifeCycle) -> u8 {
+    match *life_cycle {
+        DecoderLifeCycle::AtStart => 0,
+        DecoderLifeCycle::AtUtf8Start => 1,
+        DecoderLifeCycle::AtUtf16BeStart => 2,
+        DecoderLifeCycle::AtUtf16LeStart => 3,
+        DecoderLifeCycle::SeenUtf8First => 4,
+        DecoderLifeCycle::SeenUtf8Second => 5,
+        DecoderLifeCycle::SeenUtf16BeFirst => 6,
+        DecoderLifeCycle::SeenUtf16LeFirst => 7,
+        DecoderLifeCycle::ConvertingWithPendingBB => 8,
+        DecoderLifeCycle::Converting => 9,
+        DecoderLifeCycle::Finished => 10,
+    }
+}
+
+fn life_cycle_from_u8(life_cycle: u8, encoding: &'static Encoding) -> Option<DecoderLifeCycle> {
+    Some(
+        match life_cycle {
+            0 => DecoderLifeCycle::AtStart,
+            1 if encoding == UTF_8 => DecoderLifeCycle::AtUtf8Start,
+            2 if encoding == UTF_16BE => DecoderLifeCycle::AtUtf16BeStart,
+            3 if encoding == UTF_16LE => DecoderLifeCycle::AtUtf16LeStart,
+            4 => DecoderLifeCycle::SeenUtf8First,
+            5 => DecoderLifeCycle::SeenUtf8Second,
+            6 => DecoderLifeCycle::SeenUtf16BeFirst,
+            7 => DecoderLifeCycle::SeenUtf16LeFirst,
+            8 => DecoderLifeCycle::ConvertingWithPendingBB,
+            9 => DecoderLifeCycle::Converting,
+            10 => DecoderLifeCycle::Finished,
+            _ => {
+                return None;
+            }
+        },
+    )
+}
+
+/// Splits the common prefix of the byte serialization into the version,
+/// the encoding name and the rest.
+fn split_name(bytes: &[u8]) -> Option<(u8, String, &[u8])> {
+    if bytes.len() < 2 || bytes[0] != SNAPSHOT_VERSION {
+        return None;
+    }
+    let name_end = 2 + bytes[1] as usize;
+    if bytes.len() < name_end {
+        return None;
+    }
+    match ::core::str::from_utf8(&bytes[2..name_end]) {
+        Ok(name) => Some((bytes[0], name.to_string(), &bytes[name_end..])),
+        Err(_) => None,
+    }
+}
+
+fn push_name(bytes: &mut Vec<u8>, name: &str) {
+    bytes.push(name.len() as u8);
+    bytes.extend_from_slice(name.as_bytes());
+}
+
+fn push_u64(bytes: &mut Vec<u8>, value: u64) {
+    for i in (0..8).rev() {
+        bytes.push((value >> (8 * i)) as u8);
+    }
+}
+
+/// Reads a big-endian `u64` from the first eight bytes of `bytes`.
+fn read_u64(bytes: &[u8]) -> u64 {
+    bytes[..8]
+        .iter()
+        .fold(0u64, |value, byte| (value << 8) | u64::from(*byte))
+}
+
+/// A checkpoint of the state of a `Decoder` in the middle of a stream.
+///
+/// Obtained from `Decoder::snapshot()` and turned back into a `Decoder` that
+/// continues the stream using `Decoder::from_snapshot()`. The snapshot
+/// records the encoding, the progress of BOM sniffing or removal, how much
+/// of the input seen so far was ASCII (for `Decoder::switch_encoding()`) and
+/// the bytes of an incomplete character (or escape sequence) at the end of
+/// the input seen so far.
+///
+/// Snapshots can be persisted using `to_bytes()` and `from_bytes()` or, with
+/// the `serde` feature enabled, using Serde. The format is versioned and
+/// stays readable by later versions of this crate.
+///
+/// Available to Rust only.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DecoderSnapshot {
+    version: u8,
+    encoding: String,
+    life_cycle: u8,
+    seen_non_ascii: bool,
+    ascii_prefix: u64,
+    iso_2022_jp_ascii_prefix: u64,
+    state: Vec<u8>,
+}
+
+impl DecoderSnapshot {
+    /// The version of the snapshot format.
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    /// The `Encoding` the snapshotted `Decoder` was for (after BOM sniffing)
+    /// or `None` if the snapshot names an unknown encoding.
+    pub fn encoding(&self) -> Option<&'static Encoding> {
+        encoding_for_name(&self.encoding)
+    }
+
+    /// Serializes the snapshot into a compact byte representation.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(20 + self.encoding.len() + self.state.len());
+        bytes.push(self.version);
+        push_name(&mut bytes, &self.encoding);
+        bytes.push(self.life_cycle);
+        bytes.push(self.seen_non_ascii as u8);
+        push_u64(&mut bytes, self.ascii_prefix);
+        push_u64(&mut bytes, self.iso_2022_jp_ascii_prefix);
+        bytes.extend_from_slice(&self.state);
+        bytes
+    }
+
+    /// Deserializes a snapshot serialized by `to_bytes()` or returns `None`
+    /// if `bytes` is not in a format supported by this version of the crate.
+    ///
+    /// The returned snapshot has not been validated in full yet;
+    /// `Decoder::from_snapshot()` does that.
+    pub fn from_bytes(bytes: &[u8]) -> Option<DecoderSnapshot> {
+        match split_name(bytes) {
+            Some((version, encoding, rest)) if rest.len() >= 18 && rest[1] <= 1 => {
+                Some(
+                    DecoderSnapshot {
+                        version,
+                        encoding,
+                        life_cycle: rest[0],
+                        seen_non_ascii: rest[1] == 1,
+                        ascii_prefix: read_u64(&rest[2..]),
+                        iso_2022_jp_ascii_prefix: read_u64(&rest[10..]),
+                        state: rest[18..].to_vec(),
+                    },
+                )
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A checkpoint of the state of an `Encoder` in the middle of a stream.
+///
+/// Obtained from `Encoder::snapshot()` and turned back into an `Encoder` that
+/// continues the stream using `Encoder::from_snapshot()`. Apart from the
+/// encoding, only the ISO-2022-JP encoder has state to record.
+///
+/// Snapshots can be persisted using `to_bytes()` and `from_bytes()` or, with
+/// the `serde` feature enabled, using Serde. The format is versioned and
+/// stays readable by later versions of this crate.
+///
+/// Available to Rust only.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct EncoderSnapshot {
+    version: u8,
+    encoding: String,
+    state: Vec<u8>,
+}
+
+impl EncoderSnapshot {
+    /// The version of the snapshot format.
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    /// The `Encoding` the snapshotted `Encoder` was for or `None` if the
+    /// snapshot names an unknown encoding.
+    pub fn encoding(&self) -> Option<&'static Encoding> {
+        encoding_for_name(&self.encoding)
+    }
+
+    /// Serializes the snapshot into a compact byte representation.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(2 + self.encoding.len() + self.state.len());
+        bytes.push(self.version);
+        push_name(&mut bytes, &self.encoding);
+        bytes.extend_from_slice(&self.state);
+        bytes
+    }
+
+    /// Deserializes a snapshot serialized by `to_bytes()` or returns `None`
+    /// if `bytes` is not in a format supported by this version of the crate.
+    ///
+    /// The returned snapshot has not been validated in full yet;
+    /// `Encoder::from_snapshot()` does that.
+    pub fn from_bytes(bytes: &[u8]) -> Option<EncoderSnapshot> {
+        split_name(bytes).map(
+            |(version, encoding, rest)| {
+                EncoderSnapshot {
+                    version,
+                    encoding,
+                    state: rest.to_vec(),
+                }
+            },
+        )
+    }
+}
+
+impl Decoder {
+    /// Captures the state of the decoder so that decoding the stream can be
+    /// resumed later using `Decoder::from_snapshot()`.
+    ///
+    /// Returns `None` if the decoder is holding on to output, which can only
+    /// happen right after a `decode_*` call returned `OutputFull` or
+    /// `Malformed`. Snapshots taken after a call that returned `InputEmpty`
+    /// always succeed.
+    ///
+    /// The malformed-sequence policy set via `DecoderOptions` is not part of
+    /// the snapshot. To resume with the same policy, restore the decoder using
+    /// `DecoderOptions::build_from_snapshot()` with the policy set again.
+    ///
+    /// Available to Rust only.
+    pub fn snapshot(&self) -> Option<DecoderSnapshot> {
+        if let Some(ref handler) = self.malformed {
+            if handler.pending_len() != 0 {
+                return None;
+            }
+        }
+        let mut state = Vec::new();
+        if !self.variant.snapshot_state(&mut state) {
+            return None;
+        }
+        Some(
+            DecoderSnapshot {
+                version: SNAPSHOT_VERSION,
+                encoding: self.encoding.name().to_string(),
+                life_cycle: life_cycle_to_u8(&self.life_cycle),
+                seen_non_ascii: self.seen_non_ascii,
+                ascii_prefix: self.ascii_prefix,
+                iso_2022_jp_ascii_prefix: self.iso_2022_jp_ascii_prefix,
+                state,
+            },
+        )
+    }
+
+    /// Instantiates a decoder that continues decoding the stream from where
+    /// the decoder that `snapshot` was taken from was, or returns `None` if
+    /// `snapshot` is invalid or from an unsupported version.
+    ///
+    /// The new decoder replaces malformed sequences with the REPLACEMENT
+    /// CHARACTER even if the decoder that the snapshot was taken from was
+    /// built with a different policy. Use
+    /// `DecoderOptions::build_from_snapshot()` to resume with another
+    /// policy. `switch_encoding()` on the new decoder works as it would
+    /// have on the decoder that the snapshot was taken from.
+    ///
+    /// Available to Rust only.
+    pub fn from_snapshot(snapshot: &DecoderSnapshot) -> Option<Decoder> {
+        if snapshot.version != SNAPSHOT_VERSION {
+            return None;
+        }
+        let encoding = match snapshot.encoding() {
+            Some(encoding) => encoding,
+            None => {
+                return None;
+            }
+        };
+        if snapshot.iso_2022_jp_ascii_prefix > snapshot.ascii_prefix {
+            return None;
+        }
+        let life_cycle = match life_cycle_from_u8(snapshot.life_cycle, encoding) {
+            Some(life_cycle) => life_cycle,
+            None => {
+                return None;
+            }
+        };
+        let mut variant = encoding.new_variant_decoder();
+        {
+            let pending = match variant.restore_state(&snapshot.state) {
+                Some(pending) => pending,
+                None => {
+                    return None;
+                }
+            };
+            if !pending.is_empty() {
+                // The pending bytes are an incomplete sequence, so decoding
+                // them must consume them all without producing output.
+                let mut dst = [0u8; 16];
+                let (result, read, written) = variant.decode_to_utf8_raw(pending, &mut dst, false);
+                if result != DecoderResult::InputEmpty || read != pending.len() || written != 0 {
+                    return None;
+                }
+            }
+        }
+        Some(
+            Decoder {
+                encoding,
+                variant,
+                life_cycle,
+                malformed: None,
+                ascii_prefix: snapshot.ascii_prefix,
+                iso_2022_jp_ascii_prefix: snapshot.iso_2022_jp_ascii_prefix,
+                seen_non_ascii: snapshot.seen_non_ascii,
+            },
+        )
+    }
+}
+
+impl Encoder {
+    /// Captures the state of the encoder so that encoding the stream can be
+    /// resumed later using `Encoder::from_snapshot()`.
+    ///
+    /// Returns `None` if the encoder is holding on to output, which can only
+    /// happen right after an `encode_*` call returned `OutputFull`.
+    ///
+    /// The unmappable-character policy set via `EncoderOptions` is not part of
+    /// the snapshot. To resume with the same policy, restore the encoder using
+    /// `EncoderOptions::build_from_snapshot()` with the policy set again.
+    ///
+    /// Available to Rust only.
+    pub fn snapshot(&self) -> Option<EncoderSnapshot> {
+        if let Some(ref handler) = self.unmappable {
+            if handler.pending_len() != 0 {
+                return None;
+            }
+        }
+        let mut state = Vec::new();
+        self.variant.snapshot_state(&mut state);
+        Some(
+            EncoderSnapshot {
+                version: SNAPSHOT_VERSION,
+                encoding: self.encoding.name().to_string(),
+                state,
+            },
+        )
+    }
+
+    /// Instantiates an encoder that continues encoding the stream from where
+    /// the encoder that `snapshot` was taken from was, or returns `None` if
+    /// `snapshot` is invalid or from an unsupported version.
+    ///
+    /// The new encoder replaces unmappable characters with HTML decimal
+    /// numeric character references even if the encoder that the snapshot was
+    /// taken from was built with a different policy. Use
+    /// `EncoderOptions::build_from_snapshot()` to resume with another
+    /// policy.
+    ///
+    /// Available to Rust only.
+    pub fn from_snapshot(snapshot: &EncoderSnapshot) -> Option<Encoder> {
+        if snapshot.version != SNAPSHOT_VERSION {
+            return None;
+        }
+        let encoding = match snapshot.encoding() {
+            Some(encoding) => encoding,
+            None => {
+                return None;
+            }
+        };
+        if encoding.output_encoding() != encoding {
+            return None;
+        }
+        let mut encoder = encoding.new_encoder();
+        if !encoder.variant.restore_state(&snapshot.state) {
+            return None;
+        }
+        Some(encoder)
+    }
+}
+
+// Any copyright to the test code below this comment is dedicated to the
+// Public Domain. http://creativecommons.org/publicdomain/zero/1.0/
+
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+
+    fn decode_resumed(encoding: &'static Encoding, first: &[u8], second: &[u8], expect: &str) {
+        let mut decoder = encoding.new_decoder();
+        let mut string = String::with_capacity(64);
+        let (result, read, _) = decoder.decode_to_string(first, &mut string, false);
+        assert_eq!(result, CoderResult::InputEmpty);
+        assert_eq!(read, first.len());
+        let bytes = decoder.snapshot().unwrap().to_bytes();
+        drop(decoder);
+
+        let snapshot = DecoderSnapshot::from_bytes(&bytes).unwrap();
+        let mut decoder = Decoder::from_snapshot(&snapshot).unwrap();
+        let (result, read, _) = decoder.decode_to_string(second, &mut string, true);
+        assert_eq!(result, CoderResult::InputEmpty);
+        assert_eq!(read, second.len());
+        assert_eq!(&string[..], expect);
+    }
+
+    #[test]
+    fn test_decoder_snapshot_mid_sequence() {
+        decode_resumed(UTF_8, b"a\xF0\x9F", b"\x98\x80b", "a\u{1F600}b");
+        decode_resumed(GB18030, b"a\x81\x30\x81", b"\x30b", "a\u{0080}b");
+        decode_resumed(BIG5, b"a\xA4", b"\x40b", "a\u{4E00}b");
+        decode_resumed(EUC_JP, b"a\x8F\xB0", b"\xA1b", "a\u{4E02}b");
+        decode_resumed(SHIFT_JIS, b"a\xE0", b"\x40b", "a\u{6F3E}b");
+        decode_resumed(EUC_KR, b"a\xB0", b"\xA1b", "a\u{AC00}b");
+        decode_resumed(UTF_16LE, b"\x3D\xD8\x00", b"\xDE", "\u{1F600}");
+        decode_resumed(UTF_16BE, b"\xD8\x3D\xDE", b"\x00", "\u{1F600}");
+    }
+
+    #[test]
+    fn test_decoder_snapshot_iso_2022_jp() {
+        // Mid escape sequence
+        decode_resumed(ISO_2022_JP, b"a\x1B$", b"B\x30\x21\x1B(Bb", "a\u{4E9C}b");
+        // Mid character in the JIS X 0208 state
+        decode_resumed(ISO_2022_JP, b"\x1B$B\x30\x21\x30", b"\x21", "\u{4E9C}\u{4E9C}");
+        // Katakana state
+        decode_resumed(ISO_2022_JP, b"\x1B(I", b"\x21", "\u{FF61}");
+        // Escape sequence immediately after another one is an error
+        decode_resumed(ISO_2022_JP, b"\x1B(J", b"\x1B(Ba", "\u{FFFD}a");
+    }
+
+    #[test]
+    fn test_decoder_snapshot_bom() {
+        decode_resumed(WINDOWS_1252, b"\xEF\xBB", b"\xBFa", "a");
+        decode_resumed(WINDOWS_1252, b"\xFE", b"\xFF\x00a", "a");
+        decode_resumed(WINDOWS_1252, b"\xEF\xBB", b"a", "\u{00EF}\u{00BB}a");
+
+        let mut decoder = WINDOWS_1252.new_decoder();
+        let mut string = String::with_capacity(16);
+        assert_eq!(decoder.decode_to_string(b"\xFF\xFE", &mut string, false).0, CoderResult::InputEmpty);
+        let snapshot = decoder.snapshot().unwrap();
+        assert_eq!(snapshot.encoding(), Some(UTF_16LE));
+        let mut decoder = Decoder::from_snapshot(&snapshot).unwrap();
+        assert_eq!(decoder.decode_to_string(b"a\x00", &mut string, true).0, CoderResult::InputEmpty);
+        assert_eq!(&string[..], "a");
+    }
+
+    #[test]
+    fn test_decoder_snapshot_switch_encoding() {
+        let mut decoder = WINDOWS_1252.new_decoder();
+        let mut string = String::with_capacity(16);
+        assert_eq!(decoder.decode_to_string(b"ab\x1B(B", &mut string, false).0, CoderResult::InputEmpty);
+        let snapshot = decoder.snapshot().unwrap();
+        let snapshot = DecoderSnapshot::from_bytes(&snapshot.to_bytes()).unwrap();
+        let mut decoder = Decoder::from_snapshot(&snapshot).unwrap();
+        assert_eq!(decoder.switch_encoding(ISO_2022_JP), SwitchResult::ReloadNeeded(2));
+        assert_eq!(decoder.switch_encoding(UTF_8), SwitchResult::Switched);
+        assert_eq!(decoder.decode_to_string(b"c\xC3\xA9", &mut string, false).0, CoderResult::InputEmpty);
+        assert_eq!(&string[..], "ab\u{1B}(Bc\u{E9}");
+
+        let snapshot = decoder.snapshot().unwrap();
+        let snapshot = DecoderSnapshot::from_bytes(&snapshot.to_bytes()).unwrap();
+        let mut decoder = Decoder::from_snapshot(&snapshot).unwrap();
+        assert_eq!(decoder.switch_encoding(WINDOWS_1252), SwitchResult::ReloadNeeded(6));
+    }
+
+    #[test]
+    fn test_decoder_has_pending_state() {
+        let mut decoder = UTF_8.new_decoder();
+        let mut string = String::with_capacity(16);
+        assert!(!decoder.has_pending_state());
+        assert_eq!(decoder.decode_to_string(b"\xEF", &mut string, false).0, CoderResult::InputEmpty);
+        assert!(decoder.has_pending_state());
+        assert_eq!(decoder.decode_to_string(b"\xBB\xBFa", &mut string, false).0, CoderResult::InputEmpty);
+        assert!(!decoder.has_pending_state());
+        assert_eq!(decoder.decode_to_string(b"\xE2\x82", &mut string, false).0, CoderResult::InputEmpty);
+        assert!(decoder.has_pending_state());
+        assert_eq!(decoder.decode_to_string(b"\xAC", &mut string, false).0, CoderResult::InputEmpty);
+        assert!(!decoder.has_pending_state());
+        assert_eq!(&string[..], "a\u{20AC}");
+    }
+
+    #[test]
+    fn test_decoder_snapshot_invalid() {
+        let snapshot = UTF_8.new_decoder().snapshot().unwrap();
+        let mut bytes = snapshot.to_bytes();
+        // Complete character instead of an incomplete one
+        bytes.push(b'a');
+        let snapshot = DecoderSnapshot::from_bytes(&bytes).unwrap();
+        assert!(Decoder::from_snapshot(&snapshot).is_none());
+        // Unsupported version
+        bytes[0] = 2;
+        assert!(DecoderSnapshot::from_bytes(&bytes).is_none());
+        // Unknown encoding
+        let mut bytes = b"\x01\x03foo\x09".to_vec();
+        bytes.resize(bytes.len() + 17, 0);
+        assert!(DecoderSnapshot::from_bytes(&bytes).unwrap().encoding().is_none());
+        // Truncated
+        assert!(DecoderSnapshot::from_bytes(b"\x01\x05UTF-8").is_none());
+        assert!(DecoderSnapshot::from_bytes(b"\x01\x05UTF-8\x09\x00").is_none());
+        // ISO-2022-JP ASCII prefix longer than the ASCII prefix
+        let mut bytes = b"\x01\x05UTF-8\x09\x00".to_vec();
+        bytes.resize(bytes.len() + 16, 0);
+        bytes[24] = 1;
+        let snapshot = DecoderSnapshot::from_bytes(&bytes).unwrap();
+        assert!(Decoder::from_snapshot(&snapshot).is_none());
+    }
+
+    #[test]
+    fn test_encoder_snapshot() {
+        let mut encoder = ISO_2022_JP.new_encoder();
+        let mut vec = Vec::with_capacity(64);
+        assert_eq!(encoder.encode_from_utf8_to_vec("a\u{4E9C}", &mut vec, false).0, CoderResult::InputEmpty);
+        assert!(encoder.has_pending_state());
+        let bytes = encoder.snapshot().unwrap().to_bytes();
+
+        let snapshot = EncoderSnapshot::from_bytes(&bytes).unwrap();
+        let mut encoder = Encoder::from_snapshot(&snapshot).unwrap();
+        assert_eq!(encoder.encode_from_utf8_to_vec("\u{4E9C}b", &mut vec, true).0, CoderResult::InputEmpty);
+        assert_eq!(&vec[..], b"a\x1B$B\x30\x21\x30\x21\x1B(Bb");
+
+        let snapshot = EncoderSnapshot::from_bytes(b"\x01\x0BISO-2022-JP\x03").unwrap();
+        assert!(Encoder::from_snapshot(&snapshot).is_none());
+        let snapshot = EncoderSnapshot::from_bytes(b"\x01\x05UTF-8\x00").unwrap();
+        assert!(Encoder::from_snapshot(&snapshot).is_none());
+    }
+
+    #[test]
+    fn test_snapshot_with_policy() {
+        let mut decoder = SHIFT_JIS.decoder_options()
+                                   .malformed_policy(MalformedPolicy::Skip)
+                                   .build();
+        let mut string = String::with_capacity(16);
+        assert_eq!(decoder.decode_to_string(b"a\xFFb\x82", &mut string, false).0, CoderResult::InputEmpty);
+        let snapshot = decoder.snapshot().unwrap();
+
+        let mut decoder = Decoder::from_snapshot(&snapshot).unwrap();
+        let mut resumed = String::with_capacity(16);
+        resumed.push_str(&string);
+        assert_eq!(decoder.decode_to_string(b"\xA0\xFFc", &mut resumed, true).0, CoderResult::InputEmpty);
+        assert_eq!(&resumed[..], "ab\u{3042}\u{FFFD}c");
+
+        let mut decoder = SHIFT_JIS.decoder_options()
+                                   .malformed_policy(MalformedPolicy::Skip)
+                                   .record_malformed_sequences()
+                                   .build_from_snapshot(&snapshot)
+                                   .unwrap();
+        assert_eq!(decoder.decode_to_string(b"\xA0\xFFc", &mut string, true).0, CoderResult::InputEmpty);
+        assert_eq!(&string[..], "ab\u{3042}c");
+        assert_eq!(decoder.take_malformed_sequences().len(), 1);
+
+        let mut encoder = ISO_2022_JP.encoder_options()
+                                     .unmappable_policy(UnmappablePolicy::QuestionMark)
+                                     .build();
+        let mut vec = Vec::with_capacity(64);
+        assert_eq!(encoder.encode_from_utf8_to_vec("\u{3042}", &mut vec, false).0, CoderResult::InputEmpty);
+        let snapshot = encoder.snapshot().unwrap();
+        let mut encoder = ISO_2022_JP.encoder_options()
+                                     .unmappable_policy(UnmappablePolicy::QuestionMark)
+                                     .build_from_snapshot(&snapshot)
+                                     .unwrap();
+        assert_eq!(encoder.encode_from_utf8_to_vec("\u{1F4A9}", &mut vec, true).0, CoderResult::InputEmpty);
+        assert_eq!(&vec[..], b"\x1B$B$\"\x1B(B?");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_snapshot_serde() {
+        let mut decoder = GB18030.new_decoder();
+        let mut string = String::with_capacity(16);
+        assert_eq!(decoder.decode_to_string(b"\x81\x30", &mut string, false).0, CoderResult::InputEmpty);
+        let json = ::serde_json::to_string(&decoder.snapshot().unwrap()).unwrap();
+        let snapshot: DecoderSnapshot = ::serde_json::from_str(&json).unwrap();
+        let mut decoder = Decoder::from_snapshot(&snapshot).unwrap();
+        assert_eq!(decoder.decode_to_string(b"\x81\x30", &mut string, true).0, CoderResult::InputEmpty);
+        assert_eq!(&string[..], "\u{0080}");
+    }
+}
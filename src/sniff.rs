@@ -0,0 +1,313 @@
+// Copyright 2017 Mozilla Foundation. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! This module implements the HTML Standard's "encoding sniffing algorithm"
+//! on top of `Encoding::for_bom()`, `Encoding::for_label()`,
+//! `prescan_html()` and `EncodingDetector`.
+
+use super::*;
+
+/// How sure the encoding sniffing algorithm is about the encoding of a
+/// document.
+///
+/// Available to Rust only.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Confidence {
+    /// The encoding came from a BOM or from the transport layer and must not
+    /// be changed.
+    Certain,
+    /// The encoding was guessed or came from the document itself. An HTML
+    /// parser changes it if it later finds a `meta` that declares another
+    /// encoding.
+    Tentative,
+}
+
+/// Which step of the encoding sniffing algorithm chose the encoding.
+///
+/// Available to Rust only.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum EncodingSource {
+    /// A UTF-8, UTF-16LE or UTF-16BE BOM.
+    Bom,
+    /// The label supplied by the transport layer (e.g. the `charset`
+    /// parameter of the HTTP `Content-Type`).
+    TransportLayer,
+    /// A `meta` found by `prescan_html()`.
+    MetaPrescan,
+    /// `EncodingDetector`.
+    Detection,
+    /// The caller-supplied fallback encoding.
+    Fallback,
+}
+
+/// A builder that runs the HTML Standard's encoding sniffing algorithm.
+///
+/// The steps are, in order:
+///
+/// 1. BOM sniffing (certain).
+/// 2. The transport-layer label, if set with `transport_label()` and
+///    recognized by `Encoding::for_label()` (certain).
+/// 3. `prescan_html()`, unless disabled with `without_prescan()`
+///    (tentative).
+/// 4. `EncodingDetector`, if enabled with `detection()` and the input is
+///    not plain ASCII (tentative).
+/// 5. The fallback encoding, `WINDOWS_1252` unless set with `fallback()`
+///    (tentative).
+///
+/// Available to Rust only.
+pub struct EncodingSniffer<'a> {
+    transport_label: Option<&'a [u8]>,
+    prescan: bool,
+    detection: bool,
+    tld: Option<&'a [u8]>,
+    allow_utf8: bool,
+    fallback: &'static Encoding,
+}
+
+impl<'a> EncodingSniffer<'a> {
+    /// Starts building a sniffer with the meta prescan enabled, detection
+    /// disabled and `WINDOWS_1252` as the fallback.
+    pub fn new() -> EncodingSniffer<'a> {
+        EncodingSniffer {
+            transport_label: None,
+            prescan: true,
+            detection: false,
+            tld: None,
+            allow_utf8: false,
+            fallback: WINDOWS_1252,
+        }
+    }
+
+    /// Sets the label supplied by the transport layer, e.g. the result of
+    /// extracting the `charset` parameter from the `Content-Type` header.
+    pub fn transport_label(mut self, label: &'a [u8]) -> EncodingSniffer<'a> {
+        self.transport_label = Some(label);
+        self
+    }
+
+    /// Disables the meta prescan, e.g. for non-HTML text.
+    pub fn without_prescan(mut self) -> EncodingSniffer<'a> {
+        self.prescan = false;
+        self
+    }
+
+    /// Enables guessing the encoding with `EncodingDetector` when the
+    /// previous steps don't find one. `tld` and `allow_utf8` are passed to
+    /// `EncodingDetector::guess()`.
+    pub fn detection(mut self, tld: Option<&'a [u8]>, allow_utf8: bool) -> EncodingSniffer<'a> {
+        self.detection = true;
+        self.tld = tld;
+        self.allow_utf8 = allow_utf8;
+        self
+    }
+
+    /// Sets the encoding to use when the other steps don't find one.
+    pub fn fallback(mut self, fallback: &'static Encoding) -> EncodingSniffer<'a> {
+        self.fallback = fallback;
+        self
+    }
+
+    /// Chooses the encoding for a stream that starts with `bytes`.
+    ///
+    /// `bytes` should be at least the first 1024 bytes of the stream unless
+    /// the stream is shorter, in which case `last` must be `true`. If `last`
+    /// is `true`, `bytes` is the entire stream.
+    ///
+    /// The returned `Sniffed` holds the decoder for the rest of the stream
+    /// after it has already decoded `bytes`.
+    pub fn sniff(&self, bytes: &[u8], last: bool) -> Sniffed {
+        let (encoding, confidence, source) = self.choose(bytes, last);
+        // The decoder sniffs the BOM again, which both removes a BOM found
+        // above and follows a BOM that `bytes` was too short to show.
+        let mut decoder = encoding.new_decoder();
+        let mut text = String::new();
+        let mut read = 0usize;
+        loop {
+            let needed = decoder
+                .max_utf8_buffer_length(bytes.len() - read)
+                .unwrap_or(1024);
+            text.reserve(needed);
+            let (result, decoder_read, _) = decoder.decode_to_string(&bytes[read..], &mut text, last);
+            read += decoder_read;
+            if result == CoderResult::InputEmpty {
+                break;
+            }
+        }
+        Sniffed {
+            encoding,
+            confidence,
+            source,
+            decoder,
+            text,
+        }
+    }
+
+    fn choose(&self, bytes: &[u8], last: bool) -> (&'static Encoding, Confidence, EncodingSource) {
+        if let Some((encoding, _)) = Encoding::for_bom(bytes) {
+            return (encoding, Confidence::Certain, EncodingSource::Bom);
+        }
+        if let Some(encoding) = self.transport_label.and_then(Encoding::for_label) {
+            return (encoding, Confidence::Certain, EncodingSource::TransportLayer);
+        }
+        if self.prescan {
+            if let Some(encoding) = prescan_html(bytes) {
+                return (encoding, Confidence::Tentative, EncodingSource::MetaPrescan);
+            }
+        }
+        // Plain ASCII gives the detector nothing to go on. (ESC may start
+        // ISO-2022-JP.)
+        if self.detection && bytes.iter().any(|b| *b >= 0x80 || *b == 0x1B) {
+            let mut detector = EncodingDetector::new();
+            detector.feed(bytes, last);
+            let encoding = detector.guess(self.tld, self.allow_utf8);
+            return (encoding, Confidence::Tentative, EncodingSource::Detection);
+        }
+        (self.fallback, Confidence::Tentative, EncodingSource::Fallback)
+    }
+}
+
+impl<'a> Default for EncodingSniffer<'a> {
+    fn default() -> EncodingSniffer<'a> {
+        EncodingSniffer::new()
+    }
+}
+
+/// The outcome of `EncodingSniffer::sniff()`.
+///
+/// Available to Rust only.
+pub struct Sniffed {
+    encoding: &'static Encoding,
+    confidence: Confidence,
+    source: EncodingSource,
+    decoder: Decoder,
+    text: String,
+}
+
+impl Sniffed {
+    /// The chosen encoding.
+    pub fn encoding(&self) -> &'static Encoding {
+        self.encoding
+    }
+
+    /// The confidence of the choice.
+    pub fn confidence(&self) -> Confidence {
+        self.confidence
+    }
+
+    /// The step of the algorithm that made the choice.
+    pub fn source(&self) -> EncodingSource {
+        self.source
+    }
+
+    /// The text that the decoder produced from the bytes passed to
+    /// `EncodingSniffer::sniff()` (without the BOM).
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// The decoder, which has consumed the bytes passed to
+    /// `EncodingSniffer::sniff()`.
+    pub fn decoder_mut(&mut self) -> &mut Decoder {
+        &mut self.decoder
+    }
+
+    /// Returns the decoder (which has consumed the bytes passed to
+    /// `EncodingSniffer::sniff()`) and the text it produced from them.
+    pub fn into_parts(self) -> (Decoder, String) {
+        (self.decoder, self.text)
+    }
+}
+
+// Any copyright to the test code below this comment is dedicated to the
+// Public Domain. http://creativecommons.org/publicdomain/zero/1.0/
+
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+
+    fn check(sniffer: &EncodingSniffer,
+             bytes: &[u8],
+             encoding: &'static Encoding,
+             confidence: Confidence,
+             source: EncodingSource) {
+        let sniffed = sniffer.sniff(bytes, true);
+        assert_eq!(sniffed.encoding(), encoding);
+        assert_eq!(sniffed.confidence(), confidence);
+        assert_eq!(sniffed.source(), source);
+    }
+
+    #[test]
+    fn test_sniff_order() {
+        let meta = b"<meta charset=gbk>\xC4\xE3\xBA\xC3";
+        let sniffer = EncodingSniffer::new().transport_label(b"big5");
+        check(&sniffer,
+              b"\xEF\xBB\xBF<meta charset=gbk>",
+              UTF_8,
+              Confidence::Certain,
+              EncodingSource::Bom);
+        check(&sniffer, meta, BIG5, Confidence::Certain, EncodingSource::TransportLayer);
+        let sniffer = EncodingSniffer::new().transport_label(b"bogus");
+        check(&sniffer, meta, GBK, Confidence::Tentative, EncodingSource::MetaPrescan);
+        let sniffer = sniffer.without_prescan();
+        check(&sniffer,
+              meta,
+              WINDOWS_1252,
+              Confidence::Tentative,
+              EncodingSource::Fallback);
+        let sniffer = sniffer.detection(None, false);
+        check(&sniffer, meta, GBK, Confidence::Tentative, EncodingSource::Detection);
+        let sniffer = EncodingSniffer::new()
+            .detection(None, false)
+            .fallback(WINDOWS_1251);
+        check(&sniffer,
+              b"<p>ascii</p>",
+              WINDOWS_1251,
+              Confidence::Tentative,
+              EncodingSource::Fallback);
+    }
+
+    #[test]
+    fn test_sniff_decoder() {
+        let sniffer = EncodingSniffer::new();
+        let mut sniffed = sniffer.sniff(b"\xFF\xFEa\x00\x00", false);
+        assert_eq!(sniffed.encoding(), UTF_16LE);
+        assert_eq!(sniffed.text(), "a");
+        let mut text = String::with_capacity(10);
+        let (result, read, _) = sniffed
+            .decoder_mut()
+            .decode_to_string(b"\xD8\x00\xDC", &mut text, true);
+        assert_eq!(result, CoderResult::InputEmpty);
+        assert_eq!(read, 3);
+        assert_eq!(text, "\u{10000}");
+
+        let (mut decoder, text) = sniffer
+            .sniff(b"<meta charset=koi8-r>\xF0", false)
+            .into_parts();
+        assert_eq!(text, "<meta charset=koi8-r>\u{041F}");
+        assert_eq!(decoder.encoding(), KOI8_R);
+        let mut text = String::with_capacity(10);
+        assert_eq!(decoder.decode_to_string(b"\xD2", &mut text, true).0,
+                   CoderResult::InputEmpty);
+        assert_eq!(text, "\u{0440}");
+    }
+
+    #[test]
+    fn test_sniff_short_bom() {
+        // Too short to see the BOM, but the decoder follows it.
+        let sniffed = EncodingSniffer::new().sniff(b"\xEF\xBB", false);
+        assert_eq!(sniffed.encoding(), WINDOWS_1252);
+        let (mut decoder, text) = sniffed.into_parts();
+        assert_eq!(text, "");
+        let mut text = String::with_capacity(10);
+        assert_eq!(decoder.decode_to_string(b"\xBFa", &mut text, true).0,
+                   CoderResult::InputEmpty);
+        assert_eq!(text, "a");
+        assert_eq!(decoder.encoding(), UTF_8);
+    }
+}
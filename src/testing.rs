@@ -29,6 +29,7 @@ pub fn decode(encoding: &'static Encoding, bytes: &[u8], expect: &str) {
 pub fn decode_without_padding(encoding: &'static Encoding, bytes: &[u8], expect: &str) {
     decode_to_utf8(encoding, bytes, expect);
     decode_to_utf16(encoding, bytes, &utf16_from_utf8(expect)[..]);
+    #[cfg(feature = "alloc")]
     decode_to_string(encoding, bytes, expect);
 }
 
@@ -52,6 +53,7 @@ pub fn encode(encoding: &'static Encoding, str: &str, expect: &[u8]) {
 pub fn encode_without_padding(encoding: &'static Encoding, string: &str, expect: &[u8]) {
     encode_from_utf8(encoding, string, expect);
     encode_from_utf16(encoding, &utf16_from_utf8(string)[..], expect);
+    #[cfg(feature = "alloc")]
     encode_to_vec(encoding, string, expect);
 }
 
@@ -93,6 +95,7 @@ pub fn decode_to_utf8(encoding: &'static Encoding, bytes: &[u8], expect: &str) {
     assert_eq!(&dest[..], expect.as_bytes());
 }
 
+#[cfg(feature = "alloc")]
 pub fn decode_to_string(encoding: &'static Encoding, bytes: &[u8], expect: &str) {
     let (cow, _, _) = encoding.decode(bytes);
     assert_eq!(&cow[..], expect);
@@ -134,6 +137,7 @@ pub fn encode_from_utf16(encoding: &'static Encoding, string: &[u16], expect: &[
     assert_eq!(&dest[..], expect);
 }
 
+#[cfg(feature = "alloc")]
 pub fn encode_to_vec(encoding: &'static Encoding, string: &str, expect: &[u8]) {
     let (cow, _, _) = encoding.encode(string);
     assert_eq!(&cow[..], expect);
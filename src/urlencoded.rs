@@ -0,0 +1,285 @@
+// Copyright 2017 Mozilla Foundation. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! This module implements the parts of the URL Standard that encode
+//! non-ASCII into legacy encodings: the application/x-www-form-urlencoded
+//! serializer and the percent-encoding of the query of a URL. It also
+//! implements the application/x-www-form-urlencoded parser with a choice of
+//! encoding.
+
+use super::*;
+
+fn is_in_c0_control_set(byte: u8) -> bool {
+    !(0x20..=0x7E).contains(&byte)
+}
+
+fn is_in_query_set(byte: u8) -> bool {
+    is_in_c0_control_set(byte) || byte == b' ' || byte == b'"' || byte == b'#' || byte == b'<' ||
+    byte == b'>'
+}
+
+fn is_in_special_query_set(byte: u8) -> bool {
+    is_in_query_set(byte) || byte == b'\''
+}
+
+fn is_in_form_urlencoded_set(byte: u8) -> bool {
+    !(byte.is_ascii_alphanumeric() || byte == b'*' || byte == b'-' || byte == b'.' || byte == b'_')
+}
+
+fn percent_encode_byte(byte: u8, output: &mut String) {
+    const HEX: &[u8; 16] = b"0123456789ABCDEF";
+    output.push('%');
+    output.push(HEX[(byte >> 4) as usize] as char);
+    output.push(HEX[(byte & 0xF) as usize] as char);
+}
+
+/// Implements "percent-encode after encoding" from the URL Standard.
+///
+/// `encoding` must be an output encoding. Unmappable characters become
+/// `%26%23`, the decimal scalar value and `%3B`, i.e. a percent-encoded
+/// HTML numeric character reference.
+fn percent_encode_after_encoding(input: &str,
+                                 encoding: &'static Encoding,
+                                 in_set: fn(u8) -> bool,
+                                 space_as_plus: bool,
+                                 output: &mut String) {
+    let mut encoder = encoding.new_encoder();
+    let mut buffer = [0u8; 1024];
+    let mut src = input;
+    loop {
+        let (result, read, written) =
+            encoder.encode_from_utf8_without_replacement(src, &mut buffer, true);
+        src = &src[read..];
+        for &byte in &buffer[..written] {
+            if space_as_plus && byte == b' ' {
+                output.push('+');
+            } else if in_set(byte) {
+                percent_encode_byte(byte, output);
+            } else {
+                output.push(byte as char);
+            }
+        }
+        match result {
+            EncoderResult::InputEmpty => {
+                return;
+            }
+            EncoderResult::OutputFull => {}
+            EncoderResult::Unmappable(c) => {
+                output.push_str("%26%23");
+                output.push_str(&(c as u32).to_string());
+                output.push_str("%3B");
+            }
+        }
+    }
+}
+
+/// Serializes name-value pairs as application/x-www-form-urlencoded
+/// according to the URL Standard.
+///
+/// `encoding` is the encoding of the form (typically the encoding of the
+/// document that contains the form). As in form submission, its output
+/// encoding is used, i.e. UTF-16BE, UTF-16LE and replacement become UTF-8.
+/// Characters that the encoding cannot represent are submitted as
+/// percent-encoded HTML numeric character references (`%26%23NNNN%3B`).
+///
+/// As in HTML form submission, the value of a pair whose name is an ASCII
+/// case-insensitive match for `_charset_` is replaced with the name of the
+/// output encoding, so that the recipient can tell how to decode the rest.
+/// (HTML does this only for hidden inputs, so callers serializing other
+/// kinds of fields named `_charset_` should not pass them as such.)
+///
+/// Available to Rust only.
+pub fn form_urlencoded_serialize<I, N, V>(pairs: I, encoding: &'static Encoding) -> String
+    where I: IntoIterator<Item = (N, V)>,
+          N: AsRef<str>,
+          V: AsRef<str>
+{
+    let output_encoding = encoding.output_encoding();
+    let mut output = String::new();
+    for (name, value) in pairs {
+        let name = name.as_ref();
+        let value = if name.eq_ignore_ascii_case("_charset_") {
+            output_encoding.name()
+        } else {
+            value.as_ref()
+        };
+        if !output.is_empty() {
+            output.push('&');
+        }
+        percent_encode_after_encoding(name,
+                                      output_encoding,
+                                      is_in_form_urlencoded_set,
+                                      true,
+                                      &mut output);
+        output.push('=');
+        percent_encode_after_encoding(value,
+                                      output_encoding,
+                                      is_in_form_urlencoded_set,
+                                      true,
+                                      &mut output);
+    }
+    output
+}
+
+/// Percent-encodes the query of a URL the way the URL parser does in the
+/// query state.
+///
+/// `encoding` is the encoding of the document that the URL is parsed
+/// relative to. Its output encoding is used. `special` tells whether the
+/// scheme of the URL is special (e.g. `http` or `https`), in which case
+/// `'` is percent-encoded, too. Existing `%` escapes are left as is and
+/// unmappable characters become percent-encoded HTML numeric character
+/// references (`%26%23NNNN%3B`).
+///
+/// Available to Rust only.
+pub fn percent_encode_query(query: &str, encoding: &'static Encoding, special: bool) -> String {
+    let mut output = String::with_capacity(query.len());
+    let in_set: fn(u8) -> bool = if special {
+        is_in_special_query_set
+    } else {
+        is_in_query_set
+    };
+    percent_encode_after_encoding(query,
+                                  encoding.output_encoding(),
+                                  in_set,
+                                  false,
+                                  &mut output);
+    output
+}
+
+/// Replaces `+` with space and decodes percent escapes. A `%` that is not
+/// followed by two hex digits is kept as is.
+fn percent_decode_form(bytes: &[u8]) -> Vec<u8> {
+    fn hex_value(byte: u8) -> Option<u8> {
+        (byte as char).to_digit(16).map(|d| d as u8)
+    }
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0usize;
+    while i < bytes.len() {
+        let byte = bytes[i];
+        if byte == b'%' && i + 2 < bytes.len() {
+            if let (Some(high), Some(low)) = (hex_value(bytes[i + 1]), hex_value(bytes[i + 2])) {
+                decoded.push((high << 4) | low);
+                i += 3;
+                continue;
+            }
+        }
+        decoded.push(if byte == b'+' { b' ' } else { byte });
+        i += 1;
+    }
+    decoded
+}
+
+/// Parses application/x-www-form-urlencoded bytes into name-value pairs
+/// according to the URL Standard except that the percent-decoded bytes are
+/// decoded using `encoding` instead of UTF-8.
+///
+/// `encoding` is typically the encoding that the form was submitted in. Its
+/// output encoding is used (i.e. UTF-16BE, UTF-16LE and replacement become
+/// UTF-8), since that is what form submission would have used. Malformed
+/// byte sequences are replaced with the REPLACEMENT CHARACTER.
+///
+/// Available to Rust only.
+pub fn form_urlencoded_parse(bytes: &[u8], encoding: &'static Encoding) -> Vec<(String, String)> {
+    let encoding = encoding.output_encoding();
+    let mut pairs = Vec::new();
+    for sequence in bytes.split(|b| *b == b'&') {
+        if sequence.is_empty() {
+            continue;
+        }
+        let (name, value) = match sequence.iter().position(|b| *b == b'=') {
+            Some(equals) => (&sequence[..equals], &sequence[equals + 1..]),
+            None => (sequence, &b""[..]),
+        };
+        let name = encoding
+            .decode_without_bom_handling(&percent_decode_form(name))
+            .0
+            .into_owned();
+        let value = encoding
+            .decode_without_bom_handling(&percent_decode_form(value))
+            .0
+            .into_owned();
+        pairs.push((name, value));
+    }
+    pairs
+}
+
+// Any copyright to the test code below this comment is dedicated to the
+// Public Domain. http://creativecommons.org/publicdomain/zero/1.0/
+
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+
+    #[test]
+    fn test_form_urlencoded_serialize() {
+        assert_eq!(form_urlencoded_serialize(vec![("a", "b c"), ("\u{E9}", "\u{20AC}")], UTF_8),
+                   "a=b+c&%C3%A9=%E2%82%AC");
+        assert_eq!(form_urlencoded_serialize(vec![("a", "b c"), ("\u{E9}", "\u{20AC}")],
+                                             WINDOWS_1252),
+                   "a=b+c&%E9=%80");
+        assert_eq!(form_urlencoded_serialize(vec![("x", "*-._~!'()&=+")], UTF_8),
+                   "x=*-._%7E%21%27%28%29%26%3D%2B");
+        assert_eq!(form_urlencoded_serialize(vec![("x", "a\u{65E5}b\u{1F600}")], WINDOWS_1252),
+                   "x=a%26%2326085%3Bb%26%23128512%3B");
+        assert_eq!(form_urlencoded_serialize(vec![("x", "\u{E9}")], UTF_16LE),
+                   "x=%C3%A9");
+        assert_eq!(form_urlencoded_serialize(Vec::<(String, String)>::new(), UTF_8),
+                   "");
+    }
+
+    #[test]
+    fn test_form_urlencoded_serialize_charset() {
+        assert_eq!(form_urlencoded_serialize(vec![("_charset_", ""), ("q", "\u{0436}")],
+                                             WINDOWS_1251),
+                   "_charset_=windows-1251&q=%E6");
+        assert_eq!(form_urlencoded_serialize(vec![("_CHARSET_", "bogus")], REPLACEMENT),
+                   "_CHARSET_=UTF-8");
+        assert_eq!(form_urlencoded_serialize(vec![("_charset_", "")], SHIFT_JIS),
+                   "_charset_=Shift_JIS");
+    }
+
+    #[test]
+    fn test_percent_encode_query() {
+        assert_eq!(percent_encode_query("a b\"#<>'\u{E9}&%41", UTF_8, true),
+                   "a%20b%22%23%3C%3E%27%C3%A9&%41");
+        assert_eq!(percent_encode_query("a'b", UTF_8, false), "a'b");
+        assert_eq!(percent_encode_query("\u{E9}", WINDOWS_1252, true), "%E9");
+        assert_eq!(percent_encode_query("\u{65E5}", WINDOWS_1252, true),
+                   "%26%2326085%3B");
+        assert_eq!(percent_encode_query("\u{3042}", ISO_2022_JP, true),
+                   "%1B$B$%22%1B(B");
+    }
+
+    #[test]
+    fn test_form_urlencoded_parse() {
+        assert_eq!(form_urlencoded_parse(b"a=b+c&%C3%A9=%E2%82%AC&&x&=y&z==", UTF_8),
+                   vec![("a".to_string(), "b c".to_string()),
+                        ("\u{E9}".to_string(), "\u{20AC}".to_string()),
+                        ("x".to_string(), String::new()),
+                        (String::new(), "y".to_string()),
+                        ("z".to_string(), "=".to_string())]);
+        assert_eq!(form_urlencoded_parse(b"q=%E6", WINDOWS_1251),
+                   vec![("q".to_string(), "\u{0436}".to_string())]);
+        assert_eq!(form_urlencoded_parse(b"q=%zz%4%E9", UTF_8),
+                   vec![("q".to_string(), "%zz%4\u{FFFD}".to_string())]);
+        assert_eq!(form_urlencoded_parse(b"q=%C3%A9", UTF_16BE),
+                   vec![("q".to_string(), "\u{E9}".to_string())]);
+        assert_eq!(form_urlencoded_parse(b"", UTF_8), vec![]);
+    }
+
+    #[test]
+    fn test_form_urlencoded_round_trip() {
+        let pairs = vec![("name".to_string(), "\u{65E5}\u{672C} \u{8A9E}+".to_string())];
+        for &encoding in &[UTF_8, SHIFT_JIS, EUC_JP, ISO_2022_JP, GB18030, BIG5, EUC_KR] {
+            let serialized = form_urlencoded_serialize(pairs.clone(), encoding);
+            assert_eq!(form_urlencoded_parse(serialized.as_bytes(), encoding), pairs);
+        }
+    }
+}
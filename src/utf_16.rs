@@ -30,6 +30,34 @@ impl Utf16Decoder {
         )
     }
 
+    pub fn has_pending_state(&self) -> bool {
+        self.lead_byte.is_some() || self.lead_surrogate != 0
+    }
+
+    /// Writes the bytes of the pending lead surrogate and of the pending lead
+    /// byte, if any. Fails if there is a BMP character waiting to be output.
+    #[cfg(feature = "alloc")]
+    pub fn snapshot_state(&self, state: &mut Vec<u8>) -> bool {
+        if self.pending_bmp {
+            return false;
+        }
+        if self.lead_surrogate != 0 {
+            let high = (self.lead_surrogate >> 8) as u8;
+            let low = self.lead_surrogate as u8;
+            if self.be {
+                state.push(high);
+                state.push(low);
+            } else {
+                state.push(low);
+                state.push(high);
+            }
+        }
+        if let Some(lead) = self.lead_byte {
+            state.push(lead);
+        }
+        true
+    }
+
     pub fn additional_from_state(&self) -> usize {
         1 + if self.lead_byte.is_some() { 1 } else { 0 } +
         if self.lead_surrogate == 0 { 0 } else { 2 }
@@ -113,7 +113,7 @@ pub fn convert_utf8_to_utf16_up_to_invalid(src: &[u8], dst: &mut [u16]) -> (usiz
         let mut byte = {
             let src_remaining = &src[read..];
             let dst_remaining = &mut dst[written..];
-            let length = ::std::cmp::min(src_remaining.len(), dst_remaining.len());
+            let length = ::core::cmp::min(src_remaining.len(), dst_remaining.len());
             match unsafe {
                       ascii_to_basic_latin(
                     src_remaining.as_ptr(),
@@ -416,16 +416,18 @@ pub struct Utf8Decoder {
 }
 
 impl Utf8Decoder {
+    pub fn new_inner() -> Utf8Decoder {
+        Utf8Decoder {
+            code_point: 0,
+            bytes_seen: 0,
+            bytes_needed: 0,
+            lower_boundary: 0x80u8,
+            upper_boundary: 0xBFu8,
+        }
+    }
+
     pub fn new() -> VariantDecoder {
-        VariantDecoder::Utf8(
-            Utf8Decoder {
-                code_point: 0,
-                bytes_seen: 0,
-                bytes_needed: 0,
-                lower_boundary: 0x80u8,
-                upper_boundary: 0xBFu8,
-            }
-        )
+        VariantDecoder::Utf8(Utf8Decoder::new_inner())
     }
 
     fn extra_from_state(&self) -> usize {
@@ -436,6 +438,28 @@ impl Utf8Decoder {
         }
     }
 
+    pub fn has_pending_state(&self) -> bool {
+        self.bytes_needed != 0
+    }
+
+    /// Writes the bytes of the incomplete sequence, if any.
+    #[cfg(feature = "alloc")]
+    pub fn snapshot_state(&self, state: &mut Vec<u8>) -> bool {
+        if self.bytes_needed == 0 {
+            return true;
+        }
+        let lead_prefix = match self.bytes_needed {
+            1 => 0xC0u8,
+            2 => 0xE0u8,
+            _ => 0xF0u8,
+        };
+        state.push(lead_prefix | (self.code_point >> (6 * self.bytes_seen)) as u8);
+        for i in (0..self.bytes_seen).rev() {
+            state.push(0x80u8 | ((self.code_point >> (6 * i)) & 0x3F) as u8);
+        }
+        true
+    }
+
     pub fn max_utf16_buffer_length(&self, byte_length: usize) -> Option<usize> {
         byte_length.checked_add(1 + self.extra_from_state())
     }
@@ -699,7 +723,7 @@ impl Utf8Encoder {
         let mut to_write = src.len();
         if to_write <= dst.len() {
             unsafe {
-                ::std::ptr::copy_nonoverlapping(src.as_ptr(), dst.as_mut_ptr(), to_write);
+                ::core::ptr::copy_nonoverlapping(src.as_ptr(), dst.as_mut_ptr(), to_write);
             }
             return (EncoderResult::InputEmpty, to_write, to_write);
         }
@@ -710,12 +734,526 @@ impl Utf8Encoder {
             to_write -= 1;
         }
         unsafe {
-            ::std::ptr::copy_nonoverlapping(src.as_ptr(), dst.as_mut_ptr(), to_write);
+            ::core::ptr::copy_nonoverlapping(src.as_ptr(), dst.as_mut_ptr(), to_write);
         }
         (EncoderResult::OutputFull, to_write, to_write)
     }
 }
 
+/// The result of looking at the WTF-8 sequence at the start of a slice.
+enum Wtf8Sequence {
+    /// A scalar value or a surrogate and the length of its encoding.
+    Complete(u32, usize),
+    /// A valid prefix of a sequence that the slice ends in the middle of.
+    Incomplete,
+    /// A malformed sequence and the length of its maximal subpart.
+    Malformed(usize),
+}
+
+/// Reads the WTF-8 sequence at the start of `bytes`, which must not be
+/// empty. Unlike in UTF-8, 0xED may be followed by 0xA0 to 0xBF, which
+/// encodes a surrogate.
+fn wtf8_sequence(bytes: &[u8]) -> Wtf8Sequence {
+    let lead = bytes[0];
+    let (length, lower_boundary, upper_boundary) = match lead {
+        0...0x7F => {
+            return Wtf8Sequence::Complete(lead as u32, 1);
+        }
+        0xC2...0xDF => (2, 0x80u8, 0xBFu8),
+        0xE0 => (3, 0xA0u8, 0xBFu8),
+        0xE1...0xEF => (3, 0x80u8, 0xBFu8),
+        0xF0 => (4, 0x90u8, 0xBFu8),
+        0xF1...0xF3 => (4, 0x80u8, 0xBFu8),
+        0xF4 => (4, 0x80u8, 0x8Fu8),
+        _ => {
+            return Wtf8Sequence::Malformed(1);
+        }
+    };
+    let mut code_point = (lead as u32) & (0x7Fu32 >> length);
+    for i in 1..length {
+        if i == bytes.len() {
+            return Wtf8Sequence::Incomplete;
+        }
+        let trail = bytes[i];
+        let (lower, upper) = if i == 1 {
+            (lower_boundary, upper_boundary)
+        } else {
+            (0x80u8, 0xBFu8)
+        };
+        if trail < lower || trail > upper {
+            return Wtf8Sequence::Malformed(i);
+        }
+        code_point = (code_point << 6) | (trail as u32 & 0x3F);
+    }
+    Wtf8Sequence::Complete(code_point, length)
+}
+
+fn wtf8_length(code_point: u32) -> usize {
+    if code_point < 0x80 {
+        1
+    } else if code_point < 0x800 {
+        2
+    } else if code_point < 0x10000 {
+        3
+    } else {
+        4
+    }
+}
+
+/// Writes the WTF-8 encoding of `code_point` (which may be a surrogate) to
+/// the start of `dst` and returns its length.
+fn write_wtf8(code_point: u32, dst: &mut [u8]) -> usize {
+    let length = wtf8_length(code_point);
+    match length {
+        1 => {
+            dst[0] = code_point as u8;
+        }
+        2 => {
+            dst[0] = (0xC0 | (code_point >> 6)) as u8;
+            dst[1] = (0x80 | (code_point & 0x3F)) as u8;
+        }
+        3 => {
+            dst[0] = (0xE0 | (code_point >> 12)) as u8;
+            dst[1] = (0x80 | ((code_point >> 6) & 0x3F)) as u8;
+            dst[2] = (0x80 | (code_point & 0x3F)) as u8;
+        }
+        _ => {
+            dst[0] = (0xF0 | (code_point >> 18)) as u8;
+            dst[1] = (0x80 | ((code_point >> 12) & 0x3F)) as u8;
+            dst[2] = (0x80 | ((code_point >> 6) & 0x3F)) as u8;
+            dst[3] = (0x80 | (code_point & 0x3F)) as u8;
+        }
+    }
+    length
+}
+
+/// Writes `code_point` as one or two UTF-16 code units to the start of
+/// `dst` and returns the number of code units.
+fn write_utf16(code_point: u32, dst: &mut [u16]) -> usize {
+    if code_point < 0x10000 {
+        dst[0] = code_point as u16;
+        return 1;
+    }
+    dst[0] = (0xD7C0 + (code_point >> 10)) as u16;
+    dst[1] = (0xDC00 + (code_point & 0x3FF)) as u16;
+    2
+}
+
+/// A streaming converter from potentially ill-formed UTF-16 to WTF-8.
+///
+/// [WTF-8](https://simonsapin.github.io/wtf-8/) is UTF-8 extended to
+/// represent unpaired surrogates as three-byte sequences, so unlike
+/// converting to UTF-8, converting to WTF-8 is lossless. Well-formed UTF-16
+/// becomes plain UTF-8. A high surrogate at the end of a buffer is held
+/// until the next call, since its low surrogate may be at the start of the
+/// next buffer.
+///
+/// Available to Rust only.
+pub struct Wtf8Encoder {
+    high_surrogate: u16, // 0 if none
+}
+
+impl Wtf8Encoder {
+    /// Instantiates a converter without pending state.
+    pub fn new() -> Wtf8Encoder {
+        Wtf8Encoder { high_surrogate: 0 }
+    }
+
+    /// Checks whether the converter holds a high surrogate from the end of
+    /// the previous buffer.
+    pub fn has_pending_state(&self) -> bool {
+        self.high_surrogate != 0
+    }
+
+    /// Query the worst-case WTF-8 output size given the number of UTF-16
+    /// code units that are passed to the next call.
+    ///
+    /// Returns `None` if the size would overflow `usize`.
+    pub fn max_buffer_length(&self, u16_length: usize) -> Option<usize> {
+        let pending = if self.high_surrogate == 0 { 0 } else { 1 };
+        checked_mul(3, u16_length.checked_add(pending))
+    }
+
+    /// Incrementally converts potentially ill-formed UTF-16 to WTF-8.
+    ///
+    /// Returns `CoderResult::InputEmpty` when `src` has been consumed and
+    /// `CoderResult::OutputFull` when `dst` doesn't have space for the next
+    /// sequence, along with the number of code units read and the number of
+    /// bytes written. The caller must set `last` to `true` for the last
+    /// buffer so that a high surrogate at its end is written out.
+    pub fn encode_from_utf16(&mut self,
+                             src: &[u16],
+                             dst: &mut [u8],
+                             last: bool)
+                             -> (CoderResult, usize, usize) {
+        let mut read = 0;
+        let mut written = 0;
+        if self.high_surrogate != 0 {
+            let high = self.high_surrogate as u32;
+            let (code_point, units) = match src.first() {
+                None => {
+                    if !last {
+                        return (CoderResult::InputEmpty, 0, 0);
+                    }
+                    (high, 0)
+                }
+                Some(&unit) if (unit & 0xFC00) == 0xDC00 => {
+                    ((high << 10) + (unit as u32) - (((0xD800u32 << 10) - 0x10000u32) + 0xDC00u32),
+                     1)
+                }
+                Some(_) => (high, 0),
+            };
+            if wtf8_length(code_point) > dst.len() {
+                return (CoderResult::OutputFull, 0, 0);
+            }
+            written += write_wtf8(code_point, dst);
+            read += units;
+            self.high_surrogate = 0;
+        }
+        loop {
+            let non_ascii = {
+                let src_remaining = &src[read..];
+                let dst_remaining = &mut dst[written..];
+                let length = ::core::cmp::min(src_remaining.len(), dst_remaining.len());
+                match unsafe {
+                          basic_latin_to_ascii(src_remaining.as_ptr(),
+                                               dst_remaining.as_mut_ptr(),
+                                               length)
+                      } {
+                    None => {
+                        read += length;
+                        written += length;
+                        if read == src.len() {
+                            return (CoderResult::InputEmpty, read, written);
+                        }
+                        return (CoderResult::OutputFull, read, written);
+                    }
+                    Some((non_ascii, consumed)) => {
+                        read += consumed;
+                        written += consumed;
+                        non_ascii
+                    }
+                }
+            };
+            let unit = non_ascii as u32;
+            let (code_point, units) = if (unit & 0xFC00) != 0xD800 {
+                (unit, 1)
+            } else if read + 1 == src.len() {
+                if !last {
+                    self.high_surrogate = non_ascii;
+                    return (CoderResult::InputEmpty, read + 1, written);
+                }
+                (unit, 1)
+            } else {
+                let next = src[read + 1] as u32;
+                if (next & 0xFC00) == 0xDC00 {
+                    ((unit << 10) + next - (((0xD800u32 << 10) - 0x10000u32) + 0xDC00u32), 2)
+                } else {
+                    (unit, 1)
+                }
+            };
+            if written + wtf8_length(code_point) > dst.len() {
+                return (CoderResult::OutputFull, read, written);
+            }
+            written += write_wtf8(code_point, &mut dst[written..]);
+            read += units;
+        }
+    }
+}
+
+impl Default for Wtf8Encoder {
+    fn default() -> Wtf8Encoder {
+        Wtf8Encoder::new()
+    }
+}
+
+/// A streaming converter from WTF-8 to potentially ill-formed UTF-16.
+///
+/// This is the inverse of `Wtf8Encoder`: surrogates encoded as three-byte
+/// sequences become unpaired surrogates in the output. Malformed sequences
+/// are replaced with the REPLACEMENT CHARACTER like in the UTF-8 decoder.
+/// An encoded high surrogate followed by an encoded low surrogate (which
+/// WTF-8 doesn't allow) is converted to a surrogate pair.
+///
+/// Available to Rust only.
+pub struct Wtf8Decoder {
+    pending: [u8; 3],
+    pending_length: usize,
+}
+
+impl Wtf8Decoder {
+    /// Instantiates a converter without pending state.
+    pub fn new() -> Wtf8Decoder {
+        Wtf8Decoder {
+            pending: [0u8; 3],
+            pending_length: 0,
+        }
+    }
+
+    /// Checks whether the converter holds an incomplete sequence from the
+    /// end of the previous buffer.
+    pub fn has_pending_state(&self) -> bool {
+        self.pending_length != 0
+    }
+
+    /// Query the worst-case UTF-16 output size given the number of bytes
+    /// that are passed to the next call.
+    ///
+    /// Returns `None` if the size would overflow `usize`.
+    pub fn max_utf16_buffer_length(&self, byte_length: usize) -> Option<usize> {
+        byte_length.checked_add(self.pending_length)
+    }
+
+    /// Incrementally converts WTF-8 to potentially ill-formed UTF-16 with
+    /// malformed sequences replaced with the REPLACEMENT CHARACTER.
+    ///
+    /// Returns `CoderResult::InputEmpty` when `src` has been consumed and
+    /// `CoderResult::OutputFull` when `dst` doesn't have space for the next
+    /// character, along with the number of bytes read, the number of code
+    /// units written and whether there were replacements. The caller must
+    /// set `last` to `true` for the last buffer so that an incomplete
+    /// sequence at its end is replaced.
+    pub fn decode_to_utf16(&mut self,
+                           src: &[u8],
+                           dst: &mut [u16],
+                           last: bool)
+                           -> (CoderResult, usize, usize, bool) {
+        let mut read = 0;
+        let mut written = 0;
+        let mut had_replacements = false;
+        if self.pending_length != 0 {
+            let pending_length = self.pending_length;
+            let mut sequence = [0u8; 4];
+            sequence[..pending_length].copy_from_slice(&self.pending[..pending_length]);
+            let taken = ::core::cmp::min(4 - pending_length, src.len());
+            sequence[pending_length..pending_length + taken].copy_from_slice(&src[..taken]);
+            match wtf8_sequence(&sequence[..pending_length + taken]) {
+                Wtf8Sequence::Complete(code_point, length) => {
+                    if (if code_point < 0x10000 { 1 } else { 2 }) > dst.len() {
+                        return (CoderResult::OutputFull, 0, 0, false);
+                    }
+                    written += write_utf16(code_point, dst);
+                    read += length - pending_length;
+                }
+                Wtf8Sequence::Malformed(length) => {
+                    if dst.is_empty() {
+                        return (CoderResult::OutputFull, 0, 0, false);
+                    }
+                    dst[0] = 0xFFFD;
+                    written += 1;
+                    read += length - pending_length;
+                    had_replacements = true;
+                }
+                Wtf8Sequence::Incomplete => {
+                    // `src` is too short to complete the sequence.
+                    if !last {
+                        self.pending[pending_length..pending_length + taken]
+                            .copy_from_slice(src);
+                        self.pending_length += taken;
+                        return (CoderResult::InputEmpty, taken, 0, false);
+                    }
+                    if dst.is_empty() {
+                        return (CoderResult::OutputFull, 0, 0, false);
+                    }
+                    dst[0] = 0xFFFD;
+                    self.pending_length = 0;
+                    return (CoderResult::InputEmpty, taken, 1, true);
+                }
+            }
+            self.pending_length = 0;
+        }
+        loop {
+            let (utf8_read, utf8_written) =
+                convert_utf8_to_utf16_up_to_invalid(&src[read..], &mut dst[written..]);
+            read += utf8_read;
+            written += utf8_written;
+            if read == src.len() {
+                return (CoderResult::InputEmpty, read, written, had_replacements);
+            }
+            // A surrogate, a malformed or incomplete sequence or a character
+            // that didn't fit.
+            match wtf8_sequence(&src[read..]) {
+                Wtf8Sequence::Complete(code_point, length) => {
+                    if written + (if code_point < 0x10000 { 1 } else { 2 }) > dst.len() {
+                        return (CoderResult::OutputFull, read, written, had_replacements);
+                    }
+                    written += write_utf16(code_point, &mut dst[written..]);
+                    read += length;
+                }
+                Wtf8Sequence::Malformed(length) => {
+                    if written == dst.len() {
+                        return (CoderResult::OutputFull, read, written, had_replacements);
+                    }
+                    dst[written] = 0xFFFD;
+                    written += 1;
+                    read += length;
+                    had_replacements = true;
+                }
+                Wtf8Sequence::Incomplete => {
+                    let tail = &src[read..];
+                    if !last {
+                        self.pending[..tail.len()].copy_from_slice(tail);
+                        self.pending_length = tail.len();
+                        return (CoderResult::InputEmpty, src.len(), written, had_replacements);
+                    }
+                    if written == dst.len() {
+                        return (CoderResult::OutputFull, read, written, had_replacements);
+                    }
+                    dst[written] = 0xFFFD;
+                    return (CoderResult::InputEmpty, src.len(), written + 1, true);
+                }
+            }
+        }
+    }
+}
+
+impl Default for Wtf8Decoder {
+    fn default() -> Wtf8Decoder {
+        Wtf8Decoder::new()
+    }
+}
+
+/// Converts potentially ill-formed UTF-16 to WTF-8, encoding unpaired
+/// surrogates as three-byte sequences, and returns the number of bytes
+/// written.
+///
+/// The length of the destination buffer must be at least the length of the
+/// source buffer times three.
+///
+/// # Panics
+///
+/// Panics if the destination buffer is shorter than stated above.
+///
+/// Available to Rust only.
+pub fn convert_utf16_to_wtf8(src: &[u16], dst: &mut [u8]) -> usize {
+    assert!(dst.len() >= src.len() * 3,
+            "Destination must not be shorter than the source times three.");
+    let (result, read, written) = Wtf8Encoder::new().encode_from_utf16(src, dst, true);
+    debug_assert_eq!(result, CoderResult::InputEmpty);
+    debug_assert_eq!(read, src.len());
+    written
+}
+
+/// Converts WTF-8 to potentially ill-formed UTF-16, restoring the unpaired
+/// surrogates encoded as three-byte sequences, and returns the number of
+/// code units written.
+///
+/// Malformed sequences are replaced with the REPLACEMENT CHARACTER.
+///
+/// The length of the destination buffer must be at least the length of the
+/// source buffer.
+///
+/// # Panics
+///
+/// Panics if the destination buffer is shorter than stated above.
+///
+/// Available to Rust only.
+pub fn convert_wtf8_to_utf16(src: &[u8], dst: &mut [u16]) -> usize {
+    assert!(dst.len() >= src.len(),
+            "Destination must not be shorter than the source.");
+    let (result, read, written, _) = Wtf8Decoder::new().decode_to_utf16(src, dst, true);
+    debug_assert_eq!(result, CoderResult::InputEmpty);
+    debug_assert_eq!(read, src.len());
+    written
+}
+
+/// A part of a byte buffer as reported by `wtf8_chunks()`.
+///
+/// Available to Rust only.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Wtf8Chunk<'a> {
+    /// A maximal run of strict UTF-8.
+    Utf8(&'a str),
+    /// An unpaired surrogate encoded as a three-byte sequence.
+    Surrogate(u16),
+    /// The rest of the buffer starting from the first byte that isn't valid
+    /// WTF-8. This is always the last chunk.
+    Malformed(&'a [u8]),
+}
+
+/// An iterator over the `Wtf8Chunk`s of a byte buffer.
+///
+/// Available to Rust only.
+pub struct Wtf8Chunks<'a> {
+    bytes: &'a [u8],
+    after_high_surrogate: bool,
+}
+
+impl<'a> Iterator for Wtf8Chunks<'a> {
+    type Item = Wtf8Chunk<'a>;
+
+    fn next(&mut self) -> Option<Wtf8Chunk<'a>> {
+        if self.bytes.is_empty() {
+            return None;
+        }
+        let bytes = self.bytes;
+        // `utf8_valid_up_to()` may stop at the start of a run of non-ASCII
+        // that precedes the error, so continue from where it stopped.
+        let mut valid_up_to = 0;
+        loop {
+            valid_up_to += utf8_valid_up_to(&bytes[valid_up_to..]);
+            if valid_up_to == bytes.len() {
+                break;
+            }
+            match wtf8_sequence(&bytes[valid_up_to..]) {
+                Wtf8Sequence::Complete(code_point, length) if (code_point & 0xFFFFF800) !=
+                                                              0xD800 => {
+                    valid_up_to += length;
+                }
+                _ => {
+                    break;
+                }
+            }
+        }
+        if valid_up_to != 0 {
+            let (head, tail) = bytes.split_at(valid_up_to);
+            self.bytes = tail;
+            self.after_high_surrogate = false;
+            return Some(Wtf8Chunk::Utf8(unsafe { ::core::str::from_utf8_unchecked(head) }));
+        }
+        if let Wtf8Sequence::Complete(code_point, length) = wtf8_sequence(bytes) {
+            // Since the UTF-8 validation failed, this is a surrogate. An
+            // encoded low surrogate must not follow an encoded high
+            // surrogate, because they should have been encoded as a pair.
+            let is_high = code_point < 0xDC00;
+            if is_high || !self.after_high_surrogate {
+                self.bytes = &bytes[length..];
+                self.after_high_surrogate = is_high;
+                return Some(Wtf8Chunk::Surrogate(code_point as u16));
+            }
+        }
+        self.bytes = &[];
+        Some(Wtf8Chunk::Malformed(bytes))
+    }
+}
+
+/// Splits `bytes` into runs of strict UTF-8 and encoded unpaired
+/// surrogates, i.e. validates `bytes` as WTF-8 and reports which parts are
+/// UTF-8.
+///
+/// If `bytes` is not valid WTF-8, the last chunk is `Wtf8Chunk::Malformed`.
+///
+/// Available to Rust only.
+pub fn wtf8_chunks<'a>(bytes: &'a [u8]) -> Wtf8Chunks<'a> {
+    Wtf8Chunks {
+        bytes,
+        after_high_surrogate: false,
+    }
+}
+
+/// Returns the index of the first byte that makes the input not valid
+/// WTF-8 or the length of the input if it is valid WTF-8.
+///
+/// Available to Rust only.
+pub fn wtf8_valid_up_to(bytes: &[u8]) -> usize {
+    for chunk in wtf8_chunks(bytes) {
+        if let Wtf8Chunk::Malformed(rest) = chunk {
+            return bytes.len() - rest.len();
+        }
+    }
+    bytes.len()
+}
+
 // Any copyright to the test code below this comment is dedicated to the
 // Public Domain. http://creativecommons.org/publicdomain/zero/1.0/
 
@@ -1016,4 +1554,95 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_wtf8_round_trip() {
+        let utf16 = [0x61u16, 0xD83Du16, 0xDE00u16, 0xDC00u16, 0xE9u16, 0xD800u16, 0x62u16,
+                     0xD800u16];
+        let wtf8 = b"a\xF0\x9F\x98\x80\xED\xB0\x80\xC3\xA9\xED\xA0\x80b\xED\xA0\x80";
+        let mut bytes = [0u8; 24];
+        let written = convert_utf16_to_wtf8(&utf16[..], &mut bytes[..]);
+        assert_eq!(&bytes[..written], &wtf8[..]);
+        let mut units = [0u16; 24];
+        let written = convert_wtf8_to_utf16(&wtf8[..], &mut units[..]);
+        assert_eq!(&units[..written], &utf16[..]);
+        // Malformed sequences are replaced.
+        let written = convert_wtf8_to_utf16(b"a\xED\xA0\xFFb\xC0\xF0\x9F", &mut units[..]);
+        assert_eq!(&units[..written],
+                   &[0x61u16, 0xFFFDu16, 0xFFFDu16, 0x62u16, 0xFFFDu16, 0xFFFDu16][..]);
+    }
+
+    #[test]
+    fn test_wtf8_streaming() {
+        let utf16 = [0x61u16, 0xD83Du16, 0xDE00u16, 0xD800u16, 0x62u16, 0x3042u16, 0xDBFFu16];
+        let mut expected = [0u8; 21];
+        let expected_length = convert_utf16_to_wtf8(&utf16[..], &mut expected[..]);
+        let expected = &expected[..expected_length];
+        for split in 0..utf16.len() + 1 {
+            let mut encoder = Wtf8Encoder::new();
+            let mut bytes = [0u8; 24];
+            let (result, read, first) = encoder.encode_from_utf16(&utf16[..split], &mut bytes[..], false);
+            assert_eq!(result, CoderResult::InputEmpty);
+            assert_eq!(read, split);
+            let needed = encoder.max_buffer_length(utf16.len() - split).unwrap();
+            let (result, read, second) =
+                encoder.encode_from_utf16(&utf16[split..], &mut bytes[first..first + needed], true);
+            assert_eq!(result, CoderResult::InputEmpty);
+            assert_eq!(read, utf16.len() - split);
+            assert!(!encoder.has_pending_state());
+            assert_eq!(&bytes[..first + second], expected);
+        }
+        for split in 0..expected.len() + 1 {
+            let mut decoder = Wtf8Decoder::new();
+            let mut units = [0u16; 24];
+            let (result, read, first, _) =
+                decoder.decode_to_utf16(&expected[..split], &mut units[..], false);
+            assert_eq!(result, CoderResult::InputEmpty);
+            assert_eq!(read, split);
+            let needed = decoder
+                .max_utf16_buffer_length(expected.len() - split)
+                .unwrap();
+            let (result, read, second, had_replacements) =
+                decoder.decode_to_utf16(&expected[split..], &mut units[first..first + needed], true);
+            assert_eq!(result, CoderResult::InputEmpty);
+            assert_eq!(read, expected.len() - split);
+            assert!(!had_replacements);
+            assert!(!decoder.has_pending_state());
+            assert_eq!(&units[..first + second], &utf16[..]);
+        }
+        // Output full in the middle of a surrogate pair.
+        let mut encoder = Wtf8Encoder::new();
+        let mut bytes = [0u8; 3];
+        assert_eq!(encoder.encode_from_utf16(&utf16[..], &mut bytes[..], true),
+                   (CoderResult::OutputFull, 1, 1));
+        // An incomplete sequence at the end of the last buffer.
+        let mut decoder = Wtf8Decoder::new();
+        let mut units = [0u16; 4];
+        assert_eq!(decoder.decode_to_utf16(b"\xED\xA0", &mut units[..], false),
+                   (CoderResult::InputEmpty, 2, 0, false));
+        assert_eq!(decoder.decode_to_utf16(b"", &mut units[..], true),
+                   (CoderResult::InputEmpty, 0, 1, true));
+        assert_eq!(units[0], 0xFFFD);
+    }
+
+    #[test]
+    fn test_wtf8_chunks() {
+        let chunks: Vec<Wtf8Chunk> = wtf8_chunks(b"ab\xED\xA0\x80\xED\xA0\x81\xC3\xA9\xED\xBF\xBF")
+            .collect();
+        assert_eq!(chunks,
+                   vec![Wtf8Chunk::Utf8("ab"),
+                        Wtf8Chunk::Surrogate(0xD800),
+                        Wtf8Chunk::Surrogate(0xD801),
+                        Wtf8Chunk::Utf8("\u{E9}"),
+                        Wtf8Chunk::Surrogate(0xDFFF)]);
+        // A surrogate pair must be encoded as a four-byte sequence.
+        let chunks: Vec<Wtf8Chunk> = wtf8_chunks(b"\xED\xA0\xBD\xED\xB8\x80a").collect();
+        assert_eq!(chunks,
+                   vec![Wtf8Chunk::Surrogate(0xD83D),
+                        Wtf8Chunk::Malformed(b"\xED\xB8\x80a")]);
+        assert_eq!(wtf8_valid_up_to(b"\xED\xA0\xBD\xED\xB8\x80a"), 3);
+        assert_eq!(wtf8_valid_up_to(b"a\xED\xB8\x80\xED\xA0\xBD"), 7);
+        assert_eq!(wtf8_valid_up_to(b"a\xED\xA0"), 1);
+        assert_eq!(wtf8_valid_up_to(b"a\xFFb"), 1);
+        assert_eq!(wtf8_chunks(b"").next(), None);
+    }
 }
@@ -47,6 +47,42 @@ pub enum VariantDecoder {
 }
 
 impl VariantDecoder {
+    pub fn has_pending_state(&self) -> bool {
+        match *self {
+            VariantDecoder::Utf8(ref v) => v.has_pending_state(),
+            VariantDecoder::Gb18030(ref v) => v.has_pending_state(),
+            VariantDecoder::Big5(ref v) => v.has_pending_state(),
+            VariantDecoder::EucJp(ref v) => v.has_pending_state(),
+            VariantDecoder::Iso2022Jp(ref v) => v.has_pending_state(),
+            VariantDecoder::ShiftJis(ref v) => v.has_pending_state(),
+            VariantDecoder::EucKr(ref v) => v.has_pending_state(),
+            VariantDecoder::Utf16(ref v) => v.has_pending_state(),
+            _ => false,
+        }
+    }
+    #[cfg(feature = "alloc")]
+    pub fn snapshot_state(&self, state: &mut Vec<u8>) -> bool {
+        match *self {
+            VariantDecoder::Utf8(ref v) => v.snapshot_state(state),
+            VariantDecoder::Gb18030(ref v) => v.snapshot_state(state),
+            VariantDecoder::Big5(ref v) => v.snapshot_state(state),
+            VariantDecoder::EucJp(ref v) => v.snapshot_state(state),
+            VariantDecoder::Iso2022Jp(ref v) => v.snapshot_state(state),
+            VariantDecoder::ShiftJis(ref v) => v.snapshot_state(state),
+            VariantDecoder::EucKr(ref v) => v.snapshot_state(state),
+            VariantDecoder::Replacement(ref v) => v.snapshot_state(state),
+            VariantDecoder::Utf16(ref v) => v.snapshot_state(state),
+            _ => true,
+        }
+    }
+    #[cfg(feature = "alloc")]
+    pub fn restore_state<'a>(&mut self, state: &'a [u8]) -> Option<&'a [u8]> {
+        match *self {
+            VariantDecoder::Iso2022Jp(ref mut v) => v.restore_state(state),
+            VariantDecoder::Replacement(ref mut v) => v.restore_state(state),
+            _ => Some(state),
+        }
+    }
     pub fn max_utf16_buffer_length(&self, byte_length: usize) -> Option<usize> {
         match *self {
             VariantDecoder::SingleByte(ref v) => v.max_utf16_buffer_length(byte_length),
@@ -177,6 +213,19 @@ impl VariantEncoder {
             _ => false,
         }
     }
+    #[cfg(feature = "alloc")]
+    pub fn snapshot_state(&self, state: &mut Vec<u8>) {
+        if let VariantEncoder::Iso2022Jp(ref v) = *self {
+            v.snapshot_state(state);
+        }
+    }
+    #[cfg(feature = "alloc")]
+    pub fn restore_state(&mut self, state: &[u8]) -> bool {
+        match *self {
+            VariantEncoder::Iso2022Jp(ref mut v) => v.restore_state(state),
+            _ => state.is_empty(),
+        }
+    }
     pub fn max_buffer_length_from_utf16_without_replacement(&self,
                                                             u16_length: usize)
                                                             -> Option<usize> {
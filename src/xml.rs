@@ -0,0 +1,190 @@
+// Copyright 2017 Mozilla Foundation. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! This module implements the autodetection of the encoding of an XML
+//! entity from its first bytes as described in Appendix F of XML 1.0
+//! restricted to the encodings of the Encoding Standard.
+
+use super::*;
+
+fn is_xml_whitespace(byte: u8) -> bool {
+    match byte {
+        0x09u8 | 0x0Au8 | 0x0Du8 | 0x20u8 => true,
+        _ => false,
+    }
+}
+
+/// Returns the value of the `encoding` pseudo-attribute of the XML
+/// declaration that `bytes` starts with. `bytes` must start with `<?xml`.
+fn encoding_pseudo_attribute(bytes: &[u8]) -> Option<&[u8]> {
+    let mut position = 5usize;
+    // `<?xml-stylesheet` and the like are not XML declarations.
+    if !is_xml_whitespace(*bytes.get(position)?) {
+        return None;
+    }
+    loop {
+        while is_xml_whitespace(*bytes.get(position)?) {
+            position += 1;
+        }
+        let name_start = position;
+        loop {
+            let byte = *bytes.get(position)?;
+            if is_xml_whitespace(byte) || byte == b'=' || byte == b'?' || byte == b'>' {
+                break;
+            }
+            position += 1;
+        }
+        let name = &bytes[name_start..position];
+        if name.is_empty() {
+            // `?>` or garbage.
+            return None;
+        }
+        while is_xml_whitespace(*bytes.get(position)?) {
+            position += 1;
+        }
+        if *bytes.get(position)? != b'=' {
+            return None;
+        }
+        position += 1;
+        while is_xml_whitespace(*bytes.get(position)?) {
+            position += 1;
+        }
+        let quote = *bytes.get(position)?;
+        if quote != b'"' && quote != b'\'' {
+            return None;
+        }
+        position += 1;
+        let value_start = position;
+        loop {
+            let byte = *bytes.get(position)?;
+            if byte == quote {
+                break;
+            }
+            if byte == b'<' || byte == b'>' {
+                return None;
+            }
+            position += 1;
+        }
+        if name == b"encoding" {
+            return Some(&bytes[value_start..position]);
+        }
+        position += 1;
+    }
+}
+
+/// Autodetects the encoding of an XML entity according to Appendix F of
+/// XML 1.0.
+///
+/// The argument must be either a buffer representing the entire input
+/// stream (non-streaming case) or a buffer representing at least the start
+/// of the input stream up to and including the XML declaration, if any
+/// (streaming case).
+///
+/// Returns the encoding and the number of bytes of BOM that the caller
+/// should skip before decoding (or, equivalently, the caller may decode from
+/// the start of the buffer with a decoder that handles the BOM). Returns
+/// `None` if the entity carries no in-band encoding information, in which
+/// case XML 1.0 says the encoding is UTF-8 unless a higher-level protocol
+/// says otherwise.
+///
+/// The detection proceeds as follows:
+///
+/// 1. A UTF-8, UTF-16LE or UTF-16BE BOM (as found by `Encoding::for_bom()`)
+///    wins. Any encoding declaration is ignored.
+/// 2. `<?` encoded as UTF-16LE or UTF-16BE without a BOM results in
+///    `UTF_16LE` or `UTF_16BE`, respectively. Any encoding declaration is
+///    ignored.
+/// 3. If the buffer starts with an XML declaration in an ASCII-compatible
+///    encoding, the value of its `encoding` pseudo-attribute is resolved with
+///    `Encoding::for_label()`. If the label is unknown or there is no
+///    `encoding` pseudo-attribute, `None` is returned. A label for UTF-16BE
+///    or UTF-16LE results in `UTF_8`, since a declaration that was readable
+///    as ASCII cannot have been encoded in UTF-16.
+/// 4. Otherwise, `None` is returned.
+///
+/// Labels that the Encoding Standard maps to the replacement encoding (e.g.
+/// `iso-2022-kr` or `hz-gb-2312`) result in `REPLACEMENT`, just like with
+/// `Encoding::for_label()`. Decoding with it yields a single U+FFFD (or an
+/// error with the `*_without_replacement` methods) instead of misinterpreting
+/// content in an encoding that this crate doesn't support. Callers that would
+/// rather treat such labels as unknown should check for `REPLACEMENT`
+/// explicitly.
+///
+/// The UCS-4 and EBCDIC patterns of Appendix F are not recognized, since the
+/// Encoding Standard doesn't support those encodings.
+///
+/// Available to Rust only.
+pub fn xml_sniff(bytes: &[u8]) -> Option<(&'static Encoding, usize)> {
+    if let Some(bom) = Encoding::for_bom(bytes) {
+        return Some(bom);
+    }
+    if bytes.starts_with(b"<\x00?\x00") {
+        return Some((UTF_16LE, 0));
+    }
+    if bytes.starts_with(b"\x00<\x00?") {
+        return Some((UTF_16BE, 0));
+    }
+    if !bytes.starts_with(b"<?xml") {
+        return None;
+    }
+    let encoding = Encoding::for_label(encoding_pseudo_attribute(bytes)?)?;
+    if encoding == UTF_16BE || encoding == UTF_16LE {
+        return Some((UTF_8, 0));
+    }
+    Some((encoding, 0))
+}
+
+// Any copyright to the test code below this comment is dedicated to the
+// Public Domain. http://creativecommons.org/publicdomain/zero/1.0/
+
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+
+    #[test]
+    fn test_xml_sniff_bom() {
+        assert_eq!(xml_sniff(b"\xEF\xBB\xBF<?xml version='1.0' encoding='gbk'?>"),
+                   Some((UTF_8, 3)));
+        assert_eq!(xml_sniff(b"\xFF\xFE<\x00?\x00"), Some((UTF_16LE, 2)));
+        assert_eq!(xml_sniff(b"\xFE\xFF\x00<\x00?"), Some((UTF_16BE, 2)));
+    }
+
+    #[test]
+    fn test_xml_sniff_utf_16_without_bom() {
+        assert_eq!(xml_sniff(b"<\x00?\x00x\x00m\x00l\x00"), Some((UTF_16LE, 0)));
+        assert_eq!(xml_sniff(b"\x00<\x00?\x00x\x00m\x00l"), Some((UTF_16BE, 0)));
+    }
+
+    #[test]
+    fn test_xml_sniff_declaration() {
+        assert_eq!(xml_sniff(b"<?xml version=\"1.0\" encoding=\"Shift_JIS\"?><a/>"),
+                   Some((SHIFT_JIS, 0)));
+        assert_eq!(xml_sniff(b"<?xml version='1.0' encoding='ISO-8859-1' standalone='yes'?>"),
+                   Some((WINDOWS_1252, 0)));
+        assert_eq!(xml_sniff(b"<?xml\r\n\tversion = '1.0'\n encoding = \"koi8-r\" ?>"),
+                   Some((KOI8_R, 0)));
+        assert_eq!(xml_sniff(b"<?xml version='1.0' encoding='UTF-16'?>"),
+                   Some((UTF_8, 0)));
+        assert_eq!(xml_sniff(b"<?xml version='1.0' encoding='iso-2022-kr'?>"),
+                   Some((REPLACEMENT, 0)));
+    }
+
+    #[test]
+    fn test_xml_sniff_none() {
+        assert_eq!(xml_sniff(b""), None);
+        assert_eq!(xml_sniff(b"<a/>"), None);
+        assert_eq!(xml_sniff(b"<?xml version='1.0'?><a/>"), None);
+        assert_eq!(xml_sniff(b"<?xml version='1.0' encoding='bogus'?>"), None);
+        assert_eq!(xml_sniff(b"<?xml-stylesheet encoding='gbk'?>"), None);
+        assert_eq!(xml_sniff(b"<?xml version='1.0' encoding='gbk"), None);
+        assert_eq!(xml_sniff(b"<?xml version='1.0'?><a encoding='gbk'/>"),
+                   None);
+        assert_eq!(xml_sniff(b"\x00\x00\x00<"), None);
+    }
+}